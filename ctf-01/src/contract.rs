@@ -1,26 +1,101 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_binary, BankMsg, Binary, Coin, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Uint128,
+    to_binary, wasm_instantiate, Addr, BankMsg, Binary, Coin, CosmosMsg, Decimal, Deps, DepsMut,
+    Empty, Env, MessageInfo, Order, OverflowError, OverflowOperation, Reply, Response, StdError,
+    StdResult, Storage, SubMsg, Uint128, WasmMsg,
+};
+use ctf_common::ibc::IbcDestination;
+use ctf_common::msg::ProtocolConfigResponse;
+use ctf_common::pause::PauseState;
+use ctf_common::payment::must_pay_exact;
+use ctf_common::penalty::PenaltyDestination;
+use cw721::{OwnerOfResponse, TokensResponse};
+use cw721_base::{
+    ExecuteMsg as Cw721ExecuteMsg, InstantiateMsg as Cw721InstantiateMsg, QueryMsg as Cw721QueryMsg,
 };
 
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
-use crate::state::{Lockup, LAST_ID, LOCKUPS};
-use cw_utils::must_pay;
+use crate::msg::{
+    DepositResult, ExecuteMsg, HistoryResponse, InstantiateMsg, QueryMsg, SimulateWithdrawResponse,
+    SolvencyReportResponse, StatsResponse,
+};
+use crate::state::{
+    accrue_rewards, assert_admin, record_event, remove_lockup, settle_rewards, Config,
+    DepositLimiter, Lockup, ACTIVE_LOCKUPS, ALLOWED_DEPOSITORS, CONFIG, DEPOSIT_LIMITER, HISTORY,
+    LOCKUPS, NEXT_ID, OWNER_LOCKUPS, REWARD_INDEX, TOTAL_LOCKED, UNSETTLED_REWARDS,
+};
+use cw_storage_plus::Bound;
+use cw_utils::parse_reply_instantiate_data;
 
 pub const DENOM: &str = "uawesome";
 pub const MINIMUM_DEPOSIT_AMOUNT: Uint128 = Uint128::new(10_000);
 pub const LOCK_PERIOD: u64 = 60 * 60 * 24;
 
+const INSTANTIATE_RECEIPT_REPLY_ID: u64 = 1;
+
+// settings for pagination
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+/// Lockups withdrawn by a single `WithdrawAll`
+pub const MAX_WITHDRAW_IDS: usize = 30;
+/// Lockups of the sender a single `WithdrawAll` looks at, matured or not
+pub const MAX_WITHDRAW_SCAN: usize = 100;
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
-    _deps: DepsMut,
-    _env: Env,
-    _info: MessageInfo,
-    _msg: InstantiateMsg,
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
-    Ok(Response::new().add_attribute("action", "instantiate"))
+    let config = Config {
+        admin: info.sender,
+        lock_period: msg.lock_period.unwrap_or(LOCK_PERIOD),
+        minimum_deposit: msg.minimum_deposit.unwrap_or(MINIMUM_DEPOSIT_AMOUNT),
+        fee: msg.fee,
+        max_deposits_per_block: msg.max_deposits_per_block.unwrap_or_default(),
+        receipt_contract: None,
+        penalty_destination: msg
+            .penalty_destination
+            .unwrap_or(PenaltyDestination::Contract),
+        pause: PauseState::default(),
+    };
+    config.penalty_destination.validate(deps.api)?;
+    validate_config(&config)?;
+
+    CONFIG.save(deps.storage, &config)?;
+    NEXT_ID.save(deps.storage, &1)?;
+    TOTAL_LOCKED.save(deps.storage, &Uint128::zero())?;
+    ACTIVE_LOCKUPS.save(deps.storage, &0)?;
+    REWARD_INDEX.save(deps.storage, &Decimal::zero())?;
+    UNSETTLED_REWARDS.save(deps.storage, &Uint128::zero())?;
+
+    let mut res = Response::new()
+        .add_attribute("action", "instantiate")
+        .add_attribute("admin", config.admin);
+
+    // receipt collection address is stored on reply
+    if let Some(code_id) = msg.receipt_code_id {
+        let cw721_init_msg = Cw721InstantiateMsg {
+            name: "Lockup Receipt".to_owned(),
+            symbol: "RECEIPT".to_owned(),
+            minter: env.contract.address.to_string(),
+        };
+
+        res = res.add_submessage(SubMsg::reply_on_success(
+            wasm_instantiate(
+                code_id,
+                &cw721_init_msg,
+                vec![],
+                "lockup receipts".to_owned(),
+            )?,
+            INSTANTIATE_RECEIPT_REPLY_ID,
+        ));
+    }
+
+    Ok(res)
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -31,41 +106,172 @@ pub fn execute(
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::Deposit {} => deposit(deps, env, info),
-        ExecuteMsg::Withdraw { ids } => withdraw(deps, env, info, ids),
+        ExecuteMsg::Deposit { min_lockup_amount } => deposit(deps, env, info, min_lockup_amount),
+        ExecuteMsg::Withdraw {
+            ids,
+            ibc_destination,
+        } => withdraw(deps, env, info, ids, ibc_destination),
+        ExecuteMsg::WithdrawAll { ibc_destination } => {
+            withdraw_all(deps, env, info, ibc_destination)
+        }
+        ExecuteMsg::CancelEarly {
+            id,
+            ibc_destination,
+        } => cancel_early(deps, env, info, id, ibc_destination),
+        ExecuteMsg::UpdateConfig {
+            admin,
+            lock_period,
+            minimum_deposit,
+            fee,
+            max_deposits_per_block,
+            penalty_destination,
+        } => update_config(
+            deps,
+            env,
+            info,
+            admin,
+            lock_period,
+            minimum_deposit,
+            fee,
+            max_deposits_per_block,
+            penalty_destination,
+        ),
+        ExecuteMsg::AddDepositor { address } => add_depositor(deps, info, address),
+        ExecuteMsg::RemoveDepositor { address } => remove_depositor(deps, info, address),
+        ExecuteMsg::SetPause {
+            deposits_paused,
+            withdrawals_paused,
+        } => set_pause(deps, info, deposits_paused, withdrawals_paused),
     }
 }
 
 /// Deposit entry point for users
-pub fn deposit(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+pub fn deposit(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    min_lockup_amount: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    config.pause.assert_deposits_open()?;
+
+    // check depositor allowlist
+    if !is_allowed(deps.storage, &info.sender)? {
+        return Err(ContractError::NotAllowed {});
+    }
+
+    // check per-block deposit limit
+    if config.max_deposits_per_block > 0 {
+        let mut limiter = DEPOSIT_LIMITER
+            .may_load(deps.storage, &info.sender)?
+            .filter(|limiter| limiter.block == env.block.height)
+            .unwrap_or(DepositLimiter {
+                block: env.block.height,
+                count: 0,
+            });
+
+        if limiter.count >= config.max_deposits_per_block {
+            return Err(ContractError::RateLimited {});
+        }
+
+        limiter.count += 1;
+        DEPOSIT_LIMITER.save(deps.storage, &info.sender, &limiter)?;
+    }
+
+    if info.funds.iter().all(|coin| coin.amount.is_zero()) {
+        return Err(ContractError::ZeroAmount {});
+    }
+
     // check minimum amount and denom
-    let amount = must_pay(&info, DENOM).unwrap();
+    let amount = must_pay_exact(&info, DENOM)?;
 
-    if amount < MINIMUM_DEPOSIT_AMOUNT {
+    if amount < config.minimum_deposit {
         return Err(ContractError::Unauthorized {});
     }
 
+    // deduct deposit fee
+    let fee = config.fee.map(|fee| amount * fee).unwrap_or_default();
+    let amount = amount.checked_sub(fee)?;
+
+    // protect the depositor against a fee change landing before the deposit
+    if let Some(min_lockup_amount) = min_lockup_amount {
+        if amount < min_lockup_amount {
+            return Err(ContractError::SlippageExceeded {
+                amount,
+                min_lockup_amount,
+            });
+        }
+    }
+
     // increment lock id
-    let id = LAST_ID.load(deps.storage).unwrap_or(1);
-    LAST_ID.save(deps.storage, &(id + 1)).unwrap();
+    let id = NEXT_ID.load(deps.storage)?;
+    let next_id = id.checked_add(1).ok_or(ContractError::IdOverflow {})?;
+    NEXT_ID.save(deps.storage, &next_id)?;
 
     // create lockup
     let lock = Lockup {
         id,
         owner: info.sender,
         amount,
-        release_timestamp: env.block.time.plus_seconds(LOCK_PERIOD),
+        start_timestamp: env.block.time,
+        release_timestamp: env.block.time.plus_seconds(config.lock_period),
+        reward_index: REWARD_INDEX.load(deps.storage)?,
     };
 
     // save lockup
     LOCKUPS.save(deps.storage, id, &lock).unwrap();
+    OWNER_LOCKUPS.save(deps.storage, (&lock.owner, id), &Empty {})?;
+    TOTAL_LOCKED.update(deps.storage, |total| -> StdResult<_> {
+        Ok(total.checked_add(lock.amount)?)
+    })?;
+    ACTIVE_LOCKUPS.update(deps.storage, |count| -> StdResult<_> {
+        count
+            .checked_add(1)
+            .ok_or_else(|| StdError::overflow(OverflowError::new(OverflowOperation::Add, count, 1)))
+    })?;
+    record_event(
+        deps.storage,
+        env.block.height,
+        "deposit",
+        &lock.owner,
+        lock.id,
+        lock.amount,
+    )?;
 
-    Ok(Response::new()
+    let mut res = Response::new()
         .add_attribute("action", "deposit")
         .add_attribute("id", lock.id.to_string())
         .add_attribute("owner", lock.owner)
         .add_attribute("amount", lock.amount)
-        .add_attribute("release_timestamp", lock.release_timestamp.to_string()))
+        .add_attribute("fee", fee)
+        .add_attribute("release_timestamp", lock.release_timestamp.to_string())
+        .set_data(to_binary(&DepositResult { id: lock.id })?);
+
+    // receipt token id equals the lockup id
+    if let Some(receipt_contract) = config.receipt_contract {
+        res = res.add_message(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: receipt_contract.to_string(),
+            msg: to_binary(&Cw721ExecuteMsg::Mint::<Empty, Empty> {
+                token_id: lock.id.to_string(),
+                owner: lock.owner.to_string(),
+                token_uri: None,
+                extension: Empty {},
+            })?,
+            funds: vec![],
+        }));
+    }
+
+    if !fee.is_zero() {
+        res = res.add_message(BankMsg::Send {
+            to_address: config.admin.to_string(),
+            amount: vec![Coin {
+                denom: DENOM.to_string(),
+                amount: fee,
+            }],
+        });
+    }
+
+    Ok(res)
 }
 
 /// Withdrawal entry point for users
@@ -74,52 +280,619 @@ pub fn withdraw(
     env: Env,
     info: MessageInfo,
     ids: Vec<u64>,
+    ibc_destination: Option<IbcDestination>,
 ) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    config.pause.assert_withdrawals_open()?;
     let mut lockups: Vec<Lockup> = vec![];
     let mut total_amount = Uint128::zero();
 
     // fetch vaults to process
     for lockup_id in ids.clone() {
-        let lockup = LOCKUPS.load(deps.storage, lockup_id).unwrap();
+        let mut lockup = LOCKUPS.load(deps.storage, lockup_id).unwrap();
+        settle_rewards(deps.storage, &mut lockup)?;
         lockups.push(lockup);
     }
 
     for lockup in lockups {
         // validate owner and time
-        if lockup.owner != info.sender || env.block.time < lockup.release_timestamp {
+        let owner = lockup_owner(deps.as_ref(), &config, &lockup)?;
+        if owner != info.sender || env.block.time < lockup.release_timestamp {
             return Err(ContractError::Unauthorized {});
         }
 
         // increase total amount
-        total_amount += lockup.amount;
+        total_amount = total_amount.checked_add(lockup.amount)?;
 
         // remove from storage
-        LOCKUPS.remove(deps.storage, lockup.id);
+        remove_lockup(deps.storage, &lockup)?;
+        record_event(
+            deps.storage,
+            env.block.height,
+            "withdraw",
+            &info.sender,
+            lockup.id,
+            lockup.amount,
+        )?;
     }
 
-    let msg = BankMsg::Send {
-        to_address: info.sender.to_string(),
-        amount: vec![Coin {
-            denom: DENOM.to_string(),
-            amount: total_amount,
-        }],
-    };
-
     Ok(Response::new()
         .add_attribute("action", "withdraw")
         .add_attribute("ids", format!("{:?}", ids))
         .add_attribute("total_amount", total_amount)
-        .add_message(msg))
+        .add_message(payout_msg(
+            &env,
+            &info.sender,
+            total_amount,
+            ibc_destination,
+        )?)
+        .add_messages(burn_receipts(&config, &ids)?))
+}
+
+/// Withdraws the matured lockups the sender is entitled to in a single transfer.
+/// Lockups still locked are skipped and don't count towards `MAX_WITHDRAW_IDS`,
+/// but no more than `MAX_WITHDRAW_SCAN` lockups are looked at.
+pub fn withdraw_all(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    ibc_destination: Option<IbcDestination>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    config.pause.assert_withdrawals_open()?;
+
+    let lockups = matured_lockups(deps.as_ref(), &env, &config, &info.sender)?;
+
+    let mut withdrawn: Vec<u64> = vec![];
+    let mut total_amount = Uint128::zero();
+
+    for mut lockup in lockups {
+        settle_rewards(deps.storage, &mut lockup)?;
+
+        total_amount = total_amount.checked_add(lockup.amount)?;
+
+        remove_lockup(deps.storage, &lockup)?;
+        record_event(
+            deps.storage,
+            env.block.height,
+            "withdraw",
+            &info.sender,
+            lockup.id,
+            lockup.amount,
+        )?;
+        withdrawn.push(lockup.id);
+    }
+
+    if withdrawn.is_empty() {
+        return Err(ContractError::NothingToWithdraw {});
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "withdraw_all")
+        .add_attribute("ids", format!("{:?}", withdrawn))
+        .add_attribute("total_amount", total_amount)
+        .add_message(payout_msg(
+            &env,
+            &info.sender,
+            total_amount,
+            ibc_destination,
+        )?)
+        .add_messages(burn_receipts(&config, &withdrawn)?))
+}
+
+/// Entry point for users to exit a lockup before it is released.
+/// The refund vests linearly over the lock period, the remainder is forfeited.
+pub fn cancel_early(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+    ibc_destination: Option<IbcDestination>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    config.pause.assert_withdrawals_open()?;
+    let mut lockup = LOCKUPS.load(deps.storage, id)?;
+
+    if lockup_owner(deps.as_ref(), &config, &lockup)? != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // redistributed funds vest along with the rest of the lockup
+    settle_rewards(deps.storage, &mut lockup)?;
+
+    let refund = lockup.amount * vested_fraction(&lockup, &env);
+    let forfeited = lockup.amount.checked_sub(refund)?;
+
+    remove_lockup(deps.storage, &lockup)?;
+    record_event(
+        deps.storage,
+        env.block.height,
+        "cancel_early",
+        &info.sender,
+        id,
+        refund,
+    )?;
+
+    let mut res = Response::new()
+        .add_attribute("action", "cancel_early")
+        .add_attribute("id", id.to_string())
+        .add_attribute("refund", refund)
+        .add_attribute("forfeited", forfeited)
+        .add_messages(burn_receipts(&config, &[id])?);
+
+    if !refund.is_zero() {
+        res = res.add_message(payout_msg(&env, &info.sender, refund, ibc_destination)?);
+    }
+
+    if !forfeited.is_zero() {
+        match &config.penalty_destination {
+            PenaltyDestination::Contract => {}
+            PenaltyDestination::Treasury { address } => {
+                res = res.add_message(BankMsg::Send {
+                    to_address: address.to_string(),
+                    amount: vec![Coin {
+                        denom: DENOM.to_string(),
+                        amount: forfeited,
+                    }],
+                });
+            }
+            PenaltyDestination::Redistribute => redistribute(deps.storage, forfeited)?,
+        }
+    }
+
+    Ok(res)
+}
+
+/// Adds forfeited funds to the remaining lockups pro rata through the reward index,
+/// each lockup settles its share when it exits. Rounding dust stays in the contract
+/// and in `UNSETTLED_REWARDS`
+fn redistribute(store: &mut dyn Storage, forfeited: Uint128) -> Result<(), ContractError> {
+    let total_locked = TOTAL_LOCKED.load(store)?;
+
+    // nobody left to share with
+    if total_locked.is_zero() {
+        return Ok(());
+    }
+
+    REWARD_INDEX.update(store, |index| -> StdResult<_> {
+        Ok(index.checked_add(Decimal::from_ratio(forfeited, total_locked))?)
+    })?;
+    UNSETTLED_REWARDS.update(store, |unsettled| -> StdResult<_> {
+        Ok(unsettled.checked_add(forfeited)?)
+    })?;
+    Ok(())
+}
+
+/// Returns the address entitled to a lockup, which is the receipt holder when receipts are enabled
+fn lockup_owner(deps: Deps, config: &Config, lockup: &Lockup) -> StdResult<Addr> {
+    match &config.receipt_contract {
+        Some(receipt_contract) => {
+            let res: OwnerOfResponse = deps.querier.query_wasm_smart(
+                receipt_contract.to_string(),
+                &Cw721QueryMsg::OwnerOf::<Empty> {
+                    token_id: lockup.id.to_string(),
+                    include_expired: None,
+                },
+            )?;
+            deps.api.addr_validate(&res.owner)
+        }
+        None => Ok(lockup.owner.clone()),
+    }
+}
+
+/// Returns up to `MAX_WITHDRAW_IDS` matured lockups the owner is entitled to,
+/// looking at no more than `MAX_WITHDRAW_SCAN` of their lockups
+fn matured_lockups(deps: Deps, env: &Env, config: &Config, owner: &Addr) -> StdResult<Vec<Lockup>> {
+    let matured = |id: StdResult<u64>| -> Option<StdResult<Lockup>> {
+        match id.and_then(|id| LOCKUPS.may_load(deps.storage, id)) {
+            Ok(Some(lockup)) if env.block.time >= lockup.release_timestamp => Some(Ok(lockup)),
+            Ok(_) => None,
+            Err(err) => Some(Err(err)),
+        }
+    };
+
+    let receipt_contract = match &config.receipt_contract {
+        Some(receipt_contract) => receipt_contract,
+        None => {
+            return OWNER_LOCKUPS
+                .prefix(owner)
+                .keys(deps.storage, None, None, Order::Ascending)
+                .take(MAX_WITHDRAW_SCAN)
+                .filter_map(matured)
+                .take(MAX_WITHDRAW_IDS)
+                .collect();
+        }
+    };
+
+    // receipts change hands without this contract knowing, so they are listed from the collection
+    let mut ids = vec![];
+    let mut start_after = None;
+    while ids.len() < MAX_WITHDRAW_SCAN {
+        let res: TokensResponse = deps.querier.query_wasm_smart(
+            receipt_contract.to_string(),
+            &Cw721QueryMsg::Tokens::<Empty> {
+                owner: owner.to_string(),
+                start_after,
+                limit: Some((MAX_WITHDRAW_SCAN - ids.len()).min(MAX_LIMIT as usize) as u32),
+            },
+        )?;
+
+        start_after = match res.tokens.last() {
+            Some(last) => Some(last.clone()),
+            None => break,
+        };
+        for token_id in res.tokens {
+            ids.push(
+                token_id
+                    .parse::<u64>()
+                    .map_err(|err| StdError::parse_err("u64", err))?,
+            );
+        }
+    }
+
+    // token ids are listed in string order
+    ids.sort_unstable();
+    ids.into_iter()
+        .map(Ok)
+        .filter_map(matured)
+        .take(MAX_WITHDRAW_IDS)
+        .collect()
+}
+
+/// Pays out to the sender, over ICS20 when a remote destination is given
+fn payout_msg(
+    env: &Env,
+    sender: &Addr,
+    amount: Uint128,
+    ibc_destination: Option<IbcDestination>,
+) -> Result<CosmosMsg, ContractError> {
+    let funds = Coin {
+        denom: DENOM.to_string(),
+        amount,
+    };
+
+    match ibc_destination {
+        Some(destination) => {
+            destination.validate()?;
+            Ok(destination.transfer_msg(env, funds))
+        }
+        None => Ok(BankMsg::Send {
+            to_address: sender.to_string(),
+            amount: vec![funds],
+        }
+        .into()),
+    }
+}
+
+/// Burns the receipts of settled lockups. cw721 only lets the holder or an approved
+/// spender burn, so holders approve this contract before withdrawing
+fn burn_receipts(config: &Config, ids: &[u64]) -> StdResult<Vec<CosmosMsg>> {
+    let receipt_contract = match &config.receipt_contract {
+        Some(receipt_contract) => receipt_contract,
+        None => return Ok(vec![]),
+    };
+
+    ids.iter()
+        .map(|id| {
+            Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: receipt_contract.to_string(),
+                msg: to_binary(&Cw721ExecuteMsg::Burn::<Empty, Empty> {
+                    token_id: id.to_string(),
+                })?,
+                funds: vec![],
+            }))
+        })
+        .collect()
+}
+
+/// Fraction of the lock period that has elapsed, clamped to `[0, 1]`
+fn vested_fraction(lockup: &Lockup, env: &Env) -> Decimal {
+    let duration = lockup
+        .release_timestamp
+        .seconds()
+        .saturating_sub(lockup.start_timestamp.seconds());
+    let elapsed = env
+        .block
+        .time
+        .seconds()
+        .saturating_sub(lockup.start_timestamp.seconds());
+
+    if elapsed >= duration {
+        return Decimal::one();
+    }
+    Decimal::from_ratio(elapsed, duration)
+}
+
+/// Entry point for admin to update the config
+pub fn update_config(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    admin: Option<String>,
+    lock_period: Option<u64>,
+    minimum_deposit: Option<Uint128>,
+    fee: Option<Decimal>,
+    max_deposits_per_block: Option<u32>,
+    penalty_destination: Option<PenaltyDestination>,
+) -> Result<Response, ContractError> {
+    let mut config = assert_admin(deps.storage, &info.sender)?;
+
+    if let Some(admin) = admin {
+        let admin = deps.api.addr_validate(&admin)?;
+        // nobody could act as admin anymore
+        if admin == env.contract.address {
+            return Err(ContractError::InvalidRecipient {});
+        }
+        config.admin = admin;
+    }
+    if let Some(lock_period) = lock_period {
+        config.lock_period = lock_period;
+    }
+    if let Some(minimum_deposit) = minimum_deposit {
+        config.minimum_deposit = minimum_deposit;
+    }
+    // a zero fee clears it
+    if let Some(fee) = fee {
+        config.fee = Some(fee).filter(|fee| !fee.is_zero());
+    }
+    if let Some(max_deposits_per_block) = max_deposits_per_block {
+        config.max_deposits_per_block = max_deposits_per_block;
+    }
+    if let Some(penalty_destination) = penalty_destination {
+        penalty_destination.validate(deps.api)?;
+        config.penalty_destination = penalty_destination;
+    }
+    validate_config(&config)?;
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_config")
+        .add_attribute("admin", config.admin))
+}
+
+/// Entry point for admin to allow an address to deposit
+pub fn add_depositor(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+) -> Result<Response, ContractError> {
+    assert_admin(deps.storage, &info.sender)?;
+
+    let address = deps.api.addr_validate(&address)?;
+    ALLOWED_DEPOSITORS.save(deps.storage, &address, &Empty {})?;
+
+    Ok(Response::new()
+        .add_attribute("action", "add_depositor")
+        .add_attribute("address", address))
+}
+
+/// Entry point for admin to remove an address from the depositor allowlist
+pub fn remove_depositor(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+) -> Result<Response, ContractError> {
+    assert_admin(deps.storage, &info.sender)?;
+
+    let address = deps.api.addr_validate(&address)?;
+    ALLOWED_DEPOSITORS.remove(deps.storage, &address);
+
+    Ok(Response::new()
+        .add_attribute("action", "remove_depositor")
+        .add_attribute("address", address))
+}
+
+/// Entry point for admin to pause deposits and withdrawals
+pub fn set_pause(
+    deps: DepsMut,
+    info: MessageInfo,
+    deposits_paused: bool,
+    withdrawals_paused: bool,
+) -> Result<Response, ContractError> {
+    let mut config = assert_admin(deps.storage, &info.sender)?;
+
+    config.pause = PauseState {
+        deposits_paused,
+        withdrawals_paused,
+    };
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_pause")
+        .add_attribute("deposits_paused", deposits_paused.to_string())
+        .add_attribute("withdrawals_paused", withdrawals_paused.to_string()))
+}
+
+/// Deposits are open to everyone unless the allowlist has entries
+fn is_allowed(store: &dyn Storage, address: &Addr) -> StdResult<bool> {
+    if ALLOWED_DEPOSITORS.has(store, address) {
+        return Ok(true);
+    }
+    let empty = ALLOWED_DEPOSITORS
+        .keys(store, None, None, Order::Ascending)
+        .next()
+        .is_none();
+    Ok(empty)
+}
+
+/// Ensures config values are within sane bounds
+fn validate_config(config: &Config) -> Result<(), ContractError> {
+    if config.minimum_deposit.is_zero() {
+        return Err(ContractError::InvalidConfig {
+            reason: "minimum deposit must be greater than zero".to_string(),
+        });
+    }
+
+    if matches!(config.fee, Some(fee) if fee >= Decimal::one()) {
+        return Err(ContractError::InvalidConfig {
+            reason: "fee must be lower than 100%".to_string(),
+        });
+    }
+
+    Ok(())
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn reply(deps: DepsMut, _env: Env, reply: Reply) -> Result<Response, ContractError> {
+    match reply.id {
+        INSTANTIATE_RECEIPT_REPLY_ID => {
+            let res = parse_reply_instantiate_data(reply)?;
+            let mut config = CONFIG.load(deps.storage)?;
+            config.receipt_contract = Some(deps.api.addr_validate(&res.contract_address)?);
+            CONFIG.save(deps.storage, &config)?;
+            Ok(Response::default())
+        }
+        _ => Ok(Response::default()),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::GetLockup { id } => to_binary(&get_lockup(deps, id)?),
+        QueryMsg::Config {} => to_binary(&get_config(deps)?),
+        QueryMsg::NextId {} => to_binary(&get_next_id(deps)?),
+        QueryMsg::IsAllowed { addr } => to_binary(&get_is_allowed(deps, addr)?),
+        QueryMsg::SolvencyReport {} => to_binary(&get_solvency_report(deps, env)?),
+        QueryMsg::History { start_after, limit } => {
+            to_binary(&get_history(deps, start_after, limit)?)
+        }
+        QueryMsg::ProtocolConfig {} => to_binary(&get_protocol_config(deps)?),
+        QueryMsg::Stats {} => to_binary(&get_stats(deps)?),
+        QueryMsg::SimulateWithdraw { sender, ids } => {
+            to_binary(&simulate_withdraw(deps, env, sender, ids)?)
+        }
     }
 }
 
 /// Returns lockup information for a specified id
 pub fn get_lockup(deps: Deps, id: u64) -> StdResult<Lockup> {
-    Ok(LOCKUPS.load(deps.storage, id).unwrap())
+    let config = CONFIG.load(deps.storage)?;
+    let mut lockup = LOCKUPS.load(deps.storage, id).unwrap();
+    lockup.owner = lockup_owner(deps, &config, &lockup)?;
+    accrue_rewards(&mut lockup, REWARD_INDEX.load(deps.storage)?)?;
+    Ok(lockup)
+}
+
+/// Returns contract configuration
+pub fn get_config(deps: Deps) -> StdResult<Config> {
+    CONFIG.load(deps.storage)
+}
+
+/// Returns the id that will be assigned to the next lockup
+pub fn get_next_id(deps: Deps) -> StdResult<u64> {
+    NEXT_ID.load(deps.storage)
+}
+
+/// Returns the denom and lockup parameters deposits are subject to
+pub fn get_protocol_config(deps: Deps) -> StdResult<ProtocolConfigResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    Ok(ProtocolConfigResponse {
+        denom: DENOM.to_string(),
+        lock_period: Some(config.lock_period),
+        minimum_deposit: Some(config.minimum_deposit),
+    })
+}
+
+/// Returns whether an address is allowed to deposit
+pub fn get_is_allowed(deps: Deps, addr: String) -> StdResult<bool> {
+    let addr = deps.api.addr_validate(&addr)?;
+    is_allowed(deps.storage, &addr)
+}
+
+/// Returns the contract balance compared to the funds held in lockups
+pub fn get_solvency_report(deps: Deps, env: Env) -> StdResult<SolvencyReportResponse> {
+    let accounted_liabilities = TOTAL_LOCKED
+        .load(deps.storage)?
+        .checked_add(UNSETTLED_REWARDS.load(deps.storage)?)?;
+
+    let actual_balance = deps
+        .querier
+        .query_balance(env.contract.address, DENOM)?
+        .amount;
+
+    Ok(SolvencyReportResponse {
+        accounted_liabilities,
+        actual_balance,
+        free: actual_balance.saturating_sub(accounted_liabilities),
+        shortfall: accounted_liabilities.saturating_sub(actual_balance),
+    })
+}
+
+/// Returns lockup counts and the funds locked in a single call
+pub fn get_stats(deps: Deps) -> StdResult<StatsResponse> {
+    let next_id = NEXT_ID.load(deps.storage)?;
+
+    let active_count = ACTIVE_LOCKUPS.load(deps.storage)?;
+    let total_locked = TOTAL_LOCKED
+        .load(deps.storage)?
+        .checked_add(UNSETTLED_REWARDS.load(deps.storage)?)?;
+
+    Ok(StatsResponse {
+        next_id,
+        // ids start at 1 and are never reused
+        total_created: next_id - 1,
+        active_count,
+        total_locked,
+    })
+}
+
+/// Dry-runs `Withdraw` for the sender, it only succeeds when no id is flagged
+pub fn simulate_withdraw(
+    deps: Deps,
+    env: Env,
+    sender: String,
+    ids: Vec<u64>,
+) -> StdResult<SimulateWithdrawResponse> {
+    let sender = deps.api.addr_validate(&sender)?;
+    let config = CONFIG.load(deps.storage)?;
+    let index = REWARD_INDEX.load(deps.storage)?;
+
+    let mut res = SimulateWithdrawResponse {
+        total_amount: Uint128::zero(),
+        not_matured: vec![],
+        not_owned: vec![],
+        not_found: vec![],
+    };
+
+    // ids are processed one by one exactly like the execute path
+    for id in ids {
+        let mut lockup = match LOCKUPS.may_load(deps.storage, id)? {
+            Some(lockup) => lockup,
+            None => {
+                res.not_found.push(id);
+                continue;
+            }
+        };
+
+        if lockup_owner(deps, &config, &lockup)? != sender {
+            res.not_owned.push(id);
+        } else if env.block.time < lockup.release_timestamp {
+            res.not_matured.push(id);
+        } else {
+            accrue_rewards(&mut lockup, index)?;
+            res.total_amount = res.total_amount.checked_add(lockup.amount)?;
+        }
+    }
+
+    Ok(res)
+}
+
+/// Returns lockup events in the order they happened
+pub fn get_history(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<HistoryResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let events = HISTORY
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, event)| event))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(HistoryResponse { events })
 }
@@ -1,4 +1,8 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{OverflowError, StdError, Uint128};
+use ctf_common::ibc::IbcDestinationError;
+use ctf_common::pause::PauseError;
+use ctf_common::payment::PaymentError;
+use cw_utils::ParseReplyError;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -6,6 +10,48 @@ pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
 
+    #[error("{0}")]
+    Payment(#[from] PaymentError),
+
+    #[error("{0}")]
+    Pause(#[from] PauseError),
+
+    #[error("{0}")]
+    IbcDestination(#[from] IbcDestinationError),
+
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
+    #[error("{0}")]
+    ParseReply(#[from] ParseReplyError),
+
     #[error("Unauthorized")]
     Unauthorized {},
+
+    #[error("Zero amount is not allowed")]
+    ZeroAmount {},
+
+    #[error("Address is not allowed to deposit")]
+    NotAllowed {},
+
+    #[error("Deposit limit for this block reached")]
+    RateLimited {},
+
+    #[error("Lockup id overflow")]
+    IdOverflow {},
+
+    #[error("Lockup amount {amount} is below the minimum of {min_lockup_amount}")]
+    SlippageExceeded {
+        amount: Uint128,
+        min_lockup_amount: Uint128,
+    },
+
+    #[error("Invalid config: {reason}")]
+    InvalidConfig { reason: String },
+
+    #[error("The contract cannot be the recipient")]
+    InvalidRecipient {},
+
+    #[error("No matured lockups to withdraw")]
+    NothingToWithdraw {},
 }
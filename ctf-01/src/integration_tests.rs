@@ -1,18 +1,43 @@
 #[cfg(test)]
 pub mod tests {
     use crate::{
-        contract::{DENOM, LOCK_PERIOD, MINIMUM_DEPOSIT_AMOUNT},
-        msg::{ExecuteMsg, InstantiateMsg, QueryMsg},
-        state::Lockup,
+        contract::{
+            DENOM, LOCK_PERIOD, MAX_WITHDRAW_IDS, MAX_WITHDRAW_SCAN, MINIMUM_DEPOSIT_AMOUNT,
+        },
+        error::ContractError,
+        msg::{
+            DepositResult, ExecuteMsg, HistoryResponse, InstantiateMsg, QueryMsg,
+            SimulateWithdrawResponse, SolvencyReportResponse, StatsResponse,
+        },
+        state::{record_event, Config, Lockup, HISTORY_COUNT},
+    };
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{
+        coin, from_binary, to_binary, Addr, Binary, CosmosMsg, Decimal, Deps, DepsMut, Empty, Env,
+        IbcMsg, IbcTimeout, MessageInfo, Reply, Response, StdError, StdResult, SubMsg, Uint128,
+        WasmMsg,
+    };
+    use ctf_common::{
+        ibc::IbcDestination, msg::ProtocolConfigResponse, penalty::PenaltyDestination,
     };
-    use cosmwasm_std::{coin, Addr, Empty, Uint128};
     use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+    use insta::assert_json_snapshot;
 
     pub fn challenge_contract() -> Box<dyn Contract<Empty>> {
         let contract = ContractWrapper::new(
             crate::contract::execute,
             crate::contract::instantiate,
             crate::contract::query,
+        )
+        .with_reply(crate::contract::reply);
+        Box::new(contract)
+    }
+
+    fn cw721_code() -> Box<dyn Contract<Empty>> {
+        let contract = ContractWrapper::new(
+            cw721_base::entry::execute,
+            cw721_base::entry::instantiate,
+            cw721_base::entry::query,
         );
         Box::new(contract)
     }
@@ -25,7 +50,15 @@ pub mod tests {
         let cw_template_id = app.store_code(challenge_contract());
 
         // init contract
-        let msg = InstantiateMsg { count: 1i32 };
+        let msg = InstantiateMsg {
+            count: 1i32,
+            lock_period: None,
+            minimum_deposit: None,
+            fee: None,
+            max_deposits_per_block: None,
+            receipt_code_id: None,
+            penalty_destination: None,
+        };
         let contract_addr = app
             .instantiate_contract(
                 cw_template_id,
@@ -48,7 +81,9 @@ pub mod tests {
         app = mint_tokens(app, USER.to_string(), MINIMUM_DEPOSIT_AMOUNT);
 
         // deposit
-        let msg = ExecuteMsg::Deposit {};
+        let msg = ExecuteMsg::Deposit {
+            min_lockup_amount: None,
+        };
         let sender = Addr::unchecked(USER);
         app.execute_contract(
             sender.clone(),
@@ -97,7 +132,10 @@ pub mod tests {
         });
 
         // test withdraw
-        let msg = ExecuteMsg::Withdraw { ids: vec![1] };
+        let msg = ExecuteMsg::Withdraw {
+            ids: vec![1],
+            ibc_destination: None,
+        };
         app.execute_contract(sender, contract_addr, &msg, &[])
             .unwrap();
 
@@ -119,7 +157,9 @@ pub mod tests {
         app.execute_contract(
             hacker.clone(),
             contract_addr.clone(),
-            &ExecuteMsg::Deposit {},
+            &ExecuteMsg::Deposit {
+                min_lockup_amount: None,
+            },
             &[coin(MINIMUM_DEPOSIT_AMOUNT.u128(), DENOM)],
         )
         .unwrap();
@@ -149,7 +189,10 @@ pub mod tests {
         app.execute_contract(
             hacker.clone(),
             contract_addr.clone(),
-            &ExecuteMsg::Withdraw { ids: vec![2; 12] },
+            &ExecuteMsg::Withdraw {
+                ids: vec![2; 12],
+                ibc_destination: None,
+            },
             &[]
         ).unwrap();
 
@@ -161,5 +204,1635 @@ pub mod tests {
         let contract_balance = app.wrap().query_balance(contract_addr.to_string(), DENOM).unwrap().amount;
         assert_eq!(contract_balance, Uint128::zero());
     }
-    
+
+    #[test]
+    fn update_config() {
+        let (mut app, contract_addr) = proper_instantiate();
+
+        // defaults are taken from the constants
+        let config: Config = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::Config {})
+            .unwrap();
+        assert_eq!(config.admin, Addr::unchecked(ADMIN));
+        assert_eq!(config.lock_period, LOCK_PERIOD);
+        assert_eq!(config.minimum_deposit, MINIMUM_DEPOSIT_AMOUNT);
+        assert_eq!(config.fee, None);
+
+        let msg = ExecuteMsg::UpdateConfig {
+            admin: None,
+            lock_period: Some(LOCK_PERIOD * 2),
+            minimum_deposit: None,
+            fee: Some(Decimal::percent(1)),
+            max_deposits_per_block: None,
+            penalty_destination: None,
+        };
+
+        // only admin can update config
+        app.execute_contract(Addr::unchecked(USER), contract_addr.clone(), &msg, &[])
+            .unwrap_err();
+
+        app.execute_contract(Addr::unchecked(ADMIN), contract_addr.clone(), &msg, &[])
+            .unwrap();
+
+        // fee of 100% is rejected
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            contract_addr.clone(),
+            &ExecuteMsg::UpdateConfig {
+                admin: None,
+                lock_period: None,
+                minimum_deposit: None,
+                fee: Some(Decimal::one()),
+                max_deposits_per_block: None,
+                penalty_destination: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+
+        let config: Config = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::Config {})
+            .unwrap();
+        assert_eq!(config.lock_period, LOCK_PERIOD * 2);
+        assert_eq!(config.fee, Some(Decimal::percent(1)));
+
+        // new deposits use the updated config
+        app = mint_tokens(app, USER.to_string(), MINIMUM_DEPOSIT_AMOUNT);
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::Deposit {
+                min_lockup_amount: None,
+            },
+            &[coin(MINIMUM_DEPOSIT_AMOUNT.u128(), DENOM)],
+        )
+        .unwrap();
+
+        let lockup: Lockup = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::GetLockup { id: 2 })
+            .unwrap();
+        assert_eq!(lockup.amount, Uint128::new(9_900));
+        assert_eq!(
+            lockup.release_timestamp,
+            app.block_info().time.plus_seconds(LOCK_PERIOD * 2)
+        );
+
+        // fee is sent to the admin
+        let balance = app.wrap().query_balance(ADMIN, DENOM).unwrap().amount;
+        assert_eq!(balance, Uint128::new(100));
+
+        // a zero fee clears it
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            contract_addr.clone(),
+            &ExecuteMsg::UpdateConfig {
+                admin: None,
+                lock_period: None,
+                minimum_deposit: None,
+                fee: Some(Decimal::zero()),
+                max_deposits_per_block: None,
+                penalty_destination: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+        let config: Config = app
+            .wrap()
+            .query_wasm_smart(contract_addr, &QueryMsg::Config {})
+            .unwrap();
+        assert_eq!(config.fee, None);
+    }
+
+    #[test]
+    fn lockup_ids_increase() {
+        let (mut app, contract_addr) = proper_instantiate();
+
+        let sender = Addr::unchecked(USER);
+        app = mint_tokens(
+            app,
+            USER.to_string(),
+            MINIMUM_DEPOSIT_AMOUNT * Uint128::new(5),
+        );
+
+        // lockup 1 was created during instantiation
+        let next_id: u64 = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::NextId {})
+            .unwrap();
+        assert_eq!(next_id, 2);
+
+        for expected_id in 2..5 {
+            let res = app
+                .execute_contract(
+                    sender.clone(),
+                    contract_addr.clone(),
+                    &ExecuteMsg::Deposit {
+                        min_lockup_amount: None,
+                    },
+                    &[coin(MINIMUM_DEPOSIT_AMOUNT.u128(), DENOM)],
+                )
+                .unwrap();
+            let wasm = res.events.iter().find(|ev| ev.ty == "wasm").unwrap();
+            let id = wasm
+                .attributes
+                .iter()
+                .find(|attr| attr.key == "id")
+                .unwrap();
+            assert_eq!(id.value, expected_id.to_string());
+        }
+
+        // fast forward 24 hrs
+        app.update_block(|block| {
+            block.time = block.time.plus_seconds(LOCK_PERIOD);
+        });
+
+        // withdrawn ids are never reused
+        app.execute_contract(
+            sender.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::Withdraw {
+                ids: vec![3, 4],
+                ibc_destination: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+        app.execute_contract(
+            sender,
+            contract_addr.clone(),
+            &ExecuteMsg::Deposit {
+                min_lockup_amount: None,
+            },
+            &[coin(MINIMUM_DEPOSIT_AMOUNT.u128(), DENOM)],
+        )
+        .unwrap();
+
+        let lockup: Lockup = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::GetLockup { id: 5 })
+            .unwrap();
+        assert_eq!(lockup.owner, Addr::unchecked(USER));
+
+        let next_id: u64 = app
+            .wrap()
+            .query_wasm_smart(contract_addr, &QueryMsg::NextId {})
+            .unwrap();
+        assert_eq!(next_id, 6);
+    }
+
+    #[test]
+    fn depositor_allowlist() {
+        let (mut app, contract_addr) = proper_instantiate();
+
+        let allowed = "allowed";
+        app = mint_tokens(app, USER.to_string(), MINIMUM_DEPOSIT_AMOUNT);
+        app = mint_tokens(app, allowed.to_string(), MINIMUM_DEPOSIT_AMOUNT);
+
+        // only admin can manage the allowlist
+        let msg = ExecuteMsg::AddDepositor {
+            address: allowed.to_string(),
+        };
+        app.execute_contract(Addr::unchecked(USER), contract_addr.clone(), &msg, &[])
+            .unwrap_err();
+        app.execute_contract(Addr::unchecked(ADMIN), contract_addr.clone(), &msg, &[])
+            .unwrap();
+
+        let is_allowed: bool = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::IsAllowed {
+                    addr: USER.to_string(),
+                },
+            )
+            .unwrap();
+        assert!(!is_allowed);
+
+        // addresses outside the allowlist cannot deposit
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::Deposit {
+                min_lockup_amount: None,
+            },
+            &[coin(MINIMUM_DEPOSIT_AMOUNT.u128(), DENOM)],
+        )
+        .unwrap_err();
+
+        app.execute_contract(
+            Addr::unchecked(allowed),
+            contract_addr.clone(),
+            &ExecuteMsg::Deposit {
+                min_lockup_amount: None,
+            },
+            &[coin(MINIMUM_DEPOSIT_AMOUNT.u128(), DENOM)],
+        )
+        .unwrap();
+
+        // removing the last entry opens deposits again
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            contract_addr.clone(),
+            &ExecuteMsg::RemoveDepositor {
+                address: allowed.to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr,
+            &ExecuteMsg::Deposit {
+                min_lockup_amount: None,
+            },
+            &[coin(MINIMUM_DEPOSIT_AMOUNT.u128(), DENOM)],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn cancel_early_refund() {
+        let (mut app, contract_addr) = proper_instantiate();
+
+        let sender = Addr::unchecked(USER);
+        app = mint_tokens(
+            app,
+            USER.to_string(),
+            MINIMUM_DEPOSIT_AMOUNT * Uint128::new(2),
+        );
+
+        // create lockups 2 and 3 alongside lockup 1
+        for _ in 0..2 {
+            app.execute_contract(
+                sender.clone(),
+                contract_addr.clone(),
+                &ExecuteMsg::Deposit {
+                    min_lockup_amount: None,
+                },
+                &[coin(MINIMUM_DEPOSIT_AMOUNT.u128(), DENOM)],
+            )
+            .unwrap();
+        }
+
+        // only the owner can cancel
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            contract_addr.clone(),
+            &ExecuteMsg::CancelEarly {
+                id: 1,
+                ibc_destination: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+
+        // 0% elapsed forfeits everything
+        app.execute_contract(
+            sender.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::CancelEarly {
+                id: 1,
+                ibc_destination: None,
+            },
+            &[],
+        )
+        .unwrap();
+        let balance = app.wrap().query_balance(USER, DENOM).unwrap().amount;
+        assert_eq!(balance, Uint128::zero());
+
+        // 50% elapsed refunds half
+        app.update_block(|block| {
+            block.time = block.time.plus_seconds(LOCK_PERIOD / 2);
+        });
+        app.execute_contract(
+            sender.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::CancelEarly {
+                id: 2,
+                ibc_destination: None,
+            },
+            &[],
+        )
+        .unwrap();
+        let balance = app.wrap().query_balance(USER, DENOM).unwrap().amount;
+        assert_eq!(balance, Uint128::new(5_000));
+
+        // 100% elapsed refunds everything
+        app.update_block(|block| {
+            block.time = block.time.plus_seconds(LOCK_PERIOD);
+        });
+        app.execute_contract(
+            sender,
+            contract_addr,
+            &ExecuteMsg::CancelEarly {
+                id: 3,
+                ibc_destination: None,
+            },
+            &[],
+        )
+        .unwrap();
+        let balance = app.wrap().query_balance(USER, DENOM).unwrap().amount;
+        assert_eq!(balance, Uint128::new(15_000));
+    }
+
+    #[test]
+    fn deposit_rate_limit() {
+        let (mut app, contract_addr) = proper_instantiate();
+
+        let sender = Addr::unchecked(USER);
+        app = mint_tokens(
+            app,
+            USER.to_string(),
+            MINIMUM_DEPOSIT_AMOUNT * Uint128::new(3),
+        );
+
+        // limit users to a single deposit per block
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            contract_addr.clone(),
+            &ExecuteMsg::UpdateConfig {
+                admin: None,
+                lock_period: None,
+                minimum_deposit: None,
+                fee: None,
+                max_deposits_per_block: Some(1),
+                penalty_destination: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+        app.execute_contract(
+            sender.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::Deposit {
+                min_lockup_amount: None,
+            },
+            &[coin(MINIMUM_DEPOSIT_AMOUNT.u128(), DENOM)],
+        )
+        .unwrap();
+
+        // second deposit in the same block fails
+        app.execute_contract(
+            sender.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::Deposit {
+                min_lockup_amount: None,
+            },
+            &[coin(MINIMUM_DEPOSIT_AMOUNT.u128(), DENOM)],
+        )
+        .unwrap_err();
+
+        // next block is allowed again
+        app.update_block(|block| {
+            block.height += 1;
+        });
+
+        app.execute_contract(
+            sender,
+            contract_addr,
+            &ExecuteMsg::Deposit {
+                min_lockup_amount: None,
+            },
+            &[coin(MINIMUM_DEPOSIT_AMOUNT.u128(), DENOM)],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn zero_deposit_rejected() {
+        let (mut app, contract_addr) = proper_instantiate();
+
+        let next_id: u64 = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::NextId {})
+            .unwrap();
+        assert_eq!(next_id, 2);
+
+        let err = app
+            .execute_contract(
+                Addr::unchecked(USER),
+                contract_addr.clone(),
+                &ExecuteMsg::Deposit {
+                    min_lockup_amount: None,
+                },
+                &[],
+            )
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ContractError>(),
+            Some(ContractError::ZeroAmount {})
+        ));
+
+        // no lockup was created
+        let next_id: u64 = app
+            .wrap()
+            .query_wasm_smart(contract_addr, &QueryMsg::NextId {})
+            .unwrap();
+        assert_eq!(next_id, 2);
+    }
+
+    #[test]
+    fn solvency_report() {
+        let (mut app, contract_addr) = proper_instantiate();
+
+        app = mint_tokens(app, USER.to_owned(), MINIMUM_DEPOSIT_AMOUNT);
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::Deposit {
+                min_lockup_amount: None,
+            },
+            &[coin(MINIMUM_DEPOSIT_AMOUNT.u128(), DENOM)],
+        )
+        .unwrap();
+
+        // donation is not owed to anyone
+        app = mint_tokens(app, contract_addr.to_string(), Uint128::new(500));
+
+        let report: SolvencyReportResponse = app
+            .wrap()
+            .query_wasm_smart(contract_addr, &QueryMsg::SolvencyReport {})
+            .unwrap();
+        assert_eq!(report.accounted_liabilities, MINIMUM_DEPOSIT_AMOUNT);
+        assert_eq!(
+            report.actual_balance,
+            MINIMUM_DEPOSIT_AMOUNT + Uint128::new(500)
+        );
+        assert_eq!(report.free, Uint128::new(500));
+        assert_eq!(report.shortfall, Uint128::zero());
+    }
+
+    #[test]
+    fn lockup_receipts() {
+        let mut app = App::default();
+        let cw_template_id = app.store_code(challenge_contract());
+        let cw721_id = app.store_code(cw721_code());
+
+        let msg = InstantiateMsg {
+            count: 1i32,
+            lock_period: None,
+            minimum_deposit: None,
+            fee: None,
+            max_deposits_per_block: None,
+            receipt_code_id: Some(cw721_id),
+            penalty_destination: None,
+        };
+        let contract_addr = app
+            .instantiate_contract(
+                cw_template_id,
+                Addr::unchecked(ADMIN),
+                &msg,
+                &[],
+                "test",
+                None,
+            )
+            .unwrap();
+
+        let config: Config = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::Config {})
+            .unwrap();
+        let receipt_contract = config.receipt_contract.unwrap();
+
+        app = mint_tokens(app, USER.to_owned(), MINIMUM_DEPOSIT_AMOUNT);
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::Deposit {
+                min_lockup_amount: None,
+            },
+            &[coin(MINIMUM_DEPOSIT_AMOUNT.u128(), DENOM)],
+        )
+        .unwrap();
+
+        // receipt is minted with the lockup id
+        let owner: cw721::OwnerOfResponse = app
+            .wrap()
+            .query_wasm_smart(
+                receipt_contract.clone(),
+                &cw721_base::QueryMsg::OwnerOf::<Empty> {
+                    token_id: "1".to_string(),
+                    include_expired: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(owner.owner, USER);
+
+        // selling the receipt hands over the lockup
+        app.execute_contract(
+            Addr::unchecked(USER),
+            receipt_contract.clone(),
+            &cw721_base::ExecuteMsg::TransferNft::<Empty, Empty> {
+                recipient: "buyer".to_string(),
+                token_id: "1".to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+
+        let lockup: Lockup = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::GetLockup { id: 1 })
+            .unwrap();
+        assert_eq!(lockup.owner, Addr::unchecked("buyer"));
+
+        app.update_block(|block| {
+            block.time = block.time.plus_seconds(LOCK_PERIOD);
+        });
+
+        // original depositor no longer owns the lockup
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::Withdraw {
+                ids: vec![1],
+                ibc_destination: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+
+        // the receipt is burned on withdrawal, which needs the holder's approval
+        let withdraw_msg = ExecuteMsg::Withdraw {
+            ids: vec![1],
+            ibc_destination: None,
+        };
+        app.execute_contract(
+            Addr::unchecked("buyer"),
+            contract_addr.clone(),
+            &withdraw_msg,
+            &[],
+        )
+        .unwrap_err();
+
+        app.execute_contract(
+            Addr::unchecked("buyer"),
+            receipt_contract.clone(),
+            &cw721_base::ExecuteMsg::Approve::<Empty, Empty> {
+                spender: contract_addr.to_string(),
+                token_id: "1".to_string(),
+                expires: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+        // batch withdrawals follow the receipt too
+        let withdraw_all_msg = ExecuteMsg::WithdrawAll {
+            ibc_destination: None,
+        };
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &withdraw_all_msg,
+            &[],
+        )
+        .unwrap_err();
+        app.execute_contract(
+            Addr::unchecked("buyer"),
+            contract_addr,
+            &withdraw_all_msg,
+            &[],
+        )
+        .unwrap();
+
+        let balance = app.wrap().query_balance("buyer", DENOM).unwrap();
+        assert_eq!(balance.amount, MINIMUM_DEPOSIT_AMOUNT);
+
+        app.wrap()
+            .query_wasm_smart::<cw721::OwnerOfResponse>(
+                receipt_contract,
+                &cw721_base::QueryMsg::OwnerOf::<Empty> {
+                    token_id: "1".to_string(),
+                    include_expired: None,
+                },
+            )
+            .unwrap_err();
+    }
+
+    #[test]
+    fn history() {
+        let (mut app, contract_addr) = proper_instantiate();
+
+        app.update_block(|block| {
+            block.time = block.time.plus_seconds(LOCK_PERIOD);
+            block.height += 1;
+        });
+
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::Withdraw {
+                ids: vec![1],
+                ibc_destination: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+        let msg = QueryMsg::History {
+            start_after: None,
+            limit: None,
+        };
+        let res: HistoryResponse = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &msg)
+            .unwrap();
+        assert_eq!(res.events.len(), 2);
+
+        assert_eq!(res.events[0].action, "deposit");
+        assert_eq!(res.events[0].actor, Addr::unchecked(USER));
+        assert_eq!(res.events[0].lockup_id, 1);
+        assert_eq!(res.events[0].amount, MINIMUM_DEPOSIT_AMOUNT);
+
+        assert_eq!(res.events[1].action, "withdraw");
+        assert_eq!(res.events[1].height, res.events[0].height + 1);
+        assert_eq!(res.events[1].amount, MINIMUM_DEPOSIT_AMOUNT);
+
+        // paginate past the first event
+        let msg = QueryMsg::History {
+            start_after: Some(1),
+            limit: Some(1),
+        };
+        let res: HistoryResponse = app.wrap().query_wasm_smart(contract_addr, &msg).unwrap();
+        assert_eq!(res.events.len(), 1);
+        assert_eq!(res.events[0].id, 2);
+    }
+
+    #[test]
+    fn deposit_slippage_bound() {
+        let (mut app, contract_addr) = proper_instantiate();
+
+        // fee change lands in the same block as the deposit
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            contract_addr.clone(),
+            &ExecuteMsg::UpdateConfig {
+                admin: None,
+                lock_period: None,
+                minimum_deposit: None,
+                fee: Some(Decimal::percent(5)),
+                max_deposits_per_block: None,
+                penalty_destination: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+        app = mint_tokens(app, USER.to_owned(), MINIMUM_DEPOSIT_AMOUNT);
+
+        // a 5% fee leaves 9_500, below the depositor's bound
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::Deposit {
+                min_lockup_amount: Some(Uint128::new(9_900)),
+            },
+            &[coin(MINIMUM_DEPOSIT_AMOUNT.u128(), DENOM)],
+        )
+        .unwrap_err();
+
+        // no lockup was created
+        let next_id: u64 = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::NextId {})
+            .unwrap();
+        assert_eq!(next_id, 2);
+
+        // the deposit goes through with a looser bound
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::Deposit {
+                min_lockup_amount: Some(Uint128::new(9_500)),
+            },
+            &[coin(MINIMUM_DEPOSIT_AMOUNT.u128(), DENOM)],
+        )
+        .unwrap();
+
+        let lockup: Lockup = app
+            .wrap()
+            .query_wasm_smart(contract_addr, &QueryMsg::GetLockup { id: 2 })
+            .unwrap();
+        assert_eq!(lockup.amount, Uint128::new(9_500));
+    }
+
+    #[test]
+    fn protocol_config() {
+        let (mut app, contract_addr) = proper_instantiate();
+
+        let config: ProtocolConfigResponse = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::ProtocolConfig {})
+            .unwrap();
+        assert_eq!(config.denom, DENOM);
+        assert_eq!(config.lock_period, Some(LOCK_PERIOD));
+        assert_eq!(config.minimum_deposit, Some(MINIMUM_DEPOSIT_AMOUNT));
+
+        // deposits are accepted in the reported denom
+        app = mint_tokens(app, USER.to_owned(), MINIMUM_DEPOSIT_AMOUNT);
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr,
+            &ExecuteMsg::Deposit {
+                min_lockup_amount: None,
+            },
+            &[coin(MINIMUM_DEPOSIT_AMOUNT.u128(), config.denom)],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn ibc_withdrawal() {
+        // multi-test has no IBC support, so the messages are inspected directly
+        let mut deps = mock_dependencies();
+        let mut env = mock_env();
+
+        let msg = InstantiateMsg {
+            count: 1i32,
+            lock_period: None,
+            minimum_deposit: None,
+            fee: None,
+            max_deposits_per_block: None,
+            receipt_code_id: None,
+            penalty_destination: None,
+        };
+        crate::contract::instantiate(deps.as_mut(), env.clone(), mock_info(ADMIN, &[]), msg)
+            .unwrap();
+
+        let msg = ExecuteMsg::Deposit {
+            min_lockup_amount: None,
+        };
+        let info = mock_info(USER, &[coin(MINIMUM_DEPOSIT_AMOUNT.u128(), DENOM)]);
+        crate::contract::execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        env.block.time = env.block.time.plus_seconds(LOCK_PERIOD);
+
+        // malformed channel is rejected
+        let msg = ExecuteMsg::Withdraw {
+            ids: vec![1],
+            ibc_destination: Some(IbcDestination {
+                channel_id: "transfer".to_string(),
+                recipient: "osmo1remote".to_string(),
+                timeout_seconds: None,
+            }),
+        };
+        crate::contract::execute(deps.as_mut(), env.clone(), mock_info(USER, &[]), msg)
+            .unwrap_err();
+
+        let msg = ExecuteMsg::Withdraw {
+            ids: vec![1],
+            ibc_destination: Some(IbcDestination {
+                channel_id: "channel-0".to_string(),
+                recipient: "osmo1remote".to_string(),
+                timeout_seconds: Some(300),
+            }),
+        };
+        let res = crate::contract::execute(deps.as_mut(), env.clone(), mock_info(USER, &[]), msg)
+            .unwrap();
+
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Ibc(IbcMsg::Transfer {
+                channel_id: "channel-0".to_string(),
+                to_address: "osmo1remote".to_string(),
+                amount: coin(MINIMUM_DEPOSIT_AMOUNT.u128(), DENOM),
+                timeout: IbcTimeout::with_timestamp(env.block.time.plus_seconds(300)),
+            })
+        );
+
+        // early cancellations and batch withdrawals can be sent remotely as well
+        for _ in 0..2 {
+            let msg = ExecuteMsg::Deposit {
+                min_lockup_amount: None,
+            };
+            let info = mock_info(USER, &[coin(MINIMUM_DEPOSIT_AMOUNT.u128(), DENOM)]);
+            crate::contract::execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        }
+        let destination = IbcDestination {
+            channel_id: "channel-0".to_string(),
+            recipient: "osmo1remote".to_string(),
+            timeout_seconds: Some(300),
+        };
+
+        env.block.time = env.block.time.plus_seconds(LOCK_PERIOD / 2);
+        let msg = ExecuteMsg::CancelEarly {
+            id: 3,
+            ibc_destination: Some(destination.clone()),
+        };
+        let res = crate::contract::execute(deps.as_mut(), env.clone(), mock_info(USER, &[]), msg)
+            .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Ibc(IbcMsg::Transfer {
+                channel_id: "channel-0".to_string(),
+                to_address: "osmo1remote".to_string(),
+                amount: coin(MINIMUM_DEPOSIT_AMOUNT.u128() / 2, DENOM),
+                timeout: IbcTimeout::with_timestamp(env.block.time.plus_seconds(300)),
+            })
+        );
+
+        env.block.time = env.block.time.plus_seconds(LOCK_PERIOD / 2);
+        let msg = ExecuteMsg::WithdrawAll {
+            ibc_destination: Some(destination),
+        };
+        let res = crate::contract::execute(deps.as_mut(), env.clone(), mock_info(USER, &[]), msg)
+            .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Ibc(IbcMsg::Transfer {
+                channel_id: "channel-0".to_string(),
+                to_address: "osmo1remote".to_string(),
+                amount: coin(MINIMUM_DEPOSIT_AMOUNT.u128(), DENOM),
+                timeout: IbcTimeout::with_timestamp(env.block.time.plus_seconds(300)),
+            })
+        );
+    }
+
+    #[test]
+    fn query_snapshots() {
+        let (app, contract_addr) = proper_instantiate();
+
+        let lockup: Lockup = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::GetLockup { id: 1 })
+            .unwrap();
+        assert_json_snapshot!("lockup", lockup);
+
+        let config: Config = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::Config {})
+            .unwrap();
+        assert_json_snapshot!("config", config);
+
+        let next_id: u64 = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::NextId {})
+            .unwrap();
+        assert_json_snapshot!("next_id", next_id);
+
+        let msg = QueryMsg::IsAllowed {
+            addr: USER.to_owned(),
+        };
+        let is_allowed: bool = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &msg)
+            .unwrap();
+        assert_json_snapshot!("is_allowed", is_allowed);
+
+        let report: SolvencyReportResponse = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::SolvencyReport {})
+            .unwrap();
+        assert_json_snapshot!("solvency_report", report);
+
+        let msg = QueryMsg::History {
+            start_after: None,
+            limit: None,
+        };
+        let history: HistoryResponse = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &msg)
+            .unwrap();
+        assert_json_snapshot!("history", history);
+
+        let protocol_config: ProtocolConfigResponse = app
+            .wrap()
+            .query_wasm_smart(contract_addr, &QueryMsg::ProtocolConfig {})
+            .unwrap();
+        assert_json_snapshot!("protocol_config", protocol_config);
+    }
+
+    #[test]
+    fn withdraw_overflow_rejected() {
+        let (mut app, contract_addr) = proper_instantiate();
+
+        // large enough for a duplicated id to overflow the withdrawal total
+        let amount = Uint128::MAX / Uint128::new(2) + Uint128::one();
+        app = mint_tokens(app, USER.to_owned(), amount);
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::Deposit {
+                min_lockup_amount: None,
+            },
+            &[coin(amount.u128(), DENOM)],
+        )
+        .unwrap();
+
+        app.update_block(|block| {
+            block.time = block.time.plus_seconds(LOCK_PERIOD);
+        });
+
+        // errors instead of wrapping or panicking
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr,
+            &ExecuteMsg::Withdraw {
+                ids: vec![2, 2],
+                ibc_destination: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+    }
+
+    #[test]
+    fn stats() {
+        let (mut app, contract_addr) = proper_instantiate();
+
+        app = mint_tokens(app, USER.to_owned(), MINIMUM_DEPOSIT_AMOUNT);
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::Deposit {
+                min_lockup_amount: None,
+            },
+            &[coin(MINIMUM_DEPOSIT_AMOUNT.u128(), DENOM)],
+        )
+        .unwrap();
+
+        let stats: StatsResponse = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::Stats {})
+            .unwrap();
+        assert_eq!(stats.next_id, 3);
+        assert_eq!(stats.total_created, 2);
+        assert_eq!(stats.active_count, 2);
+        assert_eq!(stats.total_locked, MINIMUM_DEPOSIT_AMOUNT * Uint128::new(2));
+
+        app.update_block(|block| {
+            block.time = block.time.plus_seconds(LOCK_PERIOD);
+        });
+
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::Withdraw {
+                ids: vec![1],
+                ibc_destination: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+        // withdrawals only reduce the active lockups
+        let stats: StatsResponse = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::Stats {})
+            .unwrap();
+        assert_eq!(stats.next_id, 3);
+        assert_eq!(stats.total_created, 2);
+        assert_eq!(stats.active_count, 1);
+        assert_eq!(stats.total_locked, MINIMUM_DEPOSIT_AMOUNT);
+
+        // and so do cancellations
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::CancelEarly {
+                id: 2,
+                ibc_destination: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+        let stats: StatsResponse = app
+            .wrap()
+            .query_wasm_smart(contract_addr, &QueryMsg::Stats {})
+            .unwrap();
+        assert_eq!(stats.total_created, 2);
+        assert_eq!(stats.active_count, 0);
+        assert_eq!(stats.total_locked, Uint128::zero());
+    }
+
+    #[test]
+    fn contract_cannot_become_admin() {
+        let (mut app, contract_addr) = proper_instantiate();
+
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            contract_addr.clone(),
+            &ExecuteMsg::UpdateConfig {
+                admin: Some(contract_addr.to_string()),
+                lock_period: None,
+                minimum_deposit: None,
+                fee: None,
+                max_deposits_per_block: None,
+                penalty_destination: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+
+        let config: Config = app
+            .wrap()
+            .query_wasm_smart(contract_addr, &QueryMsg::Config {})
+            .unwrap();
+        assert_eq!(config.admin, Addr::unchecked(ADMIN));
+    }
+
+    #[test]
+    fn simulate_withdraw() {
+        let (mut app, contract_addr) = proper_instantiate();
+
+        app = mint_tokens(app, ADMIN.to_owned(), MINIMUM_DEPOSIT_AMOUNT);
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            contract_addr.clone(),
+            &ExecuteMsg::Deposit {
+                min_lockup_amount: None,
+            },
+            &[coin(MINIMUM_DEPOSIT_AMOUNT.u128(), DENOM)],
+        )
+        .unwrap();
+
+        let msg = QueryMsg::SimulateWithdraw {
+            sender: USER.to_owned(),
+            ids: vec![1, 2, 99],
+        };
+        let res: SimulateWithdrawResponse = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &msg)
+            .unwrap();
+        assert_eq!(res.total_amount, Uint128::zero());
+        assert_eq!(res.not_matured, vec![1]);
+        assert_eq!(res.not_owned, vec![2]);
+        assert_eq!(res.not_found, vec![99]);
+
+        app.update_block(|block| {
+            block.time = block.time.plus_seconds(LOCK_PERIOD);
+        });
+
+        let msg = QueryMsg::SimulateWithdraw {
+            sender: USER.to_owned(),
+            ids: vec![1],
+        };
+        let res: SimulateWithdrawResponse = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &msg)
+            .unwrap();
+        assert_eq!(res.total_amount, MINIMUM_DEPOSIT_AMOUNT);
+        assert!(res.not_matured.is_empty() && res.not_owned.is_empty() && res.not_found.is_empty());
+
+        // the simulated payout matches the actual withdrawal
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr,
+            &ExecuteMsg::Withdraw {
+                ids: vec![1],
+                ibc_destination: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+        let balance = app.wrap().query_balance(USER, DENOM).unwrap().amount;
+        assert_eq!(balance, res.total_amount);
+    }
+
+    #[test]
+    fn penalty_destination() {
+        let destinations = [
+            PenaltyDestination::Contract,
+            PenaltyDestination::Treasury {
+                address: Addr::unchecked("treasury"),
+            },
+            PenaltyDestination::Redistribute,
+        ];
+
+        for destination in destinations {
+            let (mut app, contract_addr) = proper_instantiate();
+
+            app.execute_contract(
+                Addr::unchecked(ADMIN),
+                contract_addr.clone(),
+                &ExecuteMsg::UpdateConfig {
+                    admin: None,
+                    lock_period: None,
+                    minimum_deposit: None,
+                    fee: None,
+                    max_deposits_per_block: None,
+                    penalty_destination: Some(destination.clone()),
+                },
+                &[],
+            )
+            .unwrap();
+
+            // lockup 2 stays active
+            app = mint_tokens(app, ADMIN.to_owned(), MINIMUM_DEPOSIT_AMOUNT);
+            app.execute_contract(
+                Addr::unchecked(ADMIN),
+                contract_addr.clone(),
+                &ExecuteMsg::Deposit {
+                    min_lockup_amount: None,
+                },
+                &[coin(MINIMUM_DEPOSIT_AMOUNT.u128(), DENOM)],
+            )
+            .unwrap();
+
+            // 0% elapsed forfeits the whole lockup
+            app.execute_contract(
+                Addr::unchecked(USER),
+                contract_addr.clone(),
+                &ExecuteMsg::CancelEarly {
+                    id: 1,
+                    ibc_destination: None,
+                },
+                &[],
+            )
+            .unwrap();
+
+            let lockup: Lockup = app
+                .wrap()
+                .query_wasm_smart(contract_addr.clone(), &QueryMsg::GetLockup { id: 2 })
+                .unwrap();
+            let contract_balance = app
+                .wrap()
+                .query_balance(contract_addr.to_string(), DENOM)
+                .unwrap()
+                .amount;
+            let treasury_balance = app.wrap().query_balance("treasury", DENOM).unwrap().amount;
+
+            match destination {
+                PenaltyDestination::Contract => {
+                    assert_eq!(lockup.amount, MINIMUM_DEPOSIT_AMOUNT);
+                    assert_eq!(contract_balance, MINIMUM_DEPOSIT_AMOUNT * Uint128::new(12));
+                }
+                PenaltyDestination::Treasury { .. } => {
+                    assert_eq!(lockup.amount, MINIMUM_DEPOSIT_AMOUNT);
+                    assert_eq!(contract_balance, MINIMUM_DEPOSIT_AMOUNT * Uint128::new(11));
+                    assert_eq!(treasury_balance, MINIMUM_DEPOSIT_AMOUNT);
+                }
+                PenaltyDestination::Redistribute => {
+                    assert_eq!(lockup.amount, MINIMUM_DEPOSIT_AMOUNT * Uint128::new(2));
+                    assert_eq!(contract_balance, MINIMUM_DEPOSIT_AMOUNT * Uint128::new(12));
+
+                    // the share is settled into the lockup when it is withdrawn
+                    app.update_block(|block| {
+                        block.time = block.time.plus_seconds(LOCK_PERIOD);
+                    });
+                    app.execute_contract(
+                        Addr::unchecked(ADMIN),
+                        contract_addr.clone(),
+                        &ExecuteMsg::Withdraw {
+                            ids: vec![2],
+                            ibc_destination: None,
+                        },
+                        &[],
+                    )
+                    .unwrap();
+
+                    let balance = app.wrap().query_balance(ADMIN, DENOM).unwrap().amount;
+                    assert_eq!(balance, MINIMUM_DEPOSIT_AMOUNT * Uint128::new(2));
+
+                    let report: SolvencyReportResponse = app
+                        .wrap()
+                        .query_wasm_smart(contract_addr.clone(), &QueryMsg::SolvencyReport {})
+                        .unwrap();
+                    assert_eq!(report.accounted_liabilities, Uint128::zero());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn pause_deposits_only() {
+        let (mut app, contract_addr) = proper_instantiate();
+
+        let pause = ExecuteMsg::SetPause {
+            deposits_paused: true,
+            withdrawals_paused: false,
+        };
+        app.execute_contract(Addr::unchecked(USER), contract_addr.clone(), &pause, &[])
+            .unwrap_err();
+        app.execute_contract(Addr::unchecked(ADMIN), contract_addr.clone(), &pause, &[])
+            .unwrap();
+
+        app = mint_tokens(app, USER.to_string(), MINIMUM_DEPOSIT_AMOUNT);
+        let deposit = ExecuteMsg::Deposit {
+            min_lockup_amount: None,
+        };
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &deposit,
+            &[coin(MINIMUM_DEPOSIT_AMOUNT.u128(), DENOM)],
+        )
+        .unwrap_err();
+
+        // users can still exit
+        app.update_block(|block| {
+            block.time = block.time.plus_seconds(LOCK_PERIOD);
+        });
+        let msg = ExecuteMsg::Withdraw {
+            ids: vec![1],
+            ibc_destination: None,
+        };
+        app.execute_contract(Addr::unchecked(USER), contract_addr.clone(), &msg, &[])
+            .unwrap();
+
+        let balance = app.wrap().query_balance(USER, DENOM).unwrap().amount;
+        assert_eq!(balance, MINIMUM_DEPOSIT_AMOUNT * Uint128::new(2));
+
+        // pausing withdrawals blocks early exits too
+        let pause = ExecuteMsg::SetPause {
+            deposits_paused: false,
+            withdrawals_paused: true,
+        };
+        app.execute_contract(Addr::unchecked(ADMIN), contract_addr.clone(), &pause, &[])
+            .unwrap();
+
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &deposit,
+            &[coin(MINIMUM_DEPOSIT_AMOUNT.u128(), DENOM)],
+        )
+        .unwrap();
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr,
+            &ExecuteMsg::CancelEarly {
+                id: 2,
+                ibc_destination: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+    }
+
+    /// Deposits the sent funds into the vault through a submessage
+    fn depositor_execute(
+        _deps: DepsMut,
+        _env: Env,
+        info: MessageInfo,
+        vault: String,
+    ) -> StdResult<Response> {
+        let msg = WasmMsg::Execute {
+            contract_addr: vault,
+            msg: to_binary(&ExecuteMsg::Deposit {
+                min_lockup_amount: None,
+            })?,
+            funds: info.funds,
+        };
+        Ok(Response::new().add_submessage(SubMsg::reply_on_success(msg, 1)))
+    }
+
+    /// Reads the lockup id from the deposit reply
+    fn depositor_reply(_deps: DepsMut, _env: Env, reply: Reply) -> StdResult<Response> {
+        let res = cw_utils::parse_reply_execute_data(reply)
+            .map_err(|err| StdError::generic_err(err.to_string()))?;
+        let result: DepositResult = from_binary(&res.data.unwrap())?;
+        Ok(Response::new().add_attribute("lockup_id", result.id.to_string()))
+    }
+
+    fn depositor_instantiate(
+        _deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        _msg: Empty,
+    ) -> StdResult<Response> {
+        Ok(Response::new())
+    }
+
+    fn depositor_query(_deps: Deps, _env: Env, _msg: Empty) -> StdResult<Binary> {
+        to_binary(&Empty {})
+    }
+
+    fn depositor_contract() -> Box<dyn Contract<Empty>> {
+        let contract =
+            ContractWrapper::new(depositor_execute, depositor_instantiate, depositor_query)
+                .with_reply(depositor_reply);
+        Box::new(contract)
+    }
+
+    #[test]
+    fn deposit_result_data() {
+        let (mut app, contract_addr) = proper_instantiate();
+
+        app = mint_tokens(
+            app,
+            USER.to_string(),
+            MINIMUM_DEPOSIT_AMOUNT * Uint128::new(2),
+        );
+
+        let res = app
+            .execute_contract(
+                Addr::unchecked(USER),
+                contract_addr.clone(),
+                &ExecuteMsg::Deposit {
+                    min_lockup_amount: None,
+                },
+                &[coin(MINIMUM_DEPOSIT_AMOUNT.u128(), DENOM)],
+            )
+            .unwrap();
+        let result: DepositResult = from_binary(&res.data.unwrap()).unwrap();
+        assert_eq!(result.id, 2);
+
+        // a calling contract gets the id in its reply
+        let depositor_id = app.store_code(depositor_contract());
+        let depositor = app
+            .instantiate_contract(
+                depositor_id,
+                Addr::unchecked(ADMIN),
+                &Empty {},
+                &[],
+                "depositor",
+                None,
+            )
+            .unwrap();
+
+        let res = app
+            .execute_contract(
+                Addr::unchecked(USER),
+                depositor,
+                &contract_addr.to_string(),
+                &[coin(MINIMUM_DEPOSIT_AMOUNT.u128(), DENOM)],
+            )
+            .unwrap();
+        let lockup_id = res
+            .events
+            .iter()
+            .flat_map(|ev| ev.attributes.iter())
+            .find(|attr| attr.key == "lockup_id")
+            .unwrap();
+        assert_eq!(lockup_id.value, "3");
+    }
+
+    #[test]
+    fn withdraw_all_matured() {
+        let (mut app, contract_addr) = proper_instantiate();
+        let deposit = ExecuteMsg::Deposit {
+            min_lockup_amount: None,
+        };
+
+        // second lockup matures half a period later
+        app.update_block(|block| {
+            block.time = block.time.plus_seconds(LOCK_PERIOD / 2);
+        });
+        app = mint_tokens(app, USER.to_string(), MINIMUM_DEPOSIT_AMOUNT);
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &deposit,
+            &[coin(MINIMUM_DEPOSIT_AMOUNT.u128(), DENOM)],
+        )
+        .unwrap();
+
+        // matured lockup of another user is left alone
+        app = mint_tokens(app, "other".to_string(), MINIMUM_DEPOSIT_AMOUNT);
+        app.execute_contract(
+            Addr::unchecked("other"),
+            contract_addr.clone(),
+            &deposit,
+            &[coin(MINIMUM_DEPOSIT_AMOUNT.u128(), DENOM)],
+        )
+        .unwrap();
+
+        app.update_block(|block| {
+            block.time = block.time.plus_seconds(LOCK_PERIOD / 2);
+        });
+
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::WithdrawAll {
+                ibc_destination: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+        let balance = app.wrap().query_balance(USER, DENOM).unwrap().amount;
+        assert_eq!(balance, MINIMUM_DEPOSIT_AMOUNT);
+
+        let lockup: Lockup = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::GetLockup { id: 2 })
+            .unwrap();
+        assert_eq!(lockup.owner, Addr::unchecked(USER));
+
+        // nothing else matured yet
+        let err = app
+            .execute_contract(
+                Addr::unchecked(USER),
+                contract_addr.clone(),
+                &ExecuteMsg::WithdrawAll {
+                    ibc_destination: None,
+                },
+                &[],
+            )
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ContractError>(),
+            Some(ContractError::NothingToWithdraw {})
+        ));
+
+        app.update_block(|block| {
+            block.time = block.time.plus_seconds(LOCK_PERIOD / 2);
+        });
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr,
+            &ExecuteMsg::WithdrawAll {
+                ibc_destination: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+        let balance = app.wrap().query_balance(USER, DENOM).unwrap().amount;
+        assert_eq!(balance, MINIMUM_DEPOSIT_AMOUNT * Uint128::new(2));
+    }
+
+    #[test]
+    fn withdraw_all_skips_locked() {
+        let (mut app, contract_addr) = proper_instantiate();
+
+        let update_lock_period = |lock_period| ExecuteMsg::UpdateConfig {
+            admin: None,
+            lock_period: Some(lock_period),
+            minimum_deposit: None,
+            fee: None,
+            max_deposits_per_block: None,
+            penalty_destination: None,
+        };
+        let deposit = ExecuteMsg::Deposit {
+            min_lockup_amount: None,
+        };
+
+        // a full batch of lockups that stay locked long after the last one
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            contract_addr.clone(),
+            &update_lock_period(10 * LOCK_PERIOD),
+            &[],
+        )
+        .unwrap();
+        for _ in 0..MAX_WITHDRAW_IDS {
+            app = mint_tokens(app, USER.to_string(), MINIMUM_DEPOSIT_AMOUNT);
+            app.execute_contract(
+                Addr::unchecked(USER),
+                contract_addr.clone(),
+                &deposit,
+                &[coin(MINIMUM_DEPOSIT_AMOUNT.u128(), DENOM)],
+            )
+            .unwrap();
+        }
+
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            contract_addr.clone(),
+            &update_lock_period(LOCK_PERIOD),
+            &[],
+        )
+        .unwrap();
+        app = mint_tokens(app, USER.to_string(), MINIMUM_DEPOSIT_AMOUNT);
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &deposit,
+            &[coin(MINIMUM_DEPOSIT_AMOUNT.u128(), DENOM)],
+        )
+        .unwrap();
+
+        app.update_block(|block| {
+            block.time = block.time.plus_seconds(LOCK_PERIOD);
+        });
+
+        // the first and the last lockup are withdrawn, the locked ones in between are skipped
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::WithdrawAll {
+                ibc_destination: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+        let balance = app.wrap().query_balance(USER, DENOM).unwrap().amount;
+        assert_eq!(balance, MINIMUM_DEPOSIT_AMOUNT * Uint128::new(2));
+
+        let stats: StatsResponse = app
+            .wrap()
+            .query_wasm_smart(contract_addr, &QueryMsg::Stats {})
+            .unwrap();
+        assert_eq!(stats.active_count, MAX_WITHDRAW_IDS as u64);
+    }
+
+    #[test]
+    fn withdraw_all_scan_is_capped() {
+        let (mut app, contract_addr) = proper_instantiate();
+
+        let update_lock_period = |lock_period| ExecuteMsg::UpdateConfig {
+            admin: None,
+            lock_period: Some(lock_period),
+            minimum_deposit: None,
+            fee: None,
+            max_deposits_per_block: None,
+            penalty_destination: None,
+        };
+        let deposit = ExecuteMsg::Deposit {
+            min_lockup_amount: None,
+        };
+
+        // locked lockups fill the rest of the scan after the first one
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            contract_addr.clone(),
+            &update_lock_period(10 * LOCK_PERIOD),
+            &[],
+        )
+        .unwrap();
+        for _ in 1..MAX_WITHDRAW_SCAN {
+            app = mint_tokens(app, USER.to_string(), MINIMUM_DEPOSIT_AMOUNT);
+            app.execute_contract(
+                Addr::unchecked(USER),
+                contract_addr.clone(),
+                &deposit,
+                &[coin(MINIMUM_DEPOSIT_AMOUNT.u128(), DENOM)],
+            )
+            .unwrap();
+        }
+
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            contract_addr.clone(),
+            &update_lock_period(LOCK_PERIOD),
+            &[],
+        )
+        .unwrap();
+        app = mint_tokens(app, USER.to_string(), MINIMUM_DEPOSIT_AMOUNT);
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &deposit,
+            &[coin(MINIMUM_DEPOSIT_AMOUNT.u128(), DENOM)],
+        )
+        .unwrap();
+
+        app.update_block(|block| {
+            block.time = block.time.plus_seconds(LOCK_PERIOD);
+        });
+
+        // the last lockup is past the scan and left for the next call
+        let withdraw_all = ExecuteMsg::WithdrawAll {
+            ibc_destination: None,
+        };
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &withdraw_all,
+            &[],
+        )
+        .unwrap();
+        let balance = app.wrap().query_balance(USER, DENOM).unwrap().amount;
+        assert_eq!(balance, MINIMUM_DEPOSIT_AMOUNT);
+
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &withdraw_all,
+            &[],
+        )
+        .unwrap();
+        let balance = app.wrap().query_balance(USER, DENOM).unwrap().amount;
+        assert_eq!(balance, MINIMUM_DEPOSIT_AMOUNT * Uint128::new(2));
+
+        let stats: StatsResponse = app
+            .wrap()
+            .query_wasm_smart(contract_addr, &QueryMsg::Stats {})
+            .unwrap();
+        assert_eq!(stats.active_count, (MAX_WITHDRAW_SCAN - 1) as u64);
+    }
+
+    #[test]
+    fn history_count_overflow_rejected() {
+        let mut deps = mock_dependencies();
+        HISTORY_COUNT
+            .save(deps.as_mut().storage, &u64::MAX)
+            .unwrap();
+
+        // errors instead of wrapping around to overwrite the first event
+        record_event(
+            deps.as_mut().storage,
+            1,
+            "deposit",
+            &Addr::unchecked(USER),
+            1,
+            MINIMUM_DEPOSIT_AMOUNT,
+        )
+        .unwrap_err();
+    }
 }
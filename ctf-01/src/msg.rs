@@ -1,16 +1,72 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Decimal, Uint128};
+use ctf_common::ibc::IbcDestination;
+use ctf_common::msg::ProtocolConfigResponse;
+use ctf_common::penalty::PenaltyDestination;
 
-use crate::state::Lockup;
+use crate::state::{Config, Lockup, LockupEvent};
 
 #[cw_serde]
 pub struct InstantiateMsg {
     pub count: i32,
+    /// Defaults to `LOCK_PERIOD`
+    pub lock_period: Option<u64>,
+    /// Defaults to `MINIMUM_DEPOSIT_AMOUNT`
+    pub minimum_deposit: Option<Uint128>,
+    pub fee: Option<Decimal>,
+    /// Defaults to zero, which disables the limit
+    pub max_deposits_per_block: Option<u32>,
+    /// cw721 code used to instantiate the lockup receipt collection
+    pub receipt_code_id: Option<u64>,
+    /// Defaults to keeping forfeited funds in the contract
+    pub penalty_destination: Option<PenaltyDestination>,
 }
 
 #[cw_serde]
 pub enum ExecuteMsg {
-    Deposit {},
-    Withdraw { ids: Vec<u64> },
+    Deposit {
+        /// Reverts when the lockup amount after fees is below this bound
+        min_lockup_amount: Option<Uint128>,
+    },
+    /// Receipts of the withdrawn lockups are burned, so receipt holders have to approve
+    /// this contract for them first
+    Withdraw {
+        ids: Vec<u64>,
+        /// Sends the withdrawal over ICS20 instead of to the sender's local account
+        ibc_destination: Option<IbcDestination>,
+    },
+    /// Withdraws the sender's matured lockups, at most `MAX_WITHDRAW_IDS` of them per call
+    /// found among their first `MAX_WITHDRAW_SCAN` lockups
+    WithdrawAll {
+        /// Sends the withdrawal over ICS20 instead of to the sender's local account
+        ibc_destination: Option<IbcDestination>,
+    },
+    /// Burns the lockup receipt like `Withdraw`
+    CancelEarly {
+        id: u64,
+        /// Sends the refund over ICS20 instead of to the sender's local account
+        ibc_destination: Option<IbcDestination>,
+    },
+    UpdateConfig {
+        admin: Option<String>,
+        lock_period: Option<u64>,
+        minimum_deposit: Option<Uint128>,
+        /// Setting a zero fee removes it
+        fee: Option<Decimal>,
+        max_deposits_per_block: Option<u32>,
+        penalty_destination: Option<PenaltyDestination>,
+    },
+    AddDepositor {
+        address: String,
+    },
+    RemoveDepositor {
+        address: String,
+    },
+    /// Pause deposits and withdrawals independently, admin only
+    SetPause {
+        deposits_paused: bool,
+        withdrawals_paused: bool,
+    },
 }
 
 #[cw_serde]
@@ -18,4 +74,79 @@ pub enum ExecuteMsg {
 pub enum QueryMsg {
     #[returns(Lockup)]
     GetLockup { id: u64 },
+
+    #[returns(Config)]
+    Config {},
+
+    #[returns(u64)]
+    NextId {},
+
+    #[returns(bool)]
+    IsAllowed { addr: String },
+
+    #[returns(SolvencyReportResponse)]
+    SolvencyReport {},
+
+    #[returns(HistoryResponse)]
+    History {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    #[returns(ProtocolConfigResponse)]
+    ProtocolConfig {},
+
+    #[returns(StatsResponse)]
+    Stats {},
+
+    #[returns(SimulateWithdrawResponse)]
+    SimulateWithdraw { sender: String, ids: Vec<u64> },
+}
+
+#[cw_serde]
+pub struct SolvencyReportResponse {
+    /// Funds owed to users according to internal accounting
+    pub accounted_liabilities: Uint128,
+    /// Funds actually held by the contract
+    pub actual_balance: Uint128,
+    /// Surplus over the accounted liabilities
+    pub free: Uint128,
+    /// Liabilities not covered by the contract balance
+    pub shortfall: Uint128,
+}
+
+#[cw_serde]
+pub struct HistoryResponse {
+    pub events: Vec<LockupEvent>,
+}
+
+#[cw_serde]
+pub struct StatsResponse {
+    /// Id assigned to the next lockup
+    pub next_id: u64,
+    /// Lockups ever created, withdrawals do not decrease it
+    pub total_created: u64,
+    /// Lockups still held by the contract
+    pub active_count: u64,
+    /// Funds held in active lockups
+    pub total_locked: Uint128,
+}
+
+#[cw_serde]
+pub struct SimulateWithdrawResponse {
+    /// Amount `Withdraw` pays out for the given ids, flagged ids excluded
+    pub total_amount: Uint128,
+    /// Ids of lockups that are not released yet
+    pub not_matured: Vec<u64>,
+    /// Ids of lockups the sender does not own
+    pub not_owned: Vec<u64>,
+    /// Ids without a lockup
+    pub not_found: Vec<u64>,
+}
+
+/// Set as `Response.data` by `Deposit`, for callers using submessages
+#[cw_serde]
+pub struct DepositResult {
+    /// Id of the created lockup
+    pub id: u64,
 }
@@ -1,7 +1,42 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Timestamp, Uint128};
+use cosmwasm_std::{
+    Addr, Decimal, Empty, OverflowError, OverflowOperation, StdError, StdResult, Storage,
+    Timestamp, Uint128,
+};
+use ctf_common::pause::PauseState;
+use ctf_common::penalty::PenaltyDestination;
 use cw_storage_plus::{Item, Map};
 
+use crate::error::ContractError;
+
+#[cw_serde]
+pub struct Config {
+    /// Admin allowed to update the config
+    pub admin: Addr,
+    /// Seconds a lockup must wait before it can be withdrawn
+    pub lock_period: u64,
+    /// Minimum amount accepted per deposit
+    pub minimum_deposit: Uint128,
+    /// Fee taken from each deposit and sent to the admin
+    pub fee: Option<Decimal>,
+    /// Maximum deposits per user in a single block, disabled when zero
+    pub max_deposits_per_block: u32,
+    /// cw721 collection minting a receipt for each lockup, lockups follow the receipt owner
+    pub receipt_contract: Option<Addr>,
+    /// Where funds forfeited by early cancellations go
+    pub penalty_destination: PenaltyDestination,
+    /// Deposits and withdrawals paused by the admin
+    pub pause: PauseState,
+}
+
+#[cw_serde]
+pub struct DepositLimiter {
+    /// Height of the user's last deposit
+    pub block: u64,
+    /// Deposits made by the user at that height
+    pub count: u32,
+}
+
 #[cw_serde]
 pub struct Lockup {
     /// Unique lockup identifier
@@ -10,9 +45,130 @@ pub struct Lockup {
     pub owner: Addr,
     /// Locked amount
     pub amount: Uint128,
+    /// Timestamp when the lockup was created
+    pub start_timestamp: Timestamp,
     /// Timestamp when the lockup can be withdrawn
     pub release_timestamp: Timestamp,
+    /// `REWARD_INDEX` the lockup last settled its share of redistributed funds at
+    pub reward_index: Decimal,
 }
 
-pub const LAST_ID: Item<u64> = Item::new("lock_id");
+#[cw_serde]
+pub struct LockupEvent {
+    /// Sequential event identifier
+    pub id: u64,
+    /// Block height the event happened at
+    pub height: u64,
+    /// One of `deposit`, `withdraw` or `cancel_early`
+    pub action: String,
+    /// Address that triggered the event
+    pub actor: Addr,
+    /// Lockup the event applies to
+    pub lockup_id: u64,
+    /// Amount locked or released
+    pub amount: Uint128,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+/// Id assigned to the next lockup
+pub const NEXT_ID: Item<u64> = Item::new("lock_id");
 pub const LOCKUPS: Map<u64, Lockup> = Map::new("lockups");
+/// Lockup ids by depositor, kept in sync with `LOCKUPS` for batch withdrawals.
+/// Only used without receipts, the receipt holder is the owner otherwise
+pub const OWNER_LOCKUPS: Map<(&Addr, u64), Empty> = Map::new("owner_lockups");
+/// Funds held in lockups, kept in sync with `LOCKUPS` so reports don't range over them
+pub const TOTAL_LOCKED: Item<Uint128> = Item::new("total_locked");
+/// Number of lockups in `LOCKUPS`, kept in sync alongside `TOTAL_LOCKED`
+pub const ACTIVE_LOCKUPS: Item<u64> = Item::new("active_lockups");
+/// Redistributed funds per unit locked, accrued by lockups until they settle
+pub const REWARD_INDEX: Item<Decimal> = Item::new("reward_index");
+/// Redistributed funds not yet settled into a lockup, owed on top of `TOTAL_LOCKED`
+pub const UNSETTLED_REWARDS: Item<Uint128> = Item::new("unsettled_rewards");
+/// Addresses allowed to deposit, deposits are open to anyone while empty
+pub const ALLOWED_DEPOSITORS: Map<&Addr, Empty> = Map::new("allowed_depositors");
+pub const DEPOSIT_LIMITER: Map<&Addr, DepositLimiter> = Map::new("deposit_limiter");
+/// Append-only log of lockup events
+pub const HISTORY: Map<u64, LockupEvent> = Map::new("history");
+pub const HISTORY_COUNT: Item<u64> = Item::new("history_count");
+
+pub fn assert_admin(store: &dyn Storage, sender: &Addr) -> Result<Config, ContractError> {
+    let config = CONFIG.load(store)?;
+
+    if config.admin != *sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    Ok(config)
+}
+
+/// Removes a lockup along with its owner index entry and releases it from the totals.
+/// Removing a lockup that is already gone leaves the totals untouched
+pub fn remove_lockup(store: &mut dyn Storage, lockup: &Lockup) -> StdResult<()> {
+    if !LOCKUPS.has(store, lockup.id) {
+        return Ok(());
+    }
+
+    LOCKUPS.remove(store, lockup.id);
+    OWNER_LOCKUPS.remove(store, (&lockup.owner, lockup.id));
+    TOTAL_LOCKED.update(store, |total| -> StdResult<_> {
+        Ok(total.checked_sub(lockup.amount)?)
+    })?;
+    ACTIVE_LOCKUPS.update(store, |count| -> StdResult<_> {
+        count
+            .checked_sub(1)
+            .ok_or_else(|| StdError::overflow(OverflowError::new(OverflowOperation::Sub, count, 1)))
+    })?;
+    Ok(())
+}
+
+/// Adds the redistributed funds accrued since the lockup last settled to its amount,
+/// returning them. Only updates the given copy
+pub fn accrue_rewards(lockup: &mut Lockup, index: Decimal) -> StdResult<Uint128> {
+    let reward = lockup.amount * index.checked_sub(lockup.reward_index)?;
+    lockup.amount = lockup.amount.checked_add(reward)?;
+    lockup.reward_index = index;
+    Ok(reward)
+}
+
+/// Settles a lockup's accrued rewards in storage, moving them from `UNSETTLED_REWARDS`
+/// into the lockup and `TOTAL_LOCKED`
+pub fn settle_rewards(store: &mut dyn Storage, lockup: &mut Lockup) -> StdResult<()> {
+    let index = REWARD_INDEX.load(store)?;
+    let reward = accrue_rewards(lockup, index)?;
+
+    if !reward.is_zero() {
+        UNSETTLED_REWARDS.update(store, |unsettled| -> StdResult<_> {
+            Ok(unsettled.checked_sub(reward)?)
+        })?;
+        TOTAL_LOCKED.update(store, |total| -> StdResult<_> {
+            Ok(total.checked_add(reward)?)
+        })?;
+    }
+    LOCKUPS.save(store, lockup.id, lockup)
+}
+
+/// Appends a lockup event to the history log
+pub fn record_event(
+    store: &mut dyn Storage,
+    height: u64,
+    action: &str,
+    actor: &Addr,
+    lockup_id: u64,
+    amount: Uint128,
+) -> StdResult<()> {
+    let count = HISTORY_COUNT.may_load(store)?.unwrap_or_default();
+    let id = count
+        .checked_add(1)
+        .ok_or_else(|| StdError::overflow(OverflowError::new(OverflowOperation::Add, count, 1)))?;
+
+    let event = LockupEvent {
+        id,
+        height,
+        action: action.to_string(),
+        actor: actor.clone(),
+        lockup_id,
+        amount,
+    };
+
+    HISTORY.save(store, id, &event)?;
+    HISTORY_COUNT.save(store, &id)
+}
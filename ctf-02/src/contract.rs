@@ -1,24 +1,103 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    coin, to_binary, BankMsg, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Uint128,
+    coin, to_binary, Addr, Api, BankMsg, Binary, Decimal, Deps, DepsMut, Env, MessageInfo, Order,
+    OverflowError, OverflowOperation, Response, StdResult, Storage, Timestamp, Uint128,
 };
-use cw_utils::must_pay;
+use ctf_common::msg::ProtocolConfigResponse;
+use ctf_common::payment::{must_pay_exact, one_coin};
+use ctf_common::penalty::PenaltyDestination;
+use cw_storage_plus::{Bound, PrefixBound};
+use std::collections::BTreeMap;
 
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
-use crate::state::{UserInfo, VOTING_POWER};
+use crate::msg::{
+    ExecuteMsg, InstantiateMsg, PositionResponse, QueryMsg, StakerRankResponse, UnbondingCursor,
+    UnbondingsResponse,
+};
+use crate::state::{
+    update_stake_index, Config, RewardState, Unbonding, UserInfo, CONFIG, DENOM_STAKES,
+    DENOM_UNBONDINGS, REWARD_STATE, STAKERS_BY_AMOUNT, TOTAL_STAKERS, UNBONDINGS, VOTING_POWER,
+};
 
 pub const DENOM: &str = "uawesome";
 pub const LOCK_PERIOD: u64 = 60 * 60 * 24; // One day
+pub const DEFAULT_FORCE_UNSTAKE_PENALTY_PERCENT: u64 = 10;
+/// Additional denoms that can be staked with a voting weight
+pub const MAX_WEIGHTED_DENOMS: usize = 10;
+/// Unbondings listed in `Position`, later ones are paged through `Unbondings`
+pub const MAX_POSITION_UNBONDINGS: usize = 30;
+/// Unbondings paid out by a single `ClaimUnbonded`, the rest are left for the next call
+pub const MAX_CLAIM_UNBONDINGS: usize = 30;
+/// Ranks reported by `StakerRank`, stakers further down are left unranked
+pub const MAX_RANKED_STAKERS: u64 = 100;
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
-    _deps: DepsMut,
+    deps: DepsMut,
     _env: Env,
     _info: MessageInfo,
-    _msg: InstantiateMsg,
+    msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
+    let denom = msg.denom.unwrap_or_else(|| DENOM.to_string());
+    validate_denom(deps.api, &denom)?;
+
+    let reward_denom = msg.reward_denom.unwrap_or_else(|| denom.clone());
+    validate_denom(deps.api, &reward_denom)?;
+
+    let weights = msg.weights.unwrap_or_default();
+    if weights.len() > MAX_WEIGHTED_DENOMS {
+        return Err(ContractError::TooManyWeights {
+            max: MAX_WEIGHTED_DENOMS,
+        });
+    }
+    for (weighted_denom, weight) in &weights {
+        validate_denom(deps.api, weighted_denom)?;
+        if weighted_denom == &denom || weighted_denom == &reward_denom {
+            return Err(ContractError::InvalidDenom {
+                denom: weighted_denom.clone(),
+            });
+        }
+        if weight.is_zero() {
+            return Err(ContractError::InvalidWeight {
+                denom: weighted_denom.clone(),
+            });
+        }
+    }
+
+    // penalties are in the staking denom, so they can only be redistributed as rewards
+    // when both denoms match
+    let default_destination = if reward_denom == denom {
+        PenaltyDestination::Redistribute
+    } else {
+        PenaltyDestination::Contract
+    };
+
+    let config = Config {
+        denom,
+        reward_denom,
+        unbonding_period: msg.unbonding_period,
+        penalty_destination: msg.penalty_destination.unwrap_or(default_destination),
+        force_unstake_penalty: msg
+            .force_unstake_penalty
+            .unwrap_or_else(|| Decimal::percent(DEFAULT_FORCE_UNSTAKE_PENALTY_PERCENT)),
+        weights,
+    };
+    if config.force_unstake_penalty >= Decimal::one() {
+        return Err(ContractError::InvalidPenalty {});
+    }
+    config.penalty_destination.validate(deps.api)?;
+    if config.penalty_destination == PenaltyDestination::Redistribute
+        && config.reward_denom != config.denom
+    {
+        return Err(ContractError::RewardDenomMismatch {});
+    }
+    CONFIG.save(deps.storage, &config)?;
+    REWARD_STATE.save(deps.storage, &RewardState::default())?;
+
     Ok(Response::new().add_attribute("action", "instantiate"))
 }
 
@@ -31,22 +110,30 @@ pub fn execute(
 ) -> Result<Response, ContractError> {
     match msg {
         ExecuteMsg::Deposit {} => deposit(deps, info),
-        ExecuteMsg::Withdraw { amount } => withdraw(deps, info, amount),
+        ExecuteMsg::Withdraw { amount, recipient } => withdraw(deps, env, info, amount, recipient),
         ExecuteMsg::Stake { lock_amount } => stake(deps, env, info, lock_amount),
         ExecuteMsg::Unstake { unlock_amount } => unstake(deps, env, info, unlock_amount),
+        ExecuteMsg::ForceUnstake {} => force_unstake(deps, env, info),
+        ExecuteMsg::StakeDenom {} => stake_denom(deps, env, info),
+        ExecuteMsg::UnstakeDenom { denom, amount } => unstake_denom(deps, env, info, denom, amount),
+        ExecuteMsg::ClaimRewards {} => claim_rewards(deps, info),
+        ExecuteMsg::Compound {} => compound(deps, env, info),
+        ExecuteMsg::FundRewards {} => fund_rewards(deps, info),
+        ExecuteMsg::ClaimUnbonded {} => claim_unbonded(deps, env, info),
     }
 }
 
 /// Entry point for user to stake tokens
 pub fn deposit(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
     // validate denom
-    let amount = must_pay(&info, DENOM).unwrap();
+    let config = CONFIG.load(deps.storage)?;
+    let amount = must_pay_exact(&info, &config.denom)?;
 
     // increase total stake
     let mut user = VOTING_POWER
         .load(deps.storage, &info.sender)
         .unwrap_or_default();
-    user.total_tokens += amount;
+    user.total_tokens = user.total_tokens.checked_add(amount)?;
 
     VOTING_POWER
         .save(deps.storage, &info.sender, &user)
@@ -61,16 +148,28 @@ pub fn deposit(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractErr
 /// Entry point for users to withdraw staked tokens
 pub fn withdraw(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     amount: Uint128,
+    recipient: Option<String>,
 ) -> Result<Response, ContractError> {
+    let recipient = match recipient {
+        Some(recipient) => deps.api.addr_validate(&recipient)?,
+        None => info.sender.clone(),
+    };
+
+    // the tokens would stay in the contract without being credited to anyone
+    if recipient == env.contract.address {
+        return Err(ContractError::InvalidRecipient {});
+    }
+
     // decrease total stake
     let mut user = VOTING_POWER.load(deps.storage, &info.sender).unwrap();
 
-    user.total_tokens -= amount;
+    user.total_tokens = user.total_tokens.checked_sub(amount)?;
 
-    // cannot withdraw staked tokens
-    if user.total_tokens.u128() < user.voting_power {
+    // cannot withdraw staked or unbonding tokens
+    if user.total_tokens.checked_sub(user.unbonding)?.u128() < user.staked() {
         return Err(ContractError::Unauthorized {});
     }
 
@@ -78,14 +177,16 @@ pub fn withdraw(
         .save(deps.storage, &info.sender, &user)
         .unwrap();
 
+    let config = CONFIG.load(deps.storage)?;
     let msg = BankMsg::Send {
-        to_address: info.sender.to_string(),
-        amount: vec![coin(amount.u128(), DENOM)],
+        to_address: recipient.to_string(),
+        amount: vec![coin(amount.u128(), config.denom)],
     };
 
     Ok(Response::new()
         .add_attribute("action", "withdraw")
         .add_attribute("user", info.sender)
+        .add_attribute("recipient", recipient)
         .add_attribute("amount", amount)
         .add_message(msg))
 }
@@ -97,18 +198,43 @@ pub fn stake(
     info: MessageInfo,
     lock_amount: u128,
 ) -> Result<Response, ContractError> {
-    // increase voting power
+    if lock_amount == 0 {
+        return Err(ContractError::ZeroAmount {});
+    }
+
     let mut user = VOTING_POWER.load(deps.storage, &info.sender).unwrap();
 
-    user.voting_power += lock_amount;
+    // restaking has to wait until pending unbondings are claimed
+    if !user.unbonding.is_zero() {
+        return Err(ContractError::UnbondingInProgress {});
+    }
 
-    // cannot stake more than total tokens
-    if user.voting_power > user.total_tokens.u128() {
-        return Err(ContractError::Unauthorized {});
+    // only tokens neither staked nor unbonding can be staked, checked before any state changes
+    let available = user
+        .total_tokens
+        .saturating_sub(user.unbonding)
+        .u128()
+        .saturating_sub(user.staked());
+    if lock_amount > available {
+        return Err(ContractError::StakeExceedsBalance {
+            available: Uint128::new(available),
+        });
     }
 
+    // increase voting power
+    let mut state = REWARD_STATE.load(deps.storage)?;
+    update_rewards(&mut user, &state)?;
+    let old_power = user.voting_power;
+
+    user.voting_power = checked_add(user.voting_power, lock_amount)?;
+
     user.released_time = env.block.time.plus_seconds(LOCK_PERIOD);
 
+    update_stake_index(deps.storage, &info.sender, old_power, user.voting_power)?;
+
+    state.total_staked = checked_add(state.total_staked, lock_amount)?;
+    REWARD_STATE.save(deps.storage, &state)?;
+
     VOTING_POWER
         .save(deps.storage, &info.sender, &user)
         .unwrap();
@@ -126,6 +252,10 @@ pub fn unstake(
     info: MessageInfo,
     unlock_amount: u128,
 ) -> Result<Response, ContractError> {
+    if unlock_amount == 0 {
+        return Err(ContractError::ZeroAmount {});
+    }
+
     // decrease voting power
     let mut user = VOTING_POWER.load(deps.storage, &info.sender).unwrap();
 
@@ -134,23 +264,544 @@ pub fn unstake(
         return Err(ContractError::Unauthorized {});
     }
 
-    user.voting_power -= unlock_amount;
+    let mut state = REWARD_STATE.load(deps.storage)?;
+    update_rewards(&mut user, &state)?;
+
+    let old_power = user.voting_power;
+
+    // rewards accrued by the unstaked portion are paid out, the rest stays pending
+    let reward_payout = if unlock_amount >= old_power {
+        user.pending_rewards
+    } else {
+        user.pending_rewards
+            .multiply_ratio(unlock_amount, old_power)
+    };
+    user.pending_rewards = user.pending_rewards.checked_sub(reward_payout)?;
+
+    // the challenge build wraps around on underflow
+    #[cfg(not(feature = "fixed"))]
+    {
+        user.voting_power -= unlock_amount;
+        state.total_staked -= unlock_amount;
+    }
+    #[cfg(feature = "fixed")]
+    {
+        // weighted stakes are released through `UnstakeDenom`
+        checked_sub(user.staked(), unlock_amount)?;
+        user.voting_power = checked_sub(user.voting_power, unlock_amount)?;
+        state.total_staked = checked_sub(state.total_staked, unlock_amount)?;
+    }
+
+    update_stake_index(deps.storage, &info.sender, old_power, user.voting_power)?;
+    REWARD_STATE.save(deps.storage, &state)?;
+
+    let mut res = Response::new()
+        .add_attribute("action", "unstake")
+        .add_attribute("unlock_amount", unlock_amount.to_string())
+        .add_attribute("user.voting_power", user.voting_power.to_string())
+        .add_attribute("reward_payout", reward_payout);
+
+    let config = CONFIG.load(deps.storage)?;
+    if !reward_payout.is_zero() {
+        res = res.add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![coin(reward_payout.u128(), &config.reward_denom)],
+        });
+    }
+
+    // unstaked tokens stay locked until the cooldown elapses
+    if let Some(unbonding_period) = config.unbonding_period {
+        let release_time = start_unbonding(
+            deps.storage,
+            &env,
+            unbonding_period,
+            &info.sender,
+            &mut user,
+            Uint128::new(unlock_amount),
+        )?;
+        res = res.add_attribute("release_time", release_time.seconds().to_string());
+    }
 
     VOTING_POWER
         .save(deps.storage, &info.sender, &user)
         .unwrap();
 
+    Ok(res)
+}
+
+/// Entry point for users to withdraw unstaked tokens whose unbonding period has elapsed,
+/// at most `MAX_CLAIM_UNBONDINGS` entries are paid out per call
+pub fn claim_unbonded(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let mut user = VOTING_POWER.load(deps.storage, &info.sender)?;
+
+    let matured = UNBONDINGS
+        .prefix(&info.sender)
+        .range(
+            deps.storage,
+            None,
+            Some(Bound::inclusive(env.block.time.seconds())),
+            Order::Ascending,
+        )
+        .take(MAX_CLAIM_UNBONDINGS)
+        .collect::<StdResult<Vec<_>>>()?;
+    let remaining = MAX_CLAIM_UNBONDINGS - matured.len();
+
+    let mut amount = Uint128::zero();
+    for (release_time, pending) in matured {
+        UNBONDINGS.remove(deps.storage, (&info.sender, release_time));
+        amount = amount.checked_add(pending)?;
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let mut payout: BTreeMap<String, Uint128> = BTreeMap::new();
+    if !amount.is_zero() {
+        user.unbonding = user.unbonding.checked_sub(amount)?;
+        user.total_tokens = user.total_tokens.checked_sub(amount)?;
+        VOTING_POWER.save(deps.storage, &info.sender, &user)?;
+        payout.insert(config.denom, amount);
+    }
+
+    // weighted denoms unbonded through `UnstakeDenom` are paid out alongside
+    let matured_denoms = DENOM_UNBONDINGS
+        .sub_prefix(&info.sender)
+        .range(
+            deps.storage,
+            None,
+            Some(Bound::exclusive((env.block.time.seconds() + 1, ""))),
+            Order::Ascending,
+        )
+        .take(remaining)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for ((release_time, denom), pending) in matured_denoms {
+        DENOM_UNBONDINGS.remove(deps.storage, (&info.sender, release_time, &denom));
+        let total = payout.entry(denom).or_default();
+        *total = total.checked_add(pending)?;
+    }
+
+    if payout.is_empty() {
+        return Err(ContractError::NothingUnbonded {});
+    }
+
+    // coins are sorted by denom
+    let msg = BankMsg::Send {
+        to_address: info.sender.to_string(),
+        amount: payout
+            .into_iter()
+            .map(|(denom, amount)| coin(amount.u128(), denom))
+            .collect(),
+    };
+
     Ok(Response::new()
-        .add_attribute("action", "unstake")
+        .add_attribute("action", "claim_unbonded")
+        .add_attribute("user", info.sender)
+        .add_attribute("amount", amount)
+        .add_message(msg))
+}
+
+/// Queues unstaked tokens for the unbonding period, returning the time they can be claimed
+fn start_unbonding(
+    store: &mut dyn Storage,
+    env: &Env,
+    unbonding_period: u64,
+    user_addr: &Addr,
+    user: &mut UserInfo,
+    amount: Uint128,
+) -> StdResult<Timestamp> {
+    let release_time = env.block.time.plus_seconds(unbonding_period);
+
+    UNBONDINGS.update(
+        store,
+        (user_addr, release_time.seconds()),
+        |pending| -> StdResult<_> { Ok(pending.unwrap_or_default().checked_add(amount)?) },
+    )?;
+    user.unbonding = user.unbonding.checked_add(amount)?;
+
+    Ok(release_time)
+}
+
+/// Entry point for users to unstake everything before the release time.
+/// The penalty is routed to the configured penalty destination and the remainder
+/// goes through the unbonding period like a regular unstake.
+/// Weighted denom stakes are left as they are.
+pub fn force_unstake(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let mut user = VOTING_POWER.load(deps.storage, &info.sender)?;
+
+    let unlock_amount = user.staked();
+    if unlock_amount == 0 {
+        return Err(ContractError::NothingStaked {});
+    }
+
+    let mut state = REWARD_STATE.load(deps.storage)?;
+    update_rewards(&mut user, &state)?;
+
+    let config = CONFIG.load(deps.storage)?;
+    let penalty = Uint128::new(unlock_amount) * config.force_unstake_penalty;
+
+    update_stake_index(
+        deps.storage,
+        &info.sender,
+        user.voting_power,
+        user.weighted_power,
+    )?;
+
+    user.voting_power = user.weighted_power;
+    user.total_tokens = user.total_tokens.checked_sub(penalty)?;
+    state.total_staked = checked_sub(state.total_staked, unlock_amount)?;
+
+    let mut res = Response::new()
+        .add_attribute("action", "force_unstake")
         .add_attribute("unlock_amount", unlock_amount.to_string())
+        .add_attribute("penalty", penalty);
+
+    // skipping the lock doesn't skip the cooldown
+    let amount = Uint128::new(unlock_amount).checked_sub(penalty)?;
+    if let Some(unbonding_period) = config.unbonding_period {
+        if !amount.is_zero() {
+            let release_time = start_unbonding(
+                deps.storage,
+                &env,
+                unbonding_period,
+                &info.sender,
+                &mut user,
+                amount,
+            )?;
+            res = res.add_attribute("release_time", release_time.seconds().to_string());
+        }
+    }
+
+    if !penalty.is_zero() {
+        match &config.penalty_destination {
+            PenaltyDestination::Contract => {}
+            PenaltyDestination::Treasury { address } => {
+                res = res.add_message(BankMsg::Send {
+                    to_address: address.to_string(),
+                    amount: vec![coin(penalty.u128(), &config.denom)],
+                });
+            }
+            // the caller's remaining weighted power doesn't share in their own penalty,
+            // it stays in the contract when nobody else is left to receive it
+            PenaltyDestination::Redistribute if state.total_staked > user.weighted_power => {
+                let others_staked = checked_sub(state.total_staked, user.weighted_power)?;
+                state.global_index = state
+                    .global_index
+                    .checked_add(Decimal::from_ratio(penalty, others_staked))?;
+                user.reward_index = state.global_index;
+            }
+            PenaltyDestination::Redistribute => {}
+        }
+    }
+
+    REWARD_STATE.save(deps.storage, &state)?;
+    VOTING_POWER.save(deps.storage, &info.sender, &user)?;
+
+    Ok(res)
+}
+
+/// Entry point for users to stake a weighted denom, voting power is granted by its weight
+pub fn stake_denom(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let payment = one_coin(&info)?;
+    let weight = config
+        .weights
+        .get(&payment.denom)
+        .ok_or_else(|| ContractError::InvalidDenom {
+            denom: payment.denom.clone(),
+        })?;
+
+    let power = weighted_power(payment.amount, *weight)?;
+    if power == 0 {
+        return Err(ContractError::ZeroAmount {});
+    }
+
+    let mut user = VOTING_POWER
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default();
+
+    // staking more has to wait until pending unbondings are claimed, like `Stake`
+    if !user.unbonding.is_zero() {
+        return Err(ContractError::UnbondingInProgress {});
+    }
+
+    let mut state = REWARD_STATE.load(deps.storage)?;
+    update_rewards(&mut user, &state)?;
+    let old_power = user.voting_power;
+
+    DENOM_STAKES.update(
+        deps.storage,
+        (&info.sender, &payment.denom),
+        |stake| -> Result<_, ContractError> {
+            let mut stake = stake.unwrap_or_default();
+            stake.amount = stake.amount.checked_add(payment.amount)?;
+            stake.power = checked_add(stake.power, power)?;
+            Ok(stake)
+        },
+    )?;
+
+    user.voting_power = checked_add(user.voting_power, power)?;
+    user.weighted_power = checked_add(user.weighted_power, power)?;
+    user.released_time = env.block.time.plus_seconds(LOCK_PERIOD);
+
+    update_stake_index(deps.storage, &info.sender, old_power, user.voting_power)?;
+
+    state.total_staked = checked_add(state.total_staked, power)?;
+    REWARD_STATE.save(deps.storage, &state)?;
+    VOTING_POWER.save(deps.storage, &info.sender, &user)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "stake_denom")
+        .add_attribute("denom", payment.denom)
+        .add_attribute("amount", payment.amount)
         .add_attribute("user.voting_power", user.voting_power.to_string()))
 }
 
+/// Entry point for users to unstake a weighted denom, subject to the same lock
+/// and unbonding period as the staking denom
+pub fn unstake_denom(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    denom: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+
+    let mut user = VOTING_POWER.load(deps.storage, &info.sender)?;
+
+    // check release time
+    if env.block.time < user.released_time {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut stake = DENOM_STAKES
+        .may_load(deps.storage, (&info.sender, &denom))?
+        .unwrap_or_default();
+    let remaining = stake.amount.checked_sub(amount)?;
+
+    // power is released pro rata, the last unstake releases whatever rounding left
+    let power = if remaining.is_zero() {
+        stake.power
+    } else {
+        Uint128::new(stake.power)
+            .multiply_ratio(amount, stake.amount)
+            .u128()
+    };
+
+    let mut state = REWARD_STATE.load(deps.storage)?;
+    update_rewards(&mut user, &state)?;
+    let old_power = user.voting_power;
+
+    user.voting_power = checked_sub(user.voting_power, power)?;
+    user.weighted_power = checked_sub(user.weighted_power, power)?;
+    state.total_staked = checked_sub(state.total_staked, power)?;
+
+    if remaining.is_zero() {
+        DENOM_STAKES.remove(deps.storage, (&info.sender, &denom));
+    } else {
+        stake.amount = remaining;
+        stake.power = checked_sub(stake.power, power)?;
+        DENOM_STAKES.save(deps.storage, (&info.sender, &denom), &stake)?;
+    }
+
+    update_stake_index(deps.storage, &info.sender, old_power, user.voting_power)?;
+    REWARD_STATE.save(deps.storage, &state)?;
+    VOTING_POWER.save(deps.storage, &info.sender, &user)?;
+
+    let mut res = Response::new()
+        .add_attribute("action", "unstake_denom")
+        .add_attribute("denom", &denom)
+        .add_attribute("amount", amount)
+        .add_attribute("user.voting_power", user.voting_power.to_string());
+
+    if let Some(unbonding_period) = CONFIG.load(deps.storage)?.unbonding_period {
+        let release_time = env.block.time.plus_seconds(unbonding_period);
+        DENOM_UNBONDINGS.update(
+            deps.storage,
+            (&info.sender, release_time.seconds(), &denom),
+            |pending| -> StdResult<_> { Ok(pending.unwrap_or_default().checked_add(amount)?) },
+        )?;
+        res = res.add_attribute("release_time", release_time.seconds().to_string());
+    } else {
+        res = res.add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![coin(amount.u128(), denom)],
+        });
+    }
+
+    Ok(res)
+}
+
+/// Entry point for users to claim accrued rewards
+pub fn claim_rewards(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let mut user = VOTING_POWER.load(deps.storage, &info.sender)?;
+    let state = REWARD_STATE.load(deps.storage)?;
+    update_rewards(&mut user, &state)?;
+
+    let amount = user.pending_rewards;
+
+    if amount.is_zero() {
+        return Err(ContractError::ZeroRewardClaim {});
+    }
+
+    user.pending_rewards = Uint128::zero();
+    VOTING_POWER.save(deps.storage, &info.sender, &user)?;
+
+    let config = CONFIG.load(deps.storage)?;
+    let msg = BankMsg::Send {
+        to_address: info.sender.to_string(),
+        amount: vec![coin(amount.u128(), config.reward_denom)],
+    };
+
+    Ok(Response::new()
+        .add_attribute("action", "claim_rewards")
+        .add_attribute("user", info.sender)
+        .add_attribute("amount", amount)
+        .add_message(msg))
+}
+
+/// Entry point for users to stake accrued rewards, rewards have to be paid in the staking denom
+pub fn compound(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if config.reward_denom != config.denom {
+        return Err(ContractError::RewardDenomMismatch {});
+    }
+
+    let mut user = VOTING_POWER.load(deps.storage, &info.sender)?;
+
+    // compounding is a stake increase, so it waits for pending unbondings like `Stake`
+    if !user.unbonding.is_zero() {
+        return Err(ContractError::UnbondingInProgress {});
+    }
+
+    let mut state = REWARD_STATE.load(deps.storage)?;
+    update_rewards(&mut user, &state)?;
+
+    let amount = user.pending_rewards;
+
+    if amount.is_zero() {
+        return Err(ContractError::ZeroRewardClaim {});
+    }
+
+    let old_power = user.voting_power;
+
+    user.pending_rewards = Uint128::zero();
+    user.total_tokens = user.total_tokens.checked_add(amount)?;
+    user.voting_power = checked_add(user.voting_power, amount.u128())?;
+    user.released_time = env.block.time.plus_seconds(LOCK_PERIOD);
+
+    update_stake_index(deps.storage, &info.sender, old_power, user.voting_power)?;
+
+    state.total_staked = checked_add(state.total_staked, amount.u128())?;
+    REWARD_STATE.save(deps.storage, &state)?;
+    VOTING_POWER.save(deps.storage, &info.sender, &user)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "compound")
+        .add_attribute("user", info.sender)
+        .add_attribute("amount", amount)
+        .add_attribute("user.voting_power", user.voting_power.to_string()))
+}
+
+/// Entry point to fund rewards, split between current stakers by voting power
+pub fn fund_rewards(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let amount = must_pay_exact(&info, &config.reward_denom)?;
+
+    let mut state = REWARD_STATE.load(deps.storage)?;
+    // rewards funded without stakers could never be claimed
+    if state.total_staked == 0 {
+        return Err(ContractError::NothingStaked {});
+    }
+
+    state.global_index = state
+        .global_index
+        .checked_add(Decimal::from_ratio(amount, state.total_staked))?;
+    REWARD_STATE.save(deps.storage, &state)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "fund_rewards")
+        .add_attribute("amount", amount)
+        .add_attribute("global_index", state.global_index.to_string()))
+}
+
+/// Accrues rewards earned since the user's last update
+pub fn update_rewards(user: &mut UserInfo, state: &RewardState) -> Result<(), OverflowError> {
+    if user.voting_power > 0 {
+        let index_delta = state.global_index.checked_sub(user.reward_index)?;
+        let earned = Uint128::new(user.voting_power) * index_delta;
+        user.pending_rewards = user.pending_rewards.checked_add(earned)?;
+    }
+    user.reward_index = state.global_index;
+    Ok(())
+}
+
+/// Voting power granted by `amount` tokens of a denom weighted by `weight`, rounded down
+fn weighted_power(amount: Uint128, weight: Decimal) -> Result<u128, OverflowError> {
+    let scaled = amount.checked_mul(weight.atomics())?;
+    Ok((scaled / Decimal::one().atomics()).u128())
+}
+
+/// Checks the denom against the bank module format, `factory/<creator>/<subdenom>`
+/// denoms must also name a valid creator address
+fn validate_denom(api: &dyn Api, denom: &str) -> Result<(), ContractError> {
+    let invalid = || ContractError::InvalidDenom {
+        denom: denom.to_string(),
+    };
+
+    let mut chars = denom.chars();
+    let starts_with_letter = chars.next().filter(char::is_ascii_alphabetic).is_some();
+    let valid_chars = chars.all(|c| c.is_ascii_alphanumeric() || "/:._-".contains(c));
+    if !starts_with_letter || !valid_chars || denom.len() < 3 || denom.len() > 128 {
+        return Err(invalid());
+    }
+
+    if let Some(path) = denom.strip_prefix("factory/") {
+        let (creator, subdenom) = path.split_once('/').ok_or_else(invalid)?;
+        if subdenom.is_empty() {
+            return Err(invalid());
+        }
+        api.addr_validate(creator).map_err(|_| invalid())?;
+    }
+
+    Ok(())
+}
+
+/// Adds raw `u128` amounts, erroring instead of wrapping around
+fn checked_add(a: u128, b: u128) -> Result<u128, OverflowError> {
+    a.checked_add(b)
+        .ok_or_else(|| OverflowError::new(OverflowOperation::Add, a, b))
+}
+
+/// Subtracts raw `u128` amounts, erroring instead of wrapping around
+fn checked_sub(a: u128, b: u128) -> Result<u128, OverflowError> {
+    a.checked_sub(b)
+        .ok_or_else(|| OverflowError::new(OverflowOperation::Sub, a, b))
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::GetUser { user } => to_binary(&get_user(deps, user)?),
         QueryMsg::GetVotingPower { user } => to_binary(&get_voting_power(deps, user)?),
+        QueryMsg::StakerRank { user } => to_binary(&get_staker_rank(deps, user)?),
+        QueryMsg::PendingRewards { user } => to_binary(&get_pending_rewards(deps, user)?),
+        QueryMsg::Unbondings {
+            user,
+            start_after,
+            limit,
+        } => to_binary(&get_unbondings(deps, user, start_after, limit)?),
+        QueryMsg::ProtocolConfig {} => to_binary(&get_protocol_config(deps)?),
+        QueryMsg::Position { user } => to_binary(&get_position(deps, user)?),
     }
 }
 
@@ -168,3 +819,154 @@ pub fn get_voting_power(deps: Deps, user: String) -> StdResult<u128> {
         .unwrap()
         .voting_power)
 }
+
+/// Returns the user's position in the descending voting power ranking. The rank is counted
+/// by reading every staker ahead, so the cost grows with the rank and the count stops at
+/// `MAX_RANKED_STAKERS`
+pub fn get_staker_rank(deps: Deps, user: String) -> StdResult<StakerRankResponse> {
+    let user_addr = deps.api.addr_validate(&user)?;
+    let voting_power = VOTING_POWER
+        .may_load(deps.storage, &user_addr)?
+        .unwrap_or_default()
+        .voting_power;
+    let total_stakers = TOTAL_STAKERS.may_load(deps.storage)?.unwrap_or_default();
+
+    if voting_power == 0 {
+        return Ok(StakerRankResponse {
+            rank: None,
+            total_stakers,
+        });
+    }
+
+    // only stakers with strictly more power rank ahead, ties share a position
+    let ahead = STAKERS_BY_AMOUNT
+        .prefix_range(
+            deps.storage,
+            Some(PrefixBound::exclusive(voting_power)),
+            None,
+            Order::Ascending,
+        )
+        .take(MAX_RANKED_STAKERS as usize)
+        .count() as u64;
+
+    Ok(StakerRankResponse {
+        rank: (ahead < MAX_RANKED_STAKERS).then_some(ahead + 1),
+        total_stakers,
+    })
+}
+
+/// Returns the rewards a user can currently claim
+pub fn get_pending_rewards(deps: Deps, user: String) -> StdResult<Uint128> {
+    let user_addr = deps.api.addr_validate(&user)?;
+    let mut user = VOTING_POWER
+        .may_load(deps.storage, &user_addr)?
+        .unwrap_or_default();
+    let state = REWARD_STATE.load(deps.storage)?;
+    update_rewards(&mut user, &state)?;
+    Ok(user.pending_rewards)
+}
+
+/// Returns a page of the user's pending unbondings across the staking and weighted denoms,
+/// earliest release first
+pub fn get_unbondings(
+    deps: Deps,
+    user: String,
+    start_after: Option<UnbondingCursor>,
+    limit: Option<u32>,
+) -> StdResult<UnbondingsResponse> {
+    let user_addr = deps.api.addr_validate(&user)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let unbondings = load_unbondings(deps.storage, &user_addr, start_after.as_ref(), limit)?;
+    Ok(UnbondingsResponse { unbondings })
+}
+
+/// Returns the user's whole staking state in one response
+pub fn get_position(deps: Deps, user: String) -> StdResult<PositionResponse> {
+    let user_addr = deps.api.addr_validate(&user)?;
+    let mut user = VOTING_POWER
+        .may_load(deps.storage, &user_addr)?
+        .unwrap_or_default();
+    let state = REWARD_STATE.load(deps.storage)?;
+    update_rewards(&mut user, &state)?;
+
+    Ok(PositionResponse {
+        total_tokens: user.total_tokens,
+        staked: Uint128::new(user.staked()),
+        voting_power: user.voting_power,
+        unlock_time: user.released_time,
+        pending_rewards: user.pending_rewards,
+        unbonding: user.unbonding,
+        unbondings: load_unbondings(deps.storage, &user_addr, None, MAX_POSITION_UNBONDINGS)?,
+    })
+}
+
+/// Loads up to `limit` unbondings of a user across the staking and weighted denoms after
+/// the cursor, earliest release first
+fn load_unbondings(
+    store: &dyn Storage,
+    user: &Addr,
+    start_after: Option<&UnbondingCursor>,
+    limit: usize,
+) -> StdResult<Vec<Unbonding>> {
+    let config = CONFIG.load(store)?;
+
+    // the staking denom comes first among unbondings released at the same time,
+    // so resuming after it still includes every weighted denom of that release time
+    let (start, denom_start) = match start_after {
+        Some(cursor) => {
+            let seconds = cursor.release_time.seconds();
+            let denom = if cursor.denom == config.denom {
+                ""
+            } else {
+                cursor.denom.as_str()
+            };
+            (
+                Some(Bound::exclusive(seconds)),
+                Some(Bound::exclusive((seconds, denom))),
+            )
+        }
+        None => (None, None),
+    };
+
+    let mut unbondings = UNBONDINGS
+        .prefix(user)
+        .range(store, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            item.map(|(release_time, amount)| Unbonding {
+                denom: config.denom.clone(),
+                amount,
+                release_time: Timestamp::from_seconds(release_time),
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let denom_unbondings = DENOM_UNBONDINGS
+        .sub_prefix(user)
+        .range(store, denom_start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for ((release_time, denom), amount) in denom_unbondings {
+        unbondings.push(Unbonding {
+            denom,
+            amount,
+            release_time: Timestamp::from_seconds(release_time),
+        });
+    }
+
+    // both queues are sorted already, the stable sort keeps the staking denom first on ties
+    unbondings.sort_by_key(|unbonding| unbonding.release_time);
+    unbondings.truncate(limit);
+    Ok(unbondings)
+}
+
+/// Returns the denom and lock period stakes are subject to
+pub fn get_protocol_config(deps: Deps) -> StdResult<ProtocolConfigResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    Ok(ProtocolConfigResponse {
+        denom: config.denom,
+        lock_period: Some(LOCK_PERIOD),
+        minimum_deposit: None,
+    })
+}
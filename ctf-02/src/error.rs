@@ -1,4 +1,5 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{OverflowError, StdError, Uint128};
+use ctf_common::payment::PaymentError;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -6,6 +7,48 @@ pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
 
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
+    #[error("{0}")]
+    Payment(#[from] PaymentError),
+
+    #[error("Invalid denom: {denom}")]
+    InvalidDenom { denom: String },
+
+    #[error("The contract cannot be the recipient")]
+    InvalidRecipient {},
+
     #[error("Unauthorized")]
     Unauthorized {},
+
+    #[error("Zero amount is not allowed")]
+    ZeroAmount {},
+
+    #[error("Nothing staked")]
+    NothingStaked {},
+
+    #[error("No rewards to claim")]
+    ZeroRewardClaim {},
+
+    #[error("Cannot stake while tokens are unbonding")]
+    UnbondingInProgress {},
+
+    #[error("No unbonded tokens to claim")]
+    NothingUnbonded {},
+
+    #[error("Cannot stake more than the {available} tokens not yet staked or unbonding")]
+    StakeExceedsBalance { available: Uint128 },
+
+    #[error("Invalid weight for {denom}")]
+    InvalidWeight { denom: String },
+
+    #[error("Cannot weight more than {max} denoms")]
+    TooManyWeights { max: usize },
+
+    #[error("Force unstake penalty must be below 100%")]
+    InvalidPenalty {},
+
+    #[error("Rewards are paid in a different denom than the stake")]
+    RewardDenomMismatch {},
 }
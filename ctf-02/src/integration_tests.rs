@@ -1,12 +1,20 @@
 #[cfg(test)]
 pub mod tests {
     use crate::{
-        contract::{DENOM, LOCK_PERIOD},
-        msg::{ExecuteMsg, InstantiateMsg, QueryMsg},
-        state::UserInfo,
+        contract::{DENOM, LOCK_PERIOD, MAX_CLAIM_UNBONDINGS, MAX_RANKED_STAKERS},
+        error::ContractError,
+        msg::{
+            ExecuteMsg, InstantiateMsg, PositionResponse, QueryMsg, StakerRankResponse,
+            UnbondingCursor, UnbondingsResponse,
+        },
+        state::{Unbonding, UserInfo},
     };
-    use cosmwasm_std::{coin, Addr, Empty, Uint128};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+    use cosmwasm_std::{coin, Addr, Decimal, Empty, Uint128};
+    use ctf_common::{msg::ProtocolConfigResponse, penalty::PenaltyDestination};
     use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+    use insta::assert_json_snapshot;
+    use std::collections::BTreeMap;
 
     pub fn challenge_contract() -> Box<dyn Contract<Empty>> {
         let contract = ContractWrapper::new(
@@ -25,7 +33,14 @@ pub mod tests {
         let cw_template_id = app.store_code(challenge_contract());
 
         // init contract
-        let msg = InstantiateMsg {};
+        let msg = InstantiateMsg {
+            denom: None,
+            reward_denom: None,
+            unbonding_period: None,
+            penalty_destination: None,
+            force_unstake_penalty: None,
+            weights: None,
+        };
         let contract_addr = app
             .instantiate_contract(
                 cw_template_id,
@@ -116,7 +131,10 @@ pub mod tests {
             .unwrap_err();
 
         // cannot withdraw while staked
-        let msg = ExecuteMsg::Withdraw { amount };
+        let msg = ExecuteMsg::Withdraw {
+            amount,
+            recipient: None,
+        };
         app.execute_contract(sender.clone(), contract_addr.clone(), &msg, &[])
             .unwrap_err();
 
@@ -143,7 +161,10 @@ pub mod tests {
         assert_eq!(voting_power, 0_u128);
 
         // normal withdraw
-        let msg = ExecuteMsg::Withdraw { amount };
+        let msg = ExecuteMsg::Withdraw {
+            amount,
+            recipient: None,
+        };
         app.execute_contract(sender, contract_addr, &msg, &[])
             .unwrap();
 
@@ -155,6 +176,7 @@ pub mod tests {
 
     // Run `cargo test --release`
     #[test]
+    #[cfg(not(feature = "fixed"))]
     fn runneth_under() {
         let (mut app, contract_addr) = proper_instantiate();
 
@@ -232,4 +254,1880 @@ pub mod tests {
         assert_eq!(user_info.voting_power, 340282366920938463463374607431768211455u128);
     }
 
+    pub fn deposit_and_stake(mut app: App, contract_addr: &Addr, user: &str, amount: u128) -> App {
+        app = mint_tokens(app, user.to_string(), Uint128::new(amount));
+        app.execute_contract(
+            Addr::unchecked(user),
+            contract_addr.clone(),
+            &ExecuteMsg::Deposit {},
+            &[coin(amount, DENOM)],
+        )
+        .unwrap();
+        app.execute_contract(
+            Addr::unchecked(user),
+            contract_addr.clone(),
+            &ExecuteMsg::Stake {
+                lock_amount: amount,
+            },
+            &[],
+        )
+        .unwrap();
+        app
+    }
+
+    #[test]
+    fn staker_rank() {
+        let (mut app, contract_addr) = proper_instantiate();
+
+        app = deposit_and_stake(app, &contract_addr, "alice", 300);
+        app = deposit_and_stake(app, &contract_addr, "bob", 100);
+        app = deposit_and_stake(app, &contract_addr, USER, 200);
+
+        let rank: StakerRankResponse = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::StakerRank {
+                    user: USER.to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(rank.rank, Some(2));
+        assert_eq!(rank.total_stakers, 3);
+
+        // fast forward time
+        app.update_block(|block| {
+            block.time = block.time.plus_seconds(LOCK_PERIOD);
+        });
+
+        // alice fully unstakes and drops out of the ranking
+        app.execute_contract(
+            Addr::unchecked("alice"),
+            contract_addr.clone(),
+            &ExecuteMsg::Unstake { unlock_amount: 300 },
+            &[],
+        )
+        .unwrap();
+
+        let rank: StakerRankResponse = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::StakerRank {
+                    user: USER.to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(rank.rank, Some(1));
+        assert_eq!(rank.total_stakers, 2);
+
+        let rank: StakerRankResponse = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr,
+                &QueryMsg::StakerRank {
+                    user: "alice".to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(rank.rank, None);
+    }
+
+    #[test]
+    fn staker_rank_capped() {
+        let (mut app, contract_addr) = proper_instantiate();
+
+        for i in 0..MAX_RANKED_STAKERS {
+            let staker = format!("staker{i}");
+            app = deposit_and_stake(app, &contract_addr, &staker, 1_000 + i as u128);
+        }
+        app = deposit_and_stake(app, &contract_addr, USER, 100);
+
+        let rank: StakerRankResponse = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::StakerRank {
+                    user: "staker0".to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(rank.rank, Some(MAX_RANKED_STAKERS));
+
+        // ranked below the cap, still counted as a staker
+        let rank: StakerRankResponse = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr,
+                &QueryMsg::StakerRank {
+                    user: USER.to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(rank.rank, None);
+        assert_eq!(rank.total_stakers, MAX_RANKED_STAKERS + 1);
+    }
+
+    #[test]
+    fn force_unstake_penalty_redistributed() {
+        let (mut app, contract_addr) = proper_instantiate();
+
+        app = deposit_and_stake(app, &contract_addr, "alice", 1_000);
+        app = deposit_and_stake(app, &contract_addr, "bob", 1_000);
+
+        // alice exits early and pays a 10% penalty
+        app.execute_contract(
+            Addr::unchecked("alice"),
+            contract_addr.clone(),
+            &ExecuteMsg::ForceUnstake {},
+            &[],
+        )
+        .unwrap();
+
+        let user: UserInfo = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::GetUser {
+                    user: "alice".to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(user.voting_power, 0);
+        assert_eq!(user.total_tokens, Uint128::new(900));
+
+        // bob is the only remaining staker and receives the whole penalty
+        let pending: Uint128 = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::PendingRewards {
+                    user: "bob".to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(pending, Uint128::new(100));
+
+        app.execute_contract(
+            Addr::unchecked("alice"),
+            contract_addr.clone(),
+            &ExecuteMsg::Withdraw {
+                amount: Uint128::new(900),
+                recipient: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+        app.execute_contract(
+            Addr::unchecked("bob"),
+            contract_addr.clone(),
+            &ExecuteMsg::ClaimRewards {},
+            &[],
+        )
+        .unwrap();
+
+        let balance = app.wrap().query_balance("alice", DENOM).unwrap().amount;
+        assert_eq!(balance, Uint128::new(900));
+        let balance = app.wrap().query_balance("bob", DENOM).unwrap().amount;
+        assert_eq!(balance, Uint128::new(100));
+
+        // nothing left to claim
+        app.execute_contract(
+            Addr::unchecked("bob"),
+            contract_addr,
+            &ExecuteMsg::ClaimRewards {},
+            &[],
+        )
+        .unwrap_err();
+    }
+
+    #[test]
+    fn zero_amounts_rejected() {
+        let (mut app, contract_addr) = proper_instantiate();
+        app = deposit_and_stake(app, &contract_addr, USER, 1_000);
+
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::Stake { lock_amount: 0 },
+            &[],
+        )
+        .unwrap_err();
+
+        app.update_block(|block| {
+            block.time = block.time.plus_seconds(LOCK_PERIOD);
+        });
+
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr,
+            &ExecuteMsg::Unstake { unlock_amount: 0 },
+            &[],
+        )
+        .unwrap_err();
+    }
+
+    #[test]
+    fn stake_while_unbonding() {
+        let mut app = App::default();
+        let code_id = app.store_code(challenge_contract());
+
+        let contract_addr = app
+            .instantiate_contract(
+                code_id,
+                Addr::unchecked(ADMIN),
+                &InstantiateMsg {
+                    denom: None,
+                    reward_denom: None,
+                    unbonding_period: Some(LOCK_PERIOD),
+                    penalty_destination: None,
+                    force_unstake_penalty: None,
+                    weights: None,
+                },
+                &[],
+                "test",
+                None,
+            )
+            .unwrap();
+
+        app = deposit_and_stake(app, &contract_addr, USER, 1_000);
+
+        app.update_block(|block| {
+            block.time = block.time.plus_seconds(LOCK_PERIOD);
+        });
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::Unstake { unlock_amount: 500 },
+            &[],
+        )
+        .unwrap();
+
+        // the unstaked tokens cannot be staked again while unbonding
+        let err = app
+            .execute_contract(
+                Addr::unchecked(USER),
+                contract_addr.clone(),
+                &ExecuteMsg::Stake { lock_amount: 500 },
+                &[],
+            )
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ContractError>(),
+            Some(ContractError::UnbondingInProgress {})
+        ));
+
+        // nor can rewards be compounded into the stake
+        app = deposit_and_stake(app, &contract_addr, "alice", 1_000);
+        app.execute_contract(
+            Addr::unchecked("alice"),
+            contract_addr.clone(),
+            &ExecuteMsg::ForceUnstake {},
+            &[],
+        )
+        .unwrap();
+        let err = app
+            .execute_contract(
+                Addr::unchecked(USER),
+                contract_addr.clone(),
+                &ExecuteMsg::Compound {},
+                &[],
+            )
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ContractError>(),
+            Some(ContractError::UnbondingInProgress {})
+        ));
+
+        // nor withdrawn or claimed before the cooldown elapses
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::Withdraw {
+                amount: Uint128::new(500),
+                recipient: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::ClaimUnbonded {},
+            &[],
+        )
+        .unwrap_err();
+
+        app.update_block(|block| {
+            block.time = block.time.plus_seconds(LOCK_PERIOD);
+        });
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::ClaimUnbonded {},
+            &[],
+        )
+        .unwrap();
+
+        let balance = app.wrap().query_balance(USER, DENOM).unwrap().amount;
+        assert_eq!(balance, Uint128::new(500));
+
+        // staking is possible again once nothing is unbonding
+        app = deposit_and_stake(app, &contract_addr, USER, 500);
+
+        let voting_power: u128 = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr,
+                &QueryMsg::GetVotingPower {
+                    user: USER.to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(voting_power, 1_000);
+    }
+
+    #[test]
+    fn force_unstake_unbonding() {
+        let mut app = App::default();
+        let code_id = app.store_code(challenge_contract());
+
+        let contract_addr = app
+            .instantiate_contract(
+                code_id,
+                Addr::unchecked(ADMIN),
+                &InstantiateMsg {
+                    denom: None,
+                    reward_denom: None,
+                    unbonding_period: Some(LOCK_PERIOD),
+                    penalty_destination: None,
+                    force_unstake_penalty: None,
+                    weights: None,
+                },
+                &[],
+                "test",
+                None,
+            )
+            .unwrap();
+
+        app = deposit_and_stake(app, &contract_addr, USER, 1_000);
+
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::ForceUnstake {},
+            &[],
+        )
+        .unwrap();
+
+        // the penalty is taken right away, the rest is queued
+        let user: UserInfo = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::GetUser {
+                    user: USER.to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(user.total_tokens, Uint128::new(900));
+        assert_eq!(user.unbonding, Uint128::new(900));
+
+        // nothing can be withdrawn before the cooldown elapses
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::Withdraw {
+                amount: Uint128::new(900),
+                recipient: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+
+        app.update_block(|block| {
+            block.time = block.time.plus_seconds(LOCK_PERIOD);
+        });
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::ClaimUnbonded {},
+            &[],
+        )
+        .unwrap();
+
+        let balance = app.wrap().query_balance(USER, DENOM).unwrap().amount;
+        assert_eq!(balance, Uint128::new(900));
+
+        let user: UserInfo = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr,
+                &QueryMsg::GetUser {
+                    user: USER.to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(user.total_tokens, Uint128::zero());
+        assert_eq!(user.unbonding, Uint128::zero());
+    }
+
+    #[test]
+    fn unbondings_query() {
+        let mut app = App::default();
+        let code_id = app.store_code(challenge_contract());
+
+        let contract_addr = app
+            .instantiate_contract(
+                code_id,
+                Addr::unchecked(ADMIN),
+                &InstantiateMsg {
+                    denom: None,
+                    reward_denom: None,
+                    unbonding_period: Some(LOCK_PERIOD),
+                    penalty_destination: None,
+                    force_unstake_penalty: None,
+                    weights: None,
+                },
+                &[],
+                "test",
+                None,
+            )
+            .unwrap();
+
+        app = deposit_and_stake(app, &contract_addr, USER, 900);
+
+        app.update_block(|block| {
+            block.time = block.time.plus_seconds(LOCK_PERIOD);
+        });
+        let start = app.block_info().time;
+
+        // three unstakes an hour apart
+        for i in 0..3 {
+            app.execute_contract(
+                Addr::unchecked(USER),
+                contract_addr.clone(),
+                &ExecuteMsg::Unstake { unlock_amount: 300 },
+                &[],
+            )
+            .unwrap();
+            if i < 2 {
+                app.update_block(|block| {
+                    block.time = block.time.plus_seconds(3_600);
+                });
+            }
+        }
+
+        let res: UnbondingsResponse = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::Unbondings {
+                    user: USER.to_string(),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(res.unbondings.len(), 3);
+        for (i, unbonding) in res.unbondings.iter().enumerate() {
+            assert_eq!(unbonding.amount, Uint128::new(300));
+            assert_eq!(
+                unbonding.release_time,
+                start.plus_seconds(LOCK_PERIOD + 3_600 * i as u64)
+            );
+        }
+
+        // claimed unbondings are no longer listed
+        app.update_block(|block| {
+            block.time = start.plus_seconds(LOCK_PERIOD);
+        });
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::ClaimUnbonded {},
+            &[],
+        )
+        .unwrap();
+
+        let res: UnbondingsResponse = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr,
+                &QueryMsg::Unbondings {
+                    user: USER.to_string(),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(res.unbondings.len(), 2);
+        assert_eq!(
+            res.unbondings[0].release_time,
+            start.plus_seconds(LOCK_PERIOD + 3_600)
+        );
+    }
+
+    #[test]
+    fn unbondings_paginated() {
+        let mut app = App::default();
+        let code_id = app.store_code(challenge_contract());
+
+        let contract_addr = app
+            .instantiate_contract(
+                code_id,
+                Addr::unchecked(ADMIN),
+                &InstantiateMsg {
+                    denom: None,
+                    reward_denom: None,
+                    unbonding_period: Some(LOCK_PERIOD),
+                    penalty_destination: None,
+                    force_unstake_penalty: None,
+                    weights: None,
+                },
+                &[],
+                "test",
+                None,
+            )
+            .unwrap();
+
+        app = deposit_and_stake(app, &contract_addr, USER, 350);
+
+        app.update_block(|block| {
+            block.time = block.time.plus_seconds(LOCK_PERIOD);
+        });
+
+        // one unbonding per second
+        for _ in 0..35 {
+            app.execute_contract(
+                Addr::unchecked(USER),
+                contract_addr.clone(),
+                &ExecuteMsg::Unstake { unlock_amount: 10 },
+                &[],
+            )
+            .unwrap();
+            app.update_block(|block| {
+                block.time = block.time.plus_seconds(1);
+            });
+        }
+
+        let query =
+            |start_after: Option<UnbondingCursor>, limit: Option<u32>| QueryMsg::Unbondings {
+                user: USER.to_string(),
+                start_after,
+                limit,
+            };
+
+        let page: UnbondingsResponse = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &query(None, None))
+            .unwrap();
+        assert_eq!(page.unbondings.len(), 10);
+
+        // oversized limits are capped
+        let page: UnbondingsResponse = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &query(None, Some(100)))
+            .unwrap();
+        assert_eq!(page.unbondings.len(), 30);
+
+        // the next page resumes right after the cursor
+        let last = &page.unbondings[9];
+        let cursor = UnbondingCursor {
+            release_time: last.release_time,
+            denom: last.denom.clone(),
+        };
+        let next: UnbondingsResponse = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &query(Some(cursor), Some(30)))
+            .unwrap();
+        assert_eq!(next.unbondings.len(), 25);
+        assert_eq!(next.unbondings[0], page.unbondings[10]);
+
+        // claims are capped, the remaining entries are paid out by the next call
+        app.update_block(|block| {
+            block.time = block.time.plus_seconds(LOCK_PERIOD);
+        });
+        for expected in [MAX_CLAIM_UNBONDINGS as u128 * 10, 350] {
+            app.execute_contract(
+                Addr::unchecked(USER),
+                contract_addr.clone(),
+                &ExecuteMsg::ClaimUnbonded {},
+                &[],
+            )
+            .unwrap();
+            let balance = app.wrap().query_balance(USER, DENOM).unwrap().amount;
+            assert_eq!(balance, Uint128::new(expected));
+        }
+
+        let err = app
+            .execute_contract(
+                Addr::unchecked(USER),
+                contract_addr,
+                &ExecuteMsg::ClaimUnbonded {},
+                &[],
+            )
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ContractError>(),
+            Some(ContractError::NothingUnbonded {})
+        ));
+    }
+
+    #[test]
+    fn withdraw_to_recipient() {
+        let (mut app, contract_addr) = proper_instantiate();
+
+        app = mint_tokens(app, USER.to_owned(), Uint128::new(1_000));
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::Deposit {},
+            &[coin(1_000, DENOM)],
+        )
+        .unwrap();
+
+        let res = app
+            .execute_contract(
+                Addr::unchecked(USER),
+                contract_addr.clone(),
+                &ExecuteMsg::Withdraw {
+                    amount: Uint128::new(400),
+                    recipient: Some("settlement".to_string()),
+                },
+                &[],
+            )
+            .unwrap();
+
+        let event = res.events.iter().find(|ev| ev.ty == "wasm").unwrap();
+        let recipient = event
+            .attributes
+            .iter()
+            .find(|attr| attr.key == "recipient")
+            .unwrap();
+        assert_eq!(recipient.value, "settlement");
+
+        let bal = app.wrap().query_balance("settlement", DENOM).unwrap();
+        assert_eq!(bal.amount, Uint128::new(400));
+
+        // remaining deposit still belongs to the depositor
+        let user: UserInfo = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr,
+                &QueryMsg::GetUser {
+                    user: USER.to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(user.total_tokens, Uint128::new(600));
+    }
+
+    #[test]
+    fn compound_rewards() {
+        let (mut app, contract_addr) = proper_instantiate();
+
+        app = deposit_and_stake(app, &contract_addr, "alice", 1_000);
+        app = deposit_and_stake(app, &contract_addr, "bob", 1_000);
+
+        // alice's penalty is distributed to bob
+        app.execute_contract(
+            Addr::unchecked("alice"),
+            contract_addr.clone(),
+            &ExecuteMsg::ForceUnstake {},
+            &[],
+        )
+        .unwrap();
+
+        app.execute_contract(
+            Addr::unchecked("bob"),
+            contract_addr.clone(),
+            &ExecuteMsg::Compound {},
+            &[],
+        )
+        .unwrap();
+
+        let user: UserInfo = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::GetUser {
+                    user: "bob".to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(user.voting_power, 1_100);
+        assert_eq!(user.total_tokens, Uint128::new(1_100));
+
+        let pending: Uint128 = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::PendingRewards {
+                    user: "bob".to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(pending, Uint128::zero());
+
+        // nothing left to compound
+        app.execute_contract(
+            Addr::unchecked("bob"),
+            contract_addr,
+            &ExecuteMsg::Compound {},
+            &[],
+        )
+        .unwrap_err();
+    }
+
+    #[test]
+    fn weighted_denoms() {
+        let mut app = App::default();
+        let code_id = app.store_code(challenge_contract());
+
+        // weights must be positive
+        let mut msg = InstantiateMsg {
+            denom: None,
+            reward_denom: None,
+            unbonding_period: None,
+            penalty_destination: None,
+            force_unstake_penalty: None,
+            weights: Some(BTreeMap::from([("uatom".to_string(), Decimal::zero())])),
+        };
+        let err = app
+            .instantiate_contract(code_id, Addr::unchecked(ADMIN), &msg, &[], "test", None)
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ContractError>(),
+            Some(ContractError::InvalidWeight { denom }) if denom == "uatom"
+        ));
+
+        // the staking denom always counts one to one
+        msg.weights = Some(BTreeMap::from([(DENOM.to_string(), Decimal::percent(200))]));
+        let err = app
+            .instantiate_contract(code_id, Addr::unchecked(ADMIN), &msg, &[], "test", None)
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ContractError>(),
+            Some(ContractError::InvalidDenom { .. })
+        ));
+
+        msg.weights = Some(BTreeMap::from([
+            ("uatom".to_string(), Decimal::percent(200)),
+            ("uosmo".to_string(), Decimal::percent(50)),
+        ]));
+        let contract_addr = app
+            .instantiate_contract(code_id, Addr::unchecked(ADMIN), &msg, &[], "test", None)
+            .unwrap();
+
+        app = deposit_and_stake(app, &contract_addr, USER, 1_000);
+        app.sudo(cw_multi_test::SudoMsg::Bank(
+            cw_multi_test::BankSudo::Mint {
+                to_address: USER.to_string(),
+                amount: vec![coin(100, "uatom"), coin(300, "uosmo"), coin(100, "uother")],
+            },
+        ))
+        .unwrap();
+
+        // only weighted denoms can be staked this way
+        let err = app
+            .execute_contract(
+                Addr::unchecked(USER),
+                contract_addr.clone(),
+                &ExecuteMsg::StakeDenom {},
+                &[coin(100, "uother")],
+            )
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ContractError>(),
+            Some(ContractError::InvalidDenom { denom }) if denom == "uother"
+        ));
+
+        for funds in [coin(100, "uatom"), coin(300, "uosmo")] {
+            app.execute_contract(
+                Addr::unchecked(USER),
+                contract_addr.clone(),
+                &ExecuteMsg::StakeDenom {},
+                &[funds],
+            )
+            .unwrap();
+        }
+
+        // 1_000 staked + 100 * 2 + 300 * 0.5
+        let user: UserInfo = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::GetUser {
+                    user: USER.to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(user.voting_power, 1_350);
+        assert_eq!(user.weighted_power, 350);
+
+        // force unstaking only releases the staking denom
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::ForceUnstake {},
+            &[],
+        )
+        .unwrap();
+        let voting_power: u128 = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::GetVotingPower {
+                    user: USER.to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(voting_power, 350);
+
+        // the penalty isn't paid back to the weighted stake left behind
+        let pending_rewards: Uint128 = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::PendingRewards {
+                    user: USER.to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(pending_rewards, Uint128::zero());
+
+        app.update_block(|block| {
+            block.time = block.time.plus_seconds(LOCK_PERIOD);
+        });
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::UnstakeDenom {
+                denom: "uosmo".to_string(),
+                amount: Uint128::new(300),
+            },
+            &[],
+        )
+        .unwrap();
+
+        let voting_power: u128 = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr,
+                &QueryMsg::GetVotingPower {
+                    user: USER.to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(voting_power, 200);
+        let balance = app.wrap().query_balance(USER, "uosmo").unwrap().amount;
+        assert_eq!(balance, Uint128::new(300));
+    }
+
+    #[test]
+    fn weighted_denom_unbondings() {
+        let mut app = App::default();
+        let code_id = app.store_code(challenge_contract());
+
+        let msg = InstantiateMsg {
+            denom: None,
+            reward_denom: None,
+            unbonding_period: Some(LOCK_PERIOD),
+            penalty_destination: None,
+            force_unstake_penalty: None,
+            weights: Some(BTreeMap::from([(
+                "uatom".to_string(),
+                Decimal::percent(200),
+            )])),
+        };
+        let contract_addr = app
+            .instantiate_contract(code_id, Addr::unchecked(ADMIN), &msg, &[], "test", None)
+            .unwrap();
+
+        app = deposit_and_stake(app, &contract_addr, USER, 1_000);
+        app.sudo(cw_multi_test::SudoMsg::Bank(
+            cw_multi_test::BankSudo::Mint {
+                to_address: USER.to_string(),
+                amount: vec![coin(200, "uatom")],
+            },
+        ))
+        .unwrap();
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::StakeDenom {},
+            &[coin(100, "uatom")],
+        )
+        .unwrap();
+
+        app.update_block(|block| {
+            block.time = block.time.plus_seconds(LOCK_PERIOD);
+        });
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::Unstake { unlock_amount: 400 },
+            &[],
+        )
+        .unwrap();
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::UnstakeDenom {
+                denom: "uatom".to_string(),
+                amount: Uint128::new(100),
+            },
+            &[],
+        )
+        .unwrap();
+
+        // weighted stakes wait for pending unbondings like `Stake`
+        let err = app
+            .execute_contract(
+                Addr::unchecked(USER),
+                contract_addr.clone(),
+                &ExecuteMsg::StakeDenom {},
+                &[coin(100, "uatom")],
+            )
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ContractError>(),
+            Some(ContractError::UnbondingInProgress {})
+        ));
+
+        // both denoms show up with their release time
+        let release_time = app.block_info().time.plus_seconds(LOCK_PERIOD);
+        let pending: UnbondingsResponse = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::Unbondings {
+                    user: USER.to_string(),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            pending.unbondings,
+            vec![
+                Unbonding {
+                    denom: DENOM.to_string(),
+                    amount: Uint128::new(400),
+                    release_time,
+                },
+                Unbonding {
+                    denom: "uatom".to_string(),
+                    amount: Uint128::new(100),
+                    release_time,
+                },
+            ]
+        );
+
+        // paging one at a time keeps both denoms of the shared release time
+        let first: UnbondingsResponse = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::Unbondings {
+                    user: USER.to_string(),
+                    start_after: None,
+                    limit: Some(1),
+                },
+            )
+            .unwrap();
+        assert_eq!(first.unbondings, pending.unbondings[..1]);
+        let second: UnbondingsResponse = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::Unbondings {
+                    user: USER.to_string(),
+                    start_after: Some(UnbondingCursor {
+                        release_time,
+                        denom: DENOM.to_string(),
+                    }),
+                    limit: Some(1),
+                },
+            )
+            .unwrap();
+        assert_eq!(second.unbondings, pending.unbondings[1..]);
+
+        app.update_block(|block| {
+            block.time = block.time.plus_seconds(LOCK_PERIOD);
+        });
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::ClaimUnbonded {},
+            &[],
+        )
+        .unwrap();
+
+        // claimed unbondings are no longer listed
+        let pending: UnbondingsResponse = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr,
+                &QueryMsg::Unbondings {
+                    user: USER.to_string(),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        assert!(pending.unbondings.is_empty());
+        let balance = app.wrap().query_balance(USER, "uatom").unwrap().amount;
+        assert_eq!(balance, Uint128::new(200));
+        let balance = app.wrap().query_balance(USER, DENOM).unwrap().amount;
+        assert_eq!(balance, Uint128::new(400));
+    }
+
+    #[test]
+    fn protocol_config() {
+        let (mut app, contract_addr) = proper_instantiate();
+
+        let config: ProtocolConfigResponse = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::ProtocolConfig {})
+            .unwrap();
+        assert_eq!(config.denom, DENOM);
+        assert_eq!(config.lock_period, Some(LOCK_PERIOD));
+        assert_eq!(config.minimum_deposit, None);
+
+        // deposits are accepted in the reported denom
+        app = mint_tokens(app, USER.to_string(), Uint128::new(1_000));
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr,
+            &ExecuteMsg::Deposit {},
+            &[coin(1_000, config.denom)],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn query_snapshots() {
+        let (mut app, contract_addr) = proper_instantiate();
+
+        app = mint_tokens(app, USER.to_string(), Uint128::new(1_000));
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::Deposit {},
+            &[coin(1_000, DENOM)],
+        )
+        .unwrap();
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::Stake { lock_amount: 500 },
+            &[],
+        )
+        .unwrap();
+
+        let msg = QueryMsg::GetUser {
+            user: USER.to_string(),
+        };
+        let user: UserInfo = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &msg)
+            .unwrap();
+        assert_json_snapshot!("user", user);
+
+        let msg = QueryMsg::GetVotingPower {
+            user: USER.to_string(),
+        };
+        let voting_power: u128 = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &msg)
+            .unwrap();
+        assert_json_snapshot!("voting_power", voting_power);
+
+        let msg = QueryMsg::StakerRank {
+            user: USER.to_string(),
+        };
+        let rank: StakerRankResponse = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &msg)
+            .unwrap();
+        assert_json_snapshot!("staker_rank", rank);
+
+        let msg = QueryMsg::PendingRewards {
+            user: USER.to_string(),
+        };
+        let pending: Uint128 = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &msg)
+            .unwrap();
+        assert_json_snapshot!("pending_rewards", pending);
+
+        let protocol_config: ProtocolConfigResponse = app
+            .wrap()
+            .query_wasm_smart(contract_addr, &QueryMsg::ProtocolConfig {})
+            .unwrap();
+        assert_json_snapshot!("protocol_config", protocol_config);
+    }
+
+    #[test]
+    #[cfg(feature = "fixed")]
+    fn unstake_underflow_rejected() {
+        let (mut app, contract_addr) = proper_instantiate();
+
+        app = deposit_and_stake(app, &contract_addr, USER, 1_000);
+
+        app.update_block(|block| {
+            block.time = block.time.plus_seconds(LOCK_PERIOD);
+        });
+
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::Unstake {
+                unlock_amount: 1_001,
+            },
+            &[],
+        )
+        .unwrap_err();
+
+        let voting_power: u128 = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr,
+                &QueryMsg::GetVotingPower {
+                    user: USER.to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(voting_power, 1_000);
+    }
+
+    #[test]
+    fn deposit_overflow_rejected() {
+        // bank balances can't reach u128::MAX in multi-test, so the contract is called directly
+        let mut deps = mock_dependencies();
+
+        crate::contract::instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(ADMIN, &[]),
+            InstantiateMsg {
+                denom: None,
+                reward_denom: None,
+                unbonding_period: None,
+                penalty_destination: None,
+                force_unstake_penalty: None,
+                weights: None,
+            },
+        )
+        .unwrap();
+
+        let info = mock_info(USER, &[coin(u128::MAX, DENOM)]);
+        crate::contract::execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {}).unwrap();
+
+        // errors instead of wrapping or panicking
+        let info = mock_info(USER, &[coin(1, DENOM)]);
+        crate::contract::execute(deps.as_mut(), mock_env(), info, ExecuteMsg::Deposit {})
+            .unwrap_err();
+    }
+
+    #[test]
+    fn factory_denom() {
+        let mut app = App::default();
+        let code_id = app.store_code(challenge_contract());
+        let denom = "factory/creator/ustake";
+
+        // malformed denoms are rejected at instantiation
+        for invalid in [
+            "1stake",
+            "u$d",
+            "factory/creator",
+            "factory/creator/",
+            "factory//x",
+        ] {
+            let msg = InstantiateMsg {
+                denom: Some(invalid.to_string()),
+                reward_denom: None,
+                unbonding_period: None,
+                penalty_destination: None,
+                force_unstake_penalty: None,
+                weights: None,
+            };
+            app.instantiate_contract(code_id, Addr::unchecked(ADMIN), &msg, &[], "test", None)
+                .unwrap_err();
+        }
+
+        let msg = InstantiateMsg {
+            denom: Some(denom.to_string()),
+            reward_denom: None,
+            unbonding_period: None,
+            penalty_destination: None,
+            force_unstake_penalty: None,
+            weights: None,
+        };
+        let contract_addr = app
+            .instantiate_contract(code_id, Addr::unchecked(ADMIN), &msg, &[], "test", None)
+            .unwrap();
+
+        let config: ProtocolConfigResponse = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::ProtocolConfig {})
+            .unwrap();
+        assert_eq!(config.denom, denom);
+
+        app.sudo(cw_multi_test::SudoMsg::Bank(
+            cw_multi_test::BankSudo::Mint {
+                to_address: USER.to_string(),
+                amount: vec![coin(1_000, denom), coin(1_000, DENOM)],
+            },
+        ))
+        .unwrap();
+
+        // the default denom is no longer accepted
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::Deposit {},
+            &[coin(1_000, DENOM)],
+        )
+        .unwrap_err();
+
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::Deposit {},
+            &[coin(1_000, denom)],
+        )
+        .unwrap();
+
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr,
+            &ExecuteMsg::Withdraw {
+                amount: Uint128::new(1_000),
+                recipient: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+        let balance = app.wrap().query_balance(USER, denom).unwrap().amount;
+        assert_eq!(balance, Uint128::new(1_000));
+    }
+
+    #[test]
+    fn unbonding_period() {
+        let mut app = App::default();
+        let code_id = app.store_code(challenge_contract());
+        let unbonding_period = 3 * LOCK_PERIOD;
+
+        let msg = InstantiateMsg {
+            denom: None,
+            reward_denom: None,
+            unbonding_period: Some(unbonding_period),
+            penalty_destination: None,
+            force_unstake_penalty: None,
+            weights: None,
+        };
+        let contract_addr = app
+            .instantiate_contract(code_id, Addr::unchecked(ADMIN), &msg, &[], "test", None)
+            .unwrap();
+
+        app = deposit_and_stake(app, &contract_addr, USER, 1_000);
+
+        app.update_block(|block| {
+            block.time = block.time.plus_seconds(LOCK_PERIOD);
+        });
+
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::Unstake { unlock_amount: 600 },
+            &[],
+        )
+        .unwrap();
+
+        // voting power is removed right away
+        let voting_power: u128 = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::GetVotingPower {
+                    user: USER.to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(voting_power, 400);
+
+        let msg = QueryMsg::Unbondings {
+            user: USER.to_string(),
+            start_after: None,
+            limit: None,
+        };
+        let pending: UnbondingsResponse = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &msg)
+            .unwrap();
+        assert_eq!(pending.unbondings.len(), 1);
+        assert_eq!(pending.unbondings[0].amount, Uint128::new(600));
+        assert_eq!(
+            pending.unbondings[0].release_time,
+            app.block_info().time.plus_seconds(unbonding_period)
+        );
+
+        // unbonding tokens can't be claimed before the cooldown elapses
+        app.update_block(|block| {
+            block.time = block.time.plus_seconds(unbonding_period - 1);
+        });
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::ClaimUnbonded {},
+            &[],
+        )
+        .unwrap_err();
+
+        app.update_block(|block| {
+            block.time = block.time.plus_seconds(1);
+        });
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::ClaimUnbonded {},
+            &[],
+        )
+        .unwrap();
+
+        let balance = app.wrap().query_balance(USER, DENOM).unwrap().amount;
+        assert_eq!(balance, Uint128::new(600));
+
+        let pending: UnbondingsResponse = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &msg)
+            .unwrap();
+        assert!(pending.unbondings.is_empty());
+
+        let user: UserInfo = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr,
+                &QueryMsg::GetUser {
+                    user: USER.to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(user.total_tokens, Uint128::new(400));
+        assert_eq!(user.unbonding, Uint128::zero());
+    }
+
+    #[test]
+    fn partial_unstake_rewards() {
+        let (mut app, contract_addr) = proper_instantiate();
+
+        app = deposit_and_stake(app, &contract_addr, "alice", 1_000);
+        app = deposit_and_stake(app, &contract_addr, "bob", 1_000);
+
+        // alice's penalty is distributed to bob
+        app.execute_contract(
+            Addr::unchecked("alice"),
+            contract_addr.clone(),
+            &ExecuteMsg::ForceUnstake {},
+            &[],
+        )
+        .unwrap();
+
+        app.update_block(|block| {
+            block.time = block.time.plus_seconds(LOCK_PERIOD);
+        });
+
+        app.execute_contract(
+            Addr::unchecked("bob"),
+            contract_addr.clone(),
+            &ExecuteMsg::Unstake { unlock_amount: 500 },
+            &[],
+        )
+        .unwrap();
+
+        // half of the rewards are paid out with the unstaked half
+        let balance = app.wrap().query_balance("bob", DENOM).unwrap().amount;
+        assert_eq!(balance, Uint128::new(50));
+
+        let user: UserInfo = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::GetUser {
+                    user: "bob".to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(user.voting_power, 500);
+        assert_eq!(user.pending_rewards, Uint128::new(50));
+
+        let pending: Uint128 = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr,
+                &QueryMsg::PendingRewards {
+                    user: "bob".to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(pending, Uint128::new(50));
+    }
+
+    #[test]
+    fn withdraw_to_contract_rejected() {
+        let (mut app, contract_addr) = proper_instantiate();
+
+        app = mint_tokens(app, USER.to_string(), Uint128::new(1_000));
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::Deposit {},
+            &[coin(1_000, DENOM)],
+        )
+        .unwrap();
+
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::Withdraw {
+                amount: Uint128::new(1_000),
+                recipient: Some(contract_addr.to_string()),
+            },
+            &[],
+        )
+        .unwrap_err();
+
+        let user: UserInfo = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr,
+                &QueryMsg::GetUser {
+                    user: USER.to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(user.total_tokens, Uint128::new(1_000));
+    }
+
+    #[test]
+    fn penalty_destination() {
+        let destinations = [
+            PenaltyDestination::Contract,
+            PenaltyDestination::Treasury {
+                address: Addr::unchecked("treasury"),
+            },
+            PenaltyDestination::Redistribute,
+        ];
+
+        for destination in destinations {
+            let mut app = App::default();
+            let code_id = app.store_code(challenge_contract());
+
+            let msg = InstantiateMsg {
+                denom: None,
+                reward_denom: None,
+                unbonding_period: None,
+                penalty_destination: Some(destination.clone()),
+                force_unstake_penalty: None,
+                weights: None,
+            };
+            let contract_addr = app
+                .instantiate_contract(code_id, Addr::unchecked(ADMIN), &msg, &[], "test", None)
+                .unwrap();
+
+            app = deposit_and_stake(app, &contract_addr, "alice", 1_000);
+            app = deposit_and_stake(app, &contract_addr, "bob", 1_000);
+
+            // 10% of alice's stake is forfeited
+            app.execute_contract(
+                Addr::unchecked("alice"),
+                contract_addr.clone(),
+                &ExecuteMsg::ForceUnstake {},
+                &[],
+            )
+            .unwrap();
+
+            let pending: Uint128 = app
+                .wrap()
+                .query_wasm_smart(
+                    contract_addr.clone(),
+                    &QueryMsg::PendingRewards {
+                        user: "bob".to_string(),
+                    },
+                )
+                .unwrap();
+            let contract_balance = app
+                .wrap()
+                .query_balance(contract_addr.to_string(), DENOM)
+                .unwrap()
+                .amount;
+            let treasury_balance = app.wrap().query_balance("treasury", DENOM).unwrap().amount;
+
+            match destination {
+                PenaltyDestination::Contract => {
+                    assert_eq!(pending, Uint128::zero());
+                    assert_eq!(contract_balance, Uint128::new(2_000));
+                }
+                PenaltyDestination::Treasury { .. } => {
+                    assert_eq!(pending, Uint128::zero());
+                    assert_eq!(contract_balance, Uint128::new(1_900));
+                    assert_eq!(treasury_balance, Uint128::new(100));
+                }
+                PenaltyDestination::Redistribute => {
+                    assert_eq!(pending, Uint128::new(100));
+                    assert_eq!(contract_balance, Uint128::new(2_000));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn position() {
+        let mut app = App::default();
+        let code_id = app.store_code(challenge_contract());
+
+        let msg = InstantiateMsg {
+            denom: None,
+            reward_denom: None,
+            unbonding_period: Some(LOCK_PERIOD),
+            penalty_destination: None,
+            force_unstake_penalty: None,
+            weights: None,
+        };
+        let contract_addr = app
+            .instantiate_contract(code_id, Addr::unchecked(ADMIN), &msg, &[], "test", None)
+            .unwrap();
+
+        app = deposit_and_stake(app, &contract_addr, "alice", 1_000);
+        app = deposit_and_stake(app, &contract_addr, "bob", 1_000);
+
+        // alice's penalty accrues to bob
+        app.execute_contract(
+            Addr::unchecked("alice"),
+            contract_addr.clone(),
+            &ExecuteMsg::ForceUnstake {},
+            &[],
+        )
+        .unwrap();
+
+        app.update_block(|block| {
+            block.time = block.time.plus_seconds(LOCK_PERIOD);
+        });
+
+        app.execute_contract(
+            Addr::unchecked("bob"),
+            contract_addr.clone(),
+            &ExecuteMsg::Unstake { unlock_amount: 400 },
+            &[],
+        )
+        .unwrap();
+
+        let position: PositionResponse = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::Position {
+                    user: "bob".to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(position.staked, Uint128::new(600));
+        assert_eq!(position.unbonding, Uint128::new(400));
+
+        // the summary matches the individual queries
+        let user: UserInfo = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::GetUser {
+                    user: "bob".to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(position.total_tokens, user.total_tokens);
+        assert_eq!(position.unlock_time, user.released_time);
+        assert_eq!(position.unbonding, user.unbonding);
+
+        let voting_power: u128 = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::GetVotingPower {
+                    user: "bob".to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(position.voting_power, voting_power);
+
+        let pending: Uint128 = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::PendingRewards {
+                    user: "bob".to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(position.pending_rewards, pending);
+
+        let unbondings: UnbondingsResponse = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::Unbondings {
+                    user: "bob".to_string(),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(position.unbondings, unbondings.unbondings);
+
+        // unknown users get an empty position
+        let position: PositionResponse = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr,
+                &QueryMsg::Position {
+                    user: "carol".to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(position.total_tokens, Uint128::zero());
+        assert!(position.unbondings.is_empty());
+    }
+
+    #[test]
+    fn reward_denom() {
+        let mut app = App::default();
+        let code_id = app.store_code(challenge_contract());
+
+        // penalties in the staking denom cannot be paid out as rewards
+        let mut msg = InstantiateMsg {
+            denom: None,
+            reward_denom: Some("ureward".to_string()),
+            unbonding_period: None,
+            penalty_destination: Some(PenaltyDestination::Redistribute),
+            force_unstake_penalty: None,
+            weights: None,
+        };
+        let err = app
+            .instantiate_contract(code_id, Addr::unchecked(ADMIN), &msg, &[], "test", None)
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ContractError>(),
+            Some(ContractError::RewardDenomMismatch {})
+        ));
+
+        msg.penalty_destination = None;
+        let contract_addr = app
+            .instantiate_contract(code_id, Addr::unchecked(ADMIN), &msg, &[], "test", None)
+            .unwrap();
+
+        app = deposit_and_stake(app, &contract_addr, "alice", 1_000);
+        app = deposit_and_stake(app, &contract_addr, "bob", 3_000);
+
+        app.sudo(cw_multi_test::SudoMsg::Bank(
+            cw_multi_test::BankSudo::Mint {
+                to_address: ADMIN.to_string(),
+                amount: vec![coin(400, "ureward")],
+            },
+        ))
+        .unwrap();
+
+        // rewards are funded in the reward denom only
+        app = mint_tokens(app, ADMIN.to_string(), Uint128::new(400));
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            contract_addr.clone(),
+            &ExecuteMsg::FundRewards {},
+            &[coin(400, DENOM)],
+        )
+        .unwrap_err();
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            contract_addr.clone(),
+            &ExecuteMsg::FundRewards {},
+            &[coin(400, "ureward")],
+        )
+        .unwrap();
+
+        let pending: Uint128 = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::PendingRewards {
+                    user: "bob".to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(pending, Uint128::new(300));
+
+        let err = app
+            .execute_contract(
+                Addr::unchecked("alice"),
+                contract_addr.clone(),
+                &ExecuteMsg::Compound {},
+                &[],
+            )
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ContractError>(),
+            Some(ContractError::RewardDenomMismatch {})
+        ));
+
+        for (user, expected) in [("alice", 100), ("bob", 300)] {
+            app.execute_contract(
+                Addr::unchecked(user),
+                contract_addr.clone(),
+                &ExecuteMsg::ClaimRewards {},
+                &[],
+            )
+            .unwrap();
+
+            let balance = app.wrap().query_balance(user, "ureward").unwrap().amount;
+            assert_eq!(balance, Uint128::new(expected));
+        }
+
+        // stake is untouched by reward payouts
+        let balance = app
+            .wrap()
+            .query_balance(contract_addr.to_string(), DENOM)
+            .unwrap()
+            .amount;
+        assert_eq!(balance, Uint128::new(4_000));
+    }
+
+    #[test]
+    fn stake_upper_bound() {
+        let (mut app, contract_addr) = proper_instantiate();
+        app = mint_tokens(app, USER.to_string(), Uint128::new(1_000));
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::Deposit {},
+            &[coin(1_000, DENOM)],
+        )
+        .unwrap();
+
+        for lock_amount in [u128::MAX, 1_001] {
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(USER),
+                    contract_addr.clone(),
+                    &ExecuteMsg::Stake { lock_amount },
+                    &[],
+                )
+                .unwrap_err();
+            assert!(matches!(
+                err.downcast_ref::<ContractError>(),
+                Some(ContractError::StakeExceedsBalance { available }) if available.u128() == 1_000
+            ));
+        }
+
+        let user: UserInfo = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::GetUser {
+                    user: USER.to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(user.voting_power, 0);
+
+        // the bound accounts for tokens already staked
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::Stake { lock_amount: 400 },
+            &[],
+        )
+        .unwrap();
+        let err = app
+            .execute_contract(
+                Addr::unchecked(USER),
+                contract_addr.clone(),
+                &ExecuteMsg::Stake { lock_amount: 601 },
+                &[],
+            )
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ContractError>(),
+            Some(ContractError::StakeExceedsBalance { available }) if available.u128() == 600
+        ));
+
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::Stake { lock_amount: 600 },
+            &[],
+        )
+        .unwrap();
+
+        let user: UserInfo = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr,
+                &QueryMsg::GetUser {
+                    user: USER.to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(user.voting_power, 1_000);
+    }
+
+    #[test]
+    fn force_unstake_penalty() {
+        let mut app = App::default();
+        let code_id = app.store_code(challenge_contract());
+
+        // the whole stake cannot be forfeited
+        let mut msg = InstantiateMsg {
+            denom: None,
+            reward_denom: None,
+            unbonding_period: None,
+            penalty_destination: Some(PenaltyDestination::Contract),
+            force_unstake_penalty: Some(Decimal::one()),
+            weights: None,
+        };
+        let err = app
+            .instantiate_contract(code_id, Addr::unchecked(ADMIN), &msg, &[], "test", None)
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ContractError>(),
+            Some(ContractError::InvalidPenalty {})
+        ));
+
+        msg.force_unstake_penalty = Some(Decimal::percent(25));
+        let contract_addr = app
+            .instantiate_contract(code_id, Addr::unchecked(ADMIN), &msg, &[], "test", None)
+            .unwrap();
+
+        app = deposit_and_stake(app, &contract_addr, USER, 1_000);
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::ForceUnstake {},
+            &[],
+        )
+        .unwrap();
+
+        let user: UserInfo = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr,
+                &QueryMsg::GetUser {
+                    user: USER.to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(user.voting_power, 0);
+        assert_eq!(user.total_tokens, Uint128::new(750));
+    }
 }
@@ -1,17 +1,57 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::Uint128;
+use cosmwasm_std::{Decimal, Timestamp, Uint128};
+use ctf_common::msg::ProtocolConfigResponse;
+use ctf_common::penalty::PenaltyDestination;
+use std::collections::BTreeMap;
 
-use crate::state::UserInfo;
+use crate::state::{Unbonding, UserInfo};
 
 #[cw_serde]
-pub struct InstantiateMsg {}
+pub struct InstantiateMsg {
+    /// Staking denom, defaults to `uawesome`. TokenFactory denoms are given in full,
+    /// e.g. `factory/<creator>/<subdenom>`
+    pub denom: Option<String>,
+    /// Denom rewards are funded and paid in, defaults to the staking denom
+    pub reward_denom: Option<String>,
+    /// Cooldown in seconds between `Unstake` and `ClaimUnbonded`,
+    /// unstaked tokens are released immediately when unset
+    pub unbonding_period: Option<u64>,
+    /// Defaults to redistributing force unstake penalties to the remaining stakers,
+    /// or keeping them in the contract when rewards are paid in another denom
+    pub penalty_destination: Option<PenaltyDestination>,
+    /// Share of the stake forfeited by `ForceUnstake`, defaults to 10%
+    pub force_unstake_penalty: Option<Decimal>,
+    /// Additional denoms that can be staked through `StakeDenom`, with the voting power
+    /// each token grants. The staking and reward denoms cannot be weighted
+    pub weights: Option<BTreeMap<String, Decimal>>,
+}
 
 #[cw_serde]
 pub enum ExecuteMsg {
     Deposit {},
-    Withdraw { amount: Uint128 },
-    Stake { lock_amount: u128 },
-    Unstake { unlock_amount: u128 },
+    Withdraw {
+        amount: Uint128,
+        recipient: Option<String>,
+    },
+    Stake {
+        lock_amount: u128,
+    },
+    Unstake {
+        unlock_amount: u128,
+    },
+    ForceUnstake {},
+    /// Stakes the attached weighted denom, voting power is granted by its weight
+    StakeDenom {},
+    UnstakeDenom {
+        denom: String,
+        amount: Uint128,
+    },
+    ClaimRewards {},
+    /// Only available while rewards are paid in the staking denom
+    Compound {},
+    /// Distributes the attached reward denom to current stakers by voting power
+    FundRewards {},
+    ClaimUnbonded {},
 }
 
 #[cw_serde]
@@ -22,4 +62,65 @@ pub enum QueryMsg {
 
     #[returns(u128)]
     GetVotingPower { user: String },
+
+    #[returns(StakerRankResponse)]
+    StakerRank { user: String },
+
+    #[returns(Uint128)]
+    PendingRewards { user: String },
+
+    /// Pages through the unbondings in the order they are released
+    #[returns(UnbondingsResponse)]
+    Unbondings {
+        user: String,
+        start_after: Option<UnbondingCursor>,
+        limit: Option<u32>,
+    },
+
+    #[returns(ProtocolConfigResponse)]
+    ProtocolConfig {},
+
+    #[returns(PositionResponse)]
+    Position { user: String },
+}
+
+#[cw_serde]
+pub struct StakerRankResponse {
+    /// 1-based position by voting power, `None` if the user has nothing staked
+    /// or ranks below `MAX_RANKED_STAKERS`
+    pub rank: Option<u64>,
+    /// Number of users with voting power
+    pub total_stakers: u64,
+}
+
+/// Identifies an unbonding by its release time and denom, the last one of a page
+/// is passed back as `start_after` to fetch the next one
+#[cw_serde]
+pub struct UnbondingCursor {
+    pub release_time: Timestamp,
+    pub denom: String,
+}
+
+#[cw_serde]
+pub struct UnbondingsResponse {
+    /// Unbondings of every denom ordered by release time, including the ones ready to claim
+    pub unbondings: Vec<Unbonding>,
+}
+
+#[cw_serde]
+pub struct PositionResponse {
+    /// Tokens deposited, staked or not
+    pub total_tokens: Uint128,
+    /// Tokens locked in the stake
+    pub staked: Uint128,
+    /// Voting power granted by the stake
+    pub voting_power: u128,
+    /// Time the stake can be unstaked without penalty
+    pub unlock_time: Timestamp,
+    /// Rewards accrued up to now, including the ones not yet checkpointed
+    pub pending_rewards: Uint128,
+    /// Staking denom amount still unbonding
+    pub unbonding: Uint128,
+    /// Earliest unbondings of every denom, capped at `MAX_POSITION_UNBONDINGS`
+    pub unbondings: Vec<Unbonding>,
 }
@@ -1,6 +1,11 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Timestamp, Uint128};
-use cw_storage_plus::Map;
+use cosmwasm_std::{
+    Addr, Decimal, Empty, OverflowError, OverflowOperation, StdError, StdResult, Storage,
+    Timestamp, Uint128,
+};
+use ctf_common::penalty::PenaltyDestination;
+use cw_storage_plus::{Item, Map};
+use std::collections::BTreeMap;
 
 #[cw_serde]
 #[derive(Default)]
@@ -9,8 +14,109 @@ pub struct UserInfo {
     pub total_tokens: Uint128,
     /// User voting power
     pub voting_power: u128,
+    /// Part of the voting power granted by weighted denom stakes
+    pub weighted_power: u128,
     /// Release time to withdraw staked tokens
     pub released_time: Timestamp,
+    /// Reward index at the user's last update
+    pub reward_index: Decimal,
+    /// Rewards accrued but not yet claimed
+    pub pending_rewards: Uint128,
+    /// Unstaked tokens waiting for the unbonding period to elapse
+    pub unbonding: Uint128,
 }
 
+#[cw_serde]
+pub struct Config {
+    /// Denom accepted for deposits and paid out on withdrawals
+    pub denom: String,
+    /// Denom rewards are paid in
+    pub reward_denom: String,
+    /// Cooldown in seconds before unstaked tokens can be claimed
+    pub unbonding_period: Option<u64>,
+    /// Where force unstake penalties go
+    pub penalty_destination: PenaltyDestination,
+    /// Share of the stake forfeited by force unstaking
+    pub force_unstake_penalty: Decimal,
+    /// Voting power per token of each additional stakeable denom,
+    /// the staking denom always counts one to one
+    pub weights: BTreeMap<String, Decimal>,
+}
+
+impl UserInfo {
+    /// Staking denom tokens locked in the stake
+    pub fn staked(&self) -> u128 {
+        self.voting_power.saturating_sub(self.weighted_power)
+    }
+}
+
+#[cw_serde]
+pub struct Unbonding {
+    pub denom: String,
+    pub amount: Uint128,
+    pub release_time: Timestamp,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct DenomStake {
+    /// Tokens of the denom staked
+    pub amount: Uint128,
+    /// Voting power the tokens were granted when staked
+    pub power: u128,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct RewardState {
+    /// Total voting power across all stakers
+    pub total_staked: u128,
+    /// Rewards distributed per unit of voting power
+    pub global_index: Decimal,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
 pub const VOTING_POWER: Map<&Addr, UserInfo> = Map::new("voting_power");
+pub const REWARD_STATE: Item<RewardState> = Item::new("reward_state");
+
+/// Pending unbondings keyed by user and release time in seconds
+pub const UNBONDINGS: Map<(&Addr, u64), Uint128> = Map::new("unbondings");
+
+/// Weighted denom stakes keyed by user and denom
+pub const DENOM_STAKES: Map<(&Addr, &str), DenomStake> = Map::new("denom_stakes");
+/// Pending weighted denom unbondings keyed by user, release time in seconds and denom
+pub const DENOM_UNBONDINGS: Map<(&Addr, u64, &str), Uint128> = Map::new("denom_unbondings");
+
+/// Stakers keyed by voting power, used to rank users without sorting on read
+pub const STAKERS_BY_AMOUNT: Map<(u128, &Addr), Empty> = Map::new("stakers_by_amount");
+/// Number of users with non-zero voting power
+pub const TOTAL_STAKERS: Item<u64> = Item::new("total_stakers");
+
+/// Moves a user's entry in the staking index after their voting power changes
+pub fn update_stake_index(
+    store: &mut dyn Storage,
+    user: &Addr,
+    old_power: u128,
+    new_power: u128,
+) -> StdResult<()> {
+    if old_power == new_power {
+        return Ok(());
+    }
+
+    let mut total_stakers = TOTAL_STAKERS.may_load(store)?.unwrap_or_default();
+
+    if old_power > 0 {
+        STAKERS_BY_AMOUNT.remove(store, (old_power, user));
+        total_stakers = total_stakers.checked_sub(1).ok_or_else(|| {
+            StdError::overflow(OverflowError::new(OverflowOperation::Sub, total_stakers, 1))
+        })?;
+    }
+    if new_power > 0 {
+        STAKERS_BY_AMOUNT.save(store, (new_power, user), &Empty {})?;
+        total_stakers = total_stakers.checked_add(1).ok_or_else(|| {
+            StdError::overflow(OverflowError::new(OverflowOperation::Add, total_stakers, 1))
+        })?;
+    }
+
+    TOTAL_STAKERS.save(store, &total_stakers)
+}
@@ -1,29 +1,66 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    coins, to_binary, BankMsg, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
-    Uint128,
+    coins, to_binary, Addr, BankMsg, Binary, Decimal, Deps, DepsMut, Empty, Env, MessageInfo,
+    Order, Response, StdResult, Storage, Uint128,
 };
-use cw_utils::must_pay;
+use ctf_common::pause::PauseState;
+use ctf_common::payment::must_pay_exact;
 
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
-use crate::state::{Balance, Config, BALANCES, CONFIG};
+use crate::msg::{
+    ExchangeRateResponse, ExecuteMsg, InstantiateMsg, QueryMsg, SolvencyReportResponse,
+};
+use crate::state::{assert_admin, Balance, Config, ALLOWED, BALANCES, CONFIG, TOTAL_DEPOSITS};
 
 pub const DENOM: &str = "uawesome";
+pub const MAX_OFFSET: u64 = 18;
+/// Keeps scaled share amounts well within `Uint128`
+pub const MAX_SHARE_DECIMALS: u64 = 12;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
     _env: Env,
-    _info: MessageInfo,
-    _msg: InstantiateMsg,
+    info: MessageInfo,
+    msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
+    let fee_admin = match msg.fee_admin {
+        Some(fee_admin) => deps.api.addr_validate(&fee_admin)?,
+        None => info.sender.clone(),
+    };
+
+    let mint_fee = msg.mint_fee.unwrap_or_default();
+    if mint_fee >= Decimal::one() {
+        return Err(ContractError::InvalidFee {});
+    }
+
+    if msg.offset > MAX_OFFSET {
+        return Err(ContractError::InvalidOffset { max: MAX_OFFSET });
+    }
+
+    let share_decimals = msg.share_decimals.unwrap_or_default();
+    if share_decimals > MAX_SHARE_DECIMALS {
+        return Err(ContractError::InvalidShareDecimals {
+            max: MAX_SHARE_DECIMALS,
+        });
+    }
+
     let config = Config {
         total_supply: Uint128::zero(),
+        admin: info.sender,
+        fee_admin,
+        mint_fee,
+        accrued_fees: Uint128::zero(),
+        shutdown: false,
+        min_first_deposit: msg.min_first_deposit.unwrap_or_default(),
+        offset: msg.offset as u32,
+        share_decimals: share_decimals as u32,
+        pause: PauseState::default(),
     };
 
     CONFIG.save(deps.storage, &config)?;
+    TOTAL_DEPOSITS.save(deps.storage, &Uint128::zero())?;
     Ok(Response::new().add_attribute("action", "instantiate"))
 }
 
@@ -37,50 +74,96 @@ pub fn execute(
     match msg {
         ExecuteMsg::Mint {} => mint(deps, env, info),
         ExecuteMsg::Burn { shares } => burn(deps, env, info, shares),
+        ExecuteMsg::BurnAll {} => burn_all(deps, env, info),
+        ExecuteMsg::WithdrawProtocolFees { amount } => withdraw_protocol_fees(deps, info, amount),
+        ExecuteMsg::AddAllowed { address } => add_allowed(deps, info, address),
+        ExecuteMsg::RemoveAllowed { address } => remove_allowed(deps, info, address),
+        ExecuteMsg::Shutdown {} => shutdown(deps, info),
+        ExecuteMsg::SetPause {
+            deposits_paused,
+            withdrawals_paused,
+        } => set_pause(deps, info, deposits_paused, withdrawals_paused),
     }
 }
 
 /// Entry point for users to mint shares
 pub fn mint(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
-    let amount = must_pay(&info, DENOM).unwrap();
+    // any other coin attached would be stranded in the vault
+    let amount = must_pay_exact(&info, DENOM)?;
 
     let mut config = CONFIG.load(deps.storage).unwrap();
 
+    if config.shutdown {
+        return Err(ContractError::VaultShutdown {});
+    }
+    config.pause.assert_deposits_open()?;
+
+    if !is_allowed(deps.storage, &info.sender)? {
+        return Err(ContractError::NotAllowed {});
+    }
+
     let contract_balance = deps
         .querier
         .query_balance(env.contract.address.to_string(), DENOM)
         .unwrap();
 
-    let total_assets = contract_balance.amount - amount;
+    // protocol fees never back shares
+    let total_assets = contract_balance
+        .amount
+        .checked_sub(amount)?
+        .checked_sub(config.accrued_fees)?;
     let total_supply = config.total_supply;
 
+    // deduct mint fee
+    let fee = amount * config.mint_fee;
+    let amount = amount.checked_sub(fee)?;
+
+    // a dust bootstrap would make share rounding exploitable
+    if total_supply.is_zero() && amount < config.min_first_deposit {
+        return Err(ContractError::FirstDepositTooSmall {
+            minimum: config.min_first_deposit,
+        });
+    }
+
     // share = asset * total supply / total assets
-    let mint_amount = if total_supply.is_zero() {
-        amount
+    let scale = share_scale(&config);
+    let virtual_offset = virtual_offset(&config);
+    let price = share_price(total_assets, total_supply, virtual_offset, scale)?;
+    let mint_amount = if total_supply.is_zero() && virtual_offset.is_zero() {
+        amount.checked_mul(scale)?
     } else {
-        amount.multiply_ratio(total_supply, total_assets)
+        amount.multiply_ratio(
+            total_supply.checked_add(virtual_offset.checked_mul(scale)?)?,
+            total_assets.checked_add(virtual_offset)?,
+        )
     };
 
     if mint_amount.is_zero() {
-        return Err(ContractError::ZeroAmountNotAllowed {});
+        return Err(ContractError::ZeroAmount {});
     }
 
     // increase total supply
-    config.total_supply += mint_amount;
+    config.total_supply = config.total_supply.checked_add(mint_amount)?;
+    config.accrued_fees = config.accrued_fees.checked_add(fee)?;
     CONFIG.save(deps.storage, &config)?;
+    TOTAL_DEPOSITS.update(deps.storage, |total| -> StdResult<_> {
+        Ok(total.checked_add(amount)?)
+    })?;
 
     // increase user balance
     let mut user = BALANCES
         .load(deps.storage, &info.sender)
         .unwrap_or_default();
-    user.amount += mint_amount;
+    user.amount = user.amount.checked_add(mint_amount)?;
     BALANCES.save(deps.storage, &info.sender, &user)?;
 
     Ok(Response::new()
         .add_attribute("action", "mint")
         .add_attribute("user", info.sender.to_string())
-        .add_attribute("asset", amount.to_string())
-        .add_attribute("shares", mint_amount.to_string()))
+        .add_attribute("assets", amount.to_string())
+        .add_attribute("fee", fee.to_string())
+        .add_attribute("shares", mint_amount.to_string())
+        .add_attribute("share_price", price.to_string()))
 }
 
 /// Entry point for users to burn shares
@@ -91,30 +174,50 @@ pub fn burn(
     shares: Uint128,
 ) -> Result<Response, ContractError> {
     let mut config = CONFIG.load(deps.storage).unwrap();
+    config.pause.assert_withdrawals_open()?;
 
     let contract_balance = deps
         .querier
         .query_balance(env.contract.address.to_string(), DENOM)
         .unwrap();
 
-    let total_assets = contract_balance.amount;
+    let total_assets = contract_balance.amount.checked_sub(config.accrued_fees)?;
     let total_supply = config.total_supply;
 
     // asset = share * total assets / total supply
-    let asset_to_return = shares.multiply_ratio(total_assets, total_supply);
+    let scale = share_scale(&config);
+    let virtual_offset = virtual_offset(&config);
+    let price = share_price(total_assets, total_supply, virtual_offset, scale)?;
+    let asset_to_return = shares.multiply_ratio(
+        total_assets.checked_add(virtual_offset)?,
+        total_supply.checked_add(virtual_offset.checked_mul(scale)?)?,
+    );
 
     if asset_to_return.is_zero() {
-        return Err(ContractError::ZeroAmountNotAllowed {});
+        return Err(ContractError::ZeroAmount {});
     }
 
     // decrease total supply
-    config.total_supply -= shares;
+    config.total_supply = config.total_supply.checked_sub(shares)?;
     CONFIG.save(deps.storage, &config)?;
 
+    // burns can pay out more than was deposited once the share price has risen,
+    // and nothing is owed once every share is burned
+    TOTAL_DEPOSITS.update(deps.storage, |total| -> StdResult<_> {
+        if config.total_supply.is_zero() {
+            return Ok(Uint128::zero());
+        }
+        Ok(total.saturating_sub(asset_to_return))
+    })?;
+
     // decrease user balance
     let mut user = BALANCES.load(deps.storage, &info.sender)?;
-    user.amount -= shares;
-    BALANCES.save(deps.storage, &info.sender, &user)?;
+    user.amount = user.amount.checked_sub(shares)?;
+    if user.amount.is_zero() {
+        BALANCES.remove(deps.storage, &info.sender);
+    } else {
+        BALANCES.save(deps.storage, &info.sender, &user)?;
+    }
 
     let msg = BankMsg::Send {
         to_address: info.sender.to_string(),
@@ -124,16 +227,183 @@ pub fn burn(
     Ok(Response::new()
         .add_attribute("action", "burn")
         .add_attribute("user", info.sender.to_string())
-        .add_attribute("asset", asset_to_return.to_string())
+        .add_attribute("assets", asset_to_return.to_string())
         .add_attribute("shares", shares.to_string())
+        .add_attribute("share_price", price.to_string())
+        .add_message(msg))
+}
+
+/// Entry point for users to burn their whole share balance
+pub fn burn_all(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let shares = BALANCES
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default()
+        .amount;
+
+    if shares.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+
+    burn(deps, env, info, shares)
+}
+
+/// Entry point for fee admin to withdraw accrued protocol fees
+pub fn withdraw_protocol_fees(
+    deps: DepsMut,
+    info: MessageInfo,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if config.fee_admin != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+
+    // only the accrued fees can leave, never depositor principal
+    if amount > config.accrued_fees {
+        return Err(ContractError::InsufficientFees {
+            accrued: config.accrued_fees,
+        });
+    }
+
+    config.accrued_fees = config.accrued_fees.checked_sub(amount)?;
+    CONFIG.save(deps.storage, &config)?;
+
+    let msg = BankMsg::Send {
+        to_address: info.sender.to_string(),
+        amount: coins(amount.u128(), DENOM),
+    };
+
+    Ok(Response::new()
+        .add_attribute("action", "withdraw_protocol_fees")
+        .add_attribute("amount", amount.to_string())
         .add_message(msg))
 }
 
+/// Entry point for admin to allow an address to mint shares
+pub fn add_allowed(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+) -> Result<Response, ContractError> {
+    assert_admin(deps.storage, &info.sender)?;
+
+    let address = deps.api.addr_validate(&address)?;
+    ALLOWED.save(deps.storage, &address, &Empty {})?;
+
+    Ok(Response::new()
+        .add_attribute("action", "add_allowed")
+        .add_attribute("address", address.to_string()))
+}
+
+/// Entry point for admin to remove an address from the allowlist
+pub fn remove_allowed(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+) -> Result<Response, ContractError> {
+    assert_admin(deps.storage, &info.sender)?;
+
+    let address = deps.api.addr_validate(&address)?;
+    ALLOWED.remove(deps.storage, &address);
+
+    Ok(Response::new()
+        .add_attribute("action", "remove_allowed")
+        .add_attribute("address", address.to_string()))
+}
+
+/// Entry point for admin to permanently stop new deposits
+pub fn shutdown(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let mut config = assert_admin(deps.storage, &info.sender)?;
+
+    config.shutdown = true;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "shutdown"))
+}
+
+/// Entry point for admin to pause mints and burns
+pub fn set_pause(
+    deps: DepsMut,
+    info: MessageInfo,
+    deposits_paused: bool,
+    withdrawals_paused: bool,
+) -> Result<Response, ContractError> {
+    let mut config = assert_admin(deps.storage, &info.sender)?;
+
+    config.pause = PauseState {
+        deposits_paused,
+        withdrawals_paused,
+    };
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_pause")
+        .add_attribute("deposits_paused", deposits_paused.to_string())
+        .add_attribute("withdrawals_paused", withdrawals_paused.to_string()))
+}
+
+/// Virtual assets added to the share price, matched by as many virtual shares
+/// times the share scale. Donations then barely move the price
+#[cfg(feature = "fixed")]
+fn virtual_offset(config: &Config) -> Uint128 {
+    Uint128::new(10u128.pow(config.offset))
+}
+
+/// The challenge build prices shares from the raw totals
+#[cfg(not(feature = "fixed"))]
+fn virtual_offset(_config: &Config) -> Uint128 {
+    Uint128::zero()
+}
+
+/// Shares minted per asset unit by the first mint
+fn share_scale(config: &Config) -> Uint128 {
+    Uint128::new(10u128.pow(config.share_decimals))
+}
+
+/// Assets per share before the operation is applied, the first mint is priced at the share scale.
+/// Saturates at `Decimal::MAX` so a price too large to represent never fails the operation
+fn share_price(
+    total_assets: Uint128,
+    total_supply: Uint128,
+    virtual_offset: Uint128,
+    scale: Uint128,
+) -> StdResult<Decimal> {
+    let effective_supply = total_supply.checked_add(virtual_offset.checked_mul(scale)?)?;
+    if effective_supply.is_zero() {
+        return Ok(Decimal::from_ratio(1u128, scale));
+    }
+
+    Ok(
+        Decimal::checked_from_ratio(total_assets.checked_add(virtual_offset)?, effective_supply)
+            .unwrap_or(Decimal::MAX),
+    )
+}
+
+/// The vault is open to everyone unless the allowlist has entries
+fn is_allowed(store: &dyn Storage, address: &Addr) -> StdResult<bool> {
+    if ALLOWED.has(store, address) {
+        return Ok(true);
+    }
+    let empty = ALLOWED
+        .keys(store, None, None, Order::Ascending)
+        .next()
+        .is_none();
+    Ok(empty)
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::GetConfig {} => to_binary(&query_config(deps)?),
         QueryMsg::UserBalance { address } => to_binary(&query_user(deps, address)?),
+        QueryMsg::IsAllowed { address } => to_binary(&query_is_allowed(deps, address)?),
+        QueryMsg::SolvencyReport {} => to_binary(&query_solvency_report(deps, env)?),
+        QueryMsg::ExchangeRate {} => to_binary(&query_exchange_rate(deps, env)?),
     }
 }
 
@@ -147,3 +417,53 @@ pub fn query_user(deps: Deps, address: String) -> StdResult<Balance> {
     let balance = BALANCES.load(deps.storage, &user).unwrap_or_default();
     Ok(balance)
 }
+
+pub fn query_is_allowed(deps: Deps, address: String) -> StdResult<bool> {
+    let address = deps.api.addr_validate(&address)?;
+    is_allowed(deps.storage, &address)
+}
+
+/// Returns the vault balance compared to the net deposits and fees.
+/// Liabilities are tracked independently of the balance, so donations show up as free funds
+pub fn query_solvency_report(deps: Deps, env: Env) -> StdResult<SolvencyReportResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let accounted_liabilities = TOTAL_DEPOSITS
+        .load(deps.storage)?
+        .checked_add(config.accrued_fees)?;
+
+    let actual_balance = deps
+        .querier
+        .query_balance(env.contract.address, DENOM)?
+        .amount;
+
+    Ok(SolvencyReportResponse {
+        accounted_liabilities,
+        actual_balance,
+        free: actual_balance.saturating_sub(accounted_liabilities),
+        shortfall: accounted_liabilities.saturating_sub(actual_balance),
+    })
+}
+
+/// Returns the assets per share `Mint` and `Burn` apply along with the nominal totals.
+/// Only the effective rate determines what users receive
+pub fn query_exchange_rate(deps: Deps, env: Env) -> StdResult<ExchangeRateResponse> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let contract_balance = deps
+        .querier
+        .query_balance(env.contract.address, DENOM)?
+        .amount;
+    let total_assets = contract_balance.checked_sub(config.accrued_fees)?;
+
+    let scale = share_scale(&config);
+    let virtual_offset = virtual_offset(&config);
+    let effective_rate = share_price(total_assets, config.total_supply, virtual_offset, scale)?;
+
+    Ok(ExchangeRateResponse {
+        effective_rate,
+        total_assets,
+        total_supply: config.total_supply,
+        virtual_offset,
+        share_scale: scale,
+    })
+}
@@ -1,4 +1,6 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{OverflowError, StdError, Uint128};
+use ctf_common::pause::PauseError;
+use ctf_common::payment::PaymentError;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -6,9 +8,39 @@ pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
 
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
+    #[error("{0}")]
+    Payment(#[from] PaymentError),
+
+    #[error("{0}")]
+    Pause(#[from] PauseError),
+
     #[error("Unauthorized")]
     Unauthorized {},
 
     #[error("Zero amount is not allowed")]
-    ZeroAmountNotAllowed {},
+    ZeroAmount {},
+
+    #[error("Address is not allowed to obtain shares")]
+    NotAllowed {},
+
+    #[error("Vault is shut down")]
+    VaultShutdown {},
+
+    #[error("Fee must be lower than 100%")]
+    InvalidFee {},
+
+    #[error("Offset must be at most {max}")]
+    InvalidOffset { max: u64 },
+
+    #[error("Share decimals must be at most {max}")]
+    InvalidShareDecimals { max: u64 },
+
+    #[error("First deposit must be at least {minimum}")]
+    FirstDepositTooSmall { minimum: Uint128 },
+
+    #[error("Only {accrued} in protocol fees available")]
+    InsufficientFees { accrued: Uint128 },
 }
@@ -2,11 +2,15 @@
 pub mod tests {
     use crate::{
         contract::DENOM,
-        msg::{ExecuteMsg, InstantiateMsg, QueryMsg},
-        state::Balance,
+        msg::{ExchangeRateResponse, ExecuteMsg, InstantiateMsg, QueryMsg, SolvencyReportResponse},
+        state::{Balance, Config, BALANCES},
+        ContractError,
     };
-    use cosmwasm_std::{coin, Addr, Empty, Uint128};
-    use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+    use cosmwasm_std::{coin, Addr, Decimal, Empty, Uint128};
+    use ctf_common::payment::PaymentError;
+    use cw_multi_test::{App, AppResponse, Contract, ContractWrapper, Executor};
+    use insta::assert_json_snapshot;
+    use std::str::FromStr;
 
     pub fn challenge_contract() -> Box<dyn Contract<Empty>> {
         let contract = ContractWrapper::new(
@@ -26,7 +30,13 @@ pub mod tests {
         let cw_template_id = app.store_code(challenge_contract());
 
         // init contract
-        let msg = InstantiateMsg { offset: 10 };
+        let msg = InstantiateMsg {
+            offset: 10,
+            fee_admin: None,
+            mint_fee: None,
+            min_first_deposit: None,
+            share_decimals: None,
+        };
         let contract_addr = app
             .instantiate_contract(
                 cw_template_id,
@@ -128,6 +138,7 @@ pub mod tests {
 
     // This demonstrates how an unprivileged user can withdraw more funds than deposited
     #[test]
+    #[cfg(not(feature = "fixed"))]
     fn left_unchecked() {
         let (mut app, contract_addr) = proper_instantiate();
 
@@ -193,4 +204,894 @@ pub mod tests {
         assert_eq!(user_bal.amount, Uint128::from(10_165u128));
     }
 
+    #[test]
+    fn protocol_fees() {
+        let mut app = App::default();
+        let cw_template_id = app.store_code(challenge_contract());
+
+        // init contract with a 1% mint fee
+        let msg = InstantiateMsg {
+            offset: 10,
+            fee_admin: Some(ADMIN.to_string()),
+            mint_fee: Some(Decimal::percent(1)),
+            min_first_deposit: None,
+            share_decimals: None,
+        };
+        let contract_addr = app
+            .instantiate_contract(
+                cw_template_id,
+                Addr::unchecked(ADMIN),
+                &msg,
+                &[],
+                "test",
+                None,
+            )
+            .unwrap();
+
+        app = mint_tokens(app, USER.to_owned(), Uint128::new(10_000));
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::Mint {},
+            &[coin(10_000, DENOM)],
+        )
+        .unwrap();
+
+        let balance: Balance = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::UserBalance {
+                    address: USER.to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(balance.amount, Uint128::new(9_900));
+
+        // only the fee admin can withdraw fees
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::WithdrawProtocolFees {
+                amount: Uint128::new(100),
+            },
+            &[],
+        )
+        .unwrap_err();
+
+        // cannot withdraw more than accrued
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            contract_addr.clone(),
+            &ExecuteMsg::WithdrawProtocolFees {
+                amount: Uint128::new(101),
+            },
+            &[],
+        )
+        .unwrap_err();
+
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            contract_addr.clone(),
+            &ExecuteMsg::WithdrawProtocolFees {
+                amount: Uint128::new(100),
+            },
+            &[],
+        )
+        .unwrap();
+
+        let bal = app.wrap().query_balance(ADMIN, DENOM).unwrap();
+        assert_eq!(bal.amount, Uint128::new(100));
+
+        // depositor principal is untouched
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr,
+            &ExecuteMsg::Burn {
+                shares: balance.amount,
+            },
+            &[],
+        )
+        .unwrap();
+
+        let bal = app.wrap().query_balance(USER, DENOM).unwrap();
+        assert_eq!(bal.amount, Uint128::new(9_900));
+    }
+
+    #[test]
+    fn depositor_allowlist() {
+        let (mut app, contract_addr) = proper_instantiate();
+
+        app = mint_tokens(app, USER.to_owned(), Uint128::new(10_000));
+        app = mint_tokens(app, USER2.to_owned(), Uint128::new(10_000));
+
+        // vault is open while the allowlist is empty
+        let allowed: bool = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::IsAllowed {
+                    address: USER2.to_string(),
+                },
+            )
+            .unwrap();
+        assert!(allowed);
+
+        // only admin can manage the allowlist
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::AddAllowed {
+                address: USER.to_string(),
+            },
+            &[],
+        )
+        .unwrap_err();
+
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            contract_addr.clone(),
+            &ExecuteMsg::AddAllowed {
+                address: USER.to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::Mint {},
+            &[coin(5_000, DENOM)],
+        )
+        .unwrap();
+
+        // user2 is not approved
+        app.execute_contract(
+            Addr::unchecked(USER2),
+            contract_addr.clone(),
+            &ExecuteMsg::Mint {},
+            &[coin(5_000, DENOM)],
+        )
+        .unwrap_err();
+
+        // removing the last entry opens the vault again
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            contract_addr.clone(),
+            &ExecuteMsg::RemoveAllowed {
+                address: USER.to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+
+        app.execute_contract(
+            Addr::unchecked(USER2),
+            contract_addr,
+            &ExecuteMsg::Mint {},
+            &[coin(5_000, DENOM)],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn shutdown_allows_redemptions() {
+        let (mut app, contract_addr) = proper_instantiate();
+
+        app = mint_tokens(app, USER.to_owned(), Uint128::new(10_000));
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::Mint {},
+            &[coin(5_000, DENOM)],
+        )
+        .unwrap();
+
+        // only admin can shut the vault down
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::Shutdown {},
+            &[],
+        )
+        .unwrap_err();
+
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            contract_addr.clone(),
+            &ExecuteMsg::Shutdown {},
+            &[],
+        )
+        .unwrap();
+
+        // new deposits are rejected
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::Mint {},
+            &[coin(5_000, DENOM)],
+        )
+        .unwrap_err();
+
+        // users can still exit
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr,
+            &ExecuteMsg::Burn {
+                shares: Uint128::new(5_000),
+            },
+            &[],
+        )
+        .unwrap();
+
+        let bal = app.wrap().query_balance(USER, DENOM).unwrap();
+        assert_eq!(bal.amount, Uint128::new(10_000));
+    }
+
+    #[test]
+    fn zero_mint_rejected() {
+        let (mut app, contract_addr) = proper_instantiate();
+
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::Mint {},
+            &[],
+        )
+        .unwrap_err();
+
+        let config: Config = app
+            .wrap()
+            .query_wasm_smart(contract_addr, &QueryMsg::GetConfig {})
+            .unwrap();
+        assert_eq!(config.total_supply, Uint128::zero());
+    }
+
+    #[test]
+    fn solvency_report() {
+        let (mut app, contract_addr) = proper_instantiate();
+
+        app = mint_tokens(app, USER.to_owned(), Uint128::new(10_000));
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::Mint {},
+            &[coin(10_000, DENOM)],
+        )
+        .unwrap();
+
+        // donation inflates the vault beyond the deposits
+        app = mint_tokens(app, contract_addr.to_string(), Uint128::new(1_000));
+
+        let report: SolvencyReportResponse = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::SolvencyReport {})
+            .unwrap();
+        assert_eq!(report.accounted_liabilities, Uint128::new(10_000));
+        assert_eq!(report.actual_balance, Uint128::new(11_000));
+        assert_eq!(report.free, Uint128::new(1_000));
+        assert_eq!(report.shortfall, Uint128::zero());
+
+        // nothing is owed once every share is burned
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::BurnAll {},
+            &[],
+        )
+        .unwrap();
+
+        let report: SolvencyReportResponse = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::SolvencyReport {})
+            .unwrap();
+        assert_eq!(report.accounted_liabilities, Uint128::zero());
+        assert_eq!(report.shortfall, Uint128::zero());
+
+        let balance = app
+            .wrap()
+            .query_balance(contract_addr.to_string(), DENOM)
+            .unwrap();
+        assert_eq!(report.free, balance.amount);
+    }
+
+    #[test]
+    fn query_snapshots() {
+        let (mut app, contract_addr) = proper_instantiate();
+
+        app = mint_tokens(app, USER.to_owned(), Uint128::new(10_000));
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::Mint {},
+            &[coin(10_000, DENOM)],
+        )
+        .unwrap();
+
+        let config: Config = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::GetConfig {})
+            .unwrap();
+        assert_json_snapshot!("config", config);
+
+        let msg = QueryMsg::UserBalance {
+            address: USER.to_owned(),
+        };
+        let balance: Balance = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &msg)
+            .unwrap();
+        assert_json_snapshot!("user_balance", balance);
+
+        let msg = QueryMsg::IsAllowed {
+            address: USER.to_owned(),
+        };
+        let is_allowed: bool = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &msg)
+            .unwrap();
+        assert_json_snapshot!("is_allowed", is_allowed);
+
+        let report: SolvencyReportResponse = app
+            .wrap()
+            .query_wasm_smart(contract_addr, &QueryMsg::SolvencyReport {})
+            .unwrap();
+        assert_json_snapshot!("solvency_report", report);
+    }
+
+    #[test]
+    fn burn_underflow_rejected() {
+        let (mut app, contract_addr) = proper_instantiate();
+
+        for user in [USER, USER2] {
+            app = mint_tokens(app, user.to_owned(), Uint128::new(10_000));
+            app.execute_contract(
+                Addr::unchecked(user),
+                contract_addr.clone(),
+                &ExecuteMsg::Mint {},
+                &[coin(10_000, DENOM)],
+            )
+            .unwrap();
+        }
+
+        // burning more shares than owned errors instead of wrapping or panicking
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::Burn {
+                shares: Uint128::new(15_000),
+            },
+            &[],
+        )
+        .unwrap_err();
+
+        let balance: Balance = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr,
+                &QueryMsg::UserBalance {
+                    address: USER.to_owned(),
+                },
+            )
+            .unwrap();
+        assert_eq!(balance.amount, Uint128::new(10_000));
+    }
+
+    #[test]
+    fn burn_all() {
+        let (mut app, contract_addr) = proper_instantiate();
+
+        // nothing to burn yet
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::BurnAll {},
+            &[],
+        )
+        .unwrap_err();
+
+        app = mint_tokens(app, USER.to_owned(), Uint128::new(10_000));
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::Mint {},
+            &[coin(10_000, DENOM)],
+        )
+        .unwrap();
+
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::BurnAll {},
+            &[],
+        )
+        .unwrap();
+
+        let bal = app.wrap().query_balance(USER, DENOM).unwrap();
+        assert_eq!(bal.amount, Uint128::new(10_000));
+
+        // the emptied balance is removed from state
+        let stored = BALANCES
+            .query(&app.wrap(), contract_addr.clone(), &Addr::unchecked(USER))
+            .unwrap();
+        assert_eq!(stored, None);
+
+        let config: Config = app
+            .wrap()
+            .query_wasm_smart(contract_addr, &QueryMsg::GetConfig {})
+            .unwrap();
+        assert_eq!(config.total_supply, Uint128::zero());
+    }
+
+    #[test]
+    fn min_first_deposit() {
+        let mut app = App::default();
+        let code_id = app.store_code(challenge_contract());
+
+        let msg = InstantiateMsg {
+            offset: 10,
+            fee_admin: None,
+            mint_fee: None,
+            min_first_deposit: Some(Uint128::new(1_000)),
+            share_decimals: None,
+        };
+        let contract_addr = app
+            .instantiate_contract(code_id, Addr::unchecked(ADMIN), &msg, &[], "test", None)
+            .unwrap();
+
+        app = mint_tokens(app, USER.to_owned(), Uint128::new(1_000));
+        app = mint_tokens(app, USER2.to_owned(), Uint128::new(1));
+
+        // dust can't bootstrap the vault
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::Mint {},
+            &[coin(999, DENOM)],
+        )
+        .unwrap_err();
+
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::Mint {},
+            &[coin(1_000, DENOM)],
+        )
+        .unwrap();
+
+        // the minimum only applies to the first mint
+        app.execute_contract(
+            Addr::unchecked(USER2),
+            contract_addr.clone(),
+            &ExecuteMsg::Mint {},
+            &[coin(1, DENOM)],
+        )
+        .unwrap();
+
+        let config: Config = app
+            .wrap()
+            .query_wasm_smart(contract_addr, &QueryMsg::GetConfig {})
+            .unwrap();
+        assert_eq!(config.total_supply, Uint128::new(1_001));
+    }
+
+    #[test]
+    fn exchange_rate() {
+        let (mut app, contract_addr) = proper_instantiate();
+
+        // the first mint is priced 1:1
+        let rate: ExchangeRateResponse = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::ExchangeRate {})
+            .unwrap();
+        assert_eq!(rate.effective_rate, Decimal::one());
+        assert_eq!(rate.total_supply, Uint128::zero());
+
+        app = mint_tokens(app, USER.to_owned(), Uint128::new(10_000));
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::Mint {},
+            &[coin(10_000, DENOM)],
+        )
+        .unwrap();
+
+        let rate: ExchangeRateResponse = app
+            .wrap()
+            .query_wasm_smart(contract_addr, &QueryMsg::ExchangeRate {})
+            .unwrap();
+        assert_eq!(rate.effective_rate, Decimal::one());
+        assert_eq!(rate.total_assets, Uint128::new(10_000));
+        assert_eq!(rate.total_supply, Uint128::new(10_000));
+    }
+
+    #[test]
+    #[cfg(feature = "fixed")]
+    fn exchange_rate_resists_donations() {
+        let (mut app, contract_addr) = proper_instantiate();
+
+        app = mint_tokens(app, USER.to_owned(), Uint128::new(10_000));
+        app = mint_tokens(app, USER2.to_owned(), Uint128::new(10_000));
+
+        // same pre-dilution as in `left_unchecked`
+        let msg: cosmwasm_std::CosmosMsg = cosmwasm_std::BankMsg::Send {
+            to_address: contract_addr.clone().into(),
+            amount: vec![coin(5_000, DENOM)],
+        }
+        .into();
+        app.execute(Addr::unchecked(USER), msg).unwrap();
+
+        // the donation counts as nominal assets but barely moves the effective rate
+        let rate: ExchangeRateResponse = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::ExchangeRate {})
+            .unwrap();
+        assert_eq!(rate.total_assets, Uint128::new(5_000));
+        assert_eq!(rate.virtual_offset, Uint128::new(10_000_000_000));
+        assert!(rate.effective_rate < Decimal::from_ratio(1_000_001u128, 1_000_000u128));
+
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::Mint {},
+            &[coin(10, DENOM)],
+        )
+        .unwrap();
+        app.execute_contract(
+            Addr::unchecked(USER2),
+            contract_addr.clone(),
+            &ExecuteMsg::Mint {},
+            &[coin(10_000, DENOM)],
+        )
+        .unwrap();
+
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::BurnAll {},
+            &[],
+        )
+        .unwrap();
+        app.execute_contract(
+            Addr::unchecked(USER2),
+            contract_addr,
+            &ExecuteMsg::BurnAll {},
+            &[],
+        )
+        .unwrap();
+
+        // the attacker loses the donation instead of stealing from USER2
+        let user_bal = app.wrap().query_balance(USER, DENOM).unwrap();
+        assert!(user_bal.amount < Uint128::new(10_000));
+        let user2_bal = app.wrap().query_balance(USER2, DENOM).unwrap();
+        assert!(user2_bal.amount >= Uint128::new(9_999));
+    }
+
+    #[test]
+    fn share_price_attributes() {
+        let (mut app, contract_addr) = proper_instantiate();
+
+        app = mint_tokens(app, USER.to_owned(), Uint128::new(10_000));
+        app = mint_tokens(app, USER2.to_owned(), Uint128::new(10_000));
+
+        fn wasm_attr(res: &AppResponse, key: &str) -> String {
+            let event = res.events.iter().find(|ev| ev.ty == "wasm").unwrap();
+            event
+                .attributes
+                .iter()
+                .find(|attr| attr.key == key)
+                .unwrap()
+                .value
+                .clone()
+        }
+
+        // shares priced at the pre-operation rate must match the assets moved
+        fn assert_consistent(res: &AppResponse) {
+            let price = Decimal::from_str(&wasm_attr(res, "share_price")).unwrap();
+            let shares = Uint128::from_str(&wasm_attr(res, "shares")).unwrap();
+            let assets = Uint128::from_str(&wasm_attr(res, "assets")).unwrap();
+
+            let valued = shares * price;
+            assert!(valued.max(assets) - valued.min(assets) <= Uint128::one());
+        }
+
+        let res = app
+            .execute_contract(
+                Addr::unchecked(USER),
+                contract_addr.clone(),
+                &ExecuteMsg::Mint {},
+                &[coin(10_000, DENOM)],
+            )
+            .unwrap();
+        assert_eq!(wasm_attr(&res, "share_price"), "1");
+        assert_eq!(wasm_attr(&res, "shares"), "10000");
+        assert_eq!(wasm_attr(&res, "assets"), "10000");
+
+        // a donation moves the price for the next depositor
+        let msg: cosmwasm_std::CosmosMsg = cosmwasm_std::BankMsg::Send {
+            to_address: contract_addr.clone().into(),
+            amount: vec![coin(5_000, DENOM)],
+        }
+        .into();
+        app.execute(Addr::unchecked(USER2), msg).unwrap();
+
+        let res = app
+            .execute_contract(
+                Addr::unchecked(USER2),
+                contract_addr.clone(),
+                &ExecuteMsg::Mint {},
+                &[coin(3_000, DENOM)],
+            )
+            .unwrap();
+        assert!(Decimal::from_str(&wasm_attr(&res, "share_price")).unwrap() > Decimal::one());
+        assert_consistent(&res);
+
+        let res = app
+            .execute_contract(
+                Addr::unchecked(USER),
+                contract_addr,
+                &ExecuteMsg::Burn {
+                    shares: Uint128::new(4_000),
+                },
+                &[],
+            )
+            .unwrap();
+        assert_consistent(&res);
+    }
+
+    #[test]
+    fn share_decimals() {
+        let mut app = App::default();
+        let code_id = app.store_code(challenge_contract());
+
+        let mut msg = InstantiateMsg {
+            offset: 10,
+            fee_admin: None,
+            mint_fee: None,
+            min_first_deposit: None,
+            share_decimals: Some(13),
+        };
+        app.instantiate_contract(code_id, Addr::unchecked(ADMIN), &msg, &[], "test", None)
+            .unwrap_err();
+
+        msg.share_decimals = Some(6);
+        let contract_addr = app
+            .instantiate_contract(code_id, Addr::unchecked(ADMIN), &msg, &[], "test", None)
+            .unwrap();
+
+        app = mint_tokens(app, USER.to_owned(), Uint128::new(10_001));
+        app = mint_tokens(app, USER2.to_owned(), Uint128::new(3_333));
+
+        let rate: ExchangeRateResponse = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::ExchangeRate {})
+            .unwrap();
+        assert_eq!(rate.share_scale, Uint128::new(1_000_000));
+        assert_eq!(
+            rate.effective_rate,
+            Decimal::from_ratio(1u128, 1_000_000u128)
+        );
+
+        // the first mint creates 10^6 shares per asset unit
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::Mint {},
+            &[coin(10_000, DENOM)],
+        )
+        .unwrap();
+        let bal: Balance = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::UserBalance {
+                    address: USER.to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(bal.amount, Uint128::new(10_000_000_000));
+
+        // an odd donation leaves the price off the scale
+        let msg: cosmwasm_std::CosmosMsg = cosmwasm_std::BankMsg::Send {
+            to_address: contract_addr.clone().into(),
+            amount: vec![coin(1, DENOM)],
+        }
+        .into();
+        app.execute(Addr::unchecked(USER), msg).unwrap();
+
+        app.execute_contract(
+            Addr::unchecked(USER2),
+            contract_addr.clone(),
+            &ExecuteMsg::Mint {},
+            &[coin(3_333, DENOM)],
+        )
+        .unwrap();
+
+        // less than one asset unit worth of shares can't be redeemed
+        app.execute_contract(
+            Addr::unchecked(USER2),
+            contract_addr.clone(),
+            &ExecuteMsg::Burn {
+                shares: Uint128::one(),
+            },
+            &[],
+        )
+        .unwrap_err();
+
+        for user in [USER2, USER] {
+            app.execute_contract(
+                Addr::unchecked(user),
+                contract_addr.clone(),
+                &ExecuteMsg::BurnAll {},
+                &[],
+            )
+            .unwrap();
+        }
+
+        // round trips lose at most one unit of dust
+        let user2_bal = app.wrap().query_balance(USER2, DENOM).unwrap();
+        assert!(user2_bal.amount >= Uint128::new(3_332));
+        let user_bal = app.wrap().query_balance(USER, DENOM).unwrap();
+        assert!(user_bal.amount >= Uint128::new(10_000));
+    }
+
+    #[test]
+    fn pause_deposits_only() {
+        let (mut app, contract_addr) = proper_instantiate();
+
+        app = mint_tokens(app, USER.to_owned(), Uint128::new(10_000));
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::Mint {},
+            &[coin(5_000, DENOM)],
+        )
+        .unwrap();
+
+        let pause = ExecuteMsg::SetPause {
+            deposits_paused: true,
+            withdrawals_paused: false,
+        };
+        app.execute_contract(Addr::unchecked(USER), contract_addr.clone(), &pause, &[])
+            .unwrap_err();
+        app.execute_contract(Addr::unchecked(ADMIN), contract_addr.clone(), &pause, &[])
+            .unwrap();
+
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::Mint {},
+            &[coin(5_000, DENOM)],
+        )
+        .unwrap_err();
+
+        // users can still exit
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::BurnAll {},
+            &[],
+        )
+        .unwrap();
+
+        let bal = app.wrap().query_balance(USER, DENOM).unwrap();
+        assert_eq!(bal.amount, Uint128::new(10_000));
+
+        // unlike a shutdown the pause can be lifted
+        let pause = ExecuteMsg::SetPause {
+            deposits_paused: false,
+            withdrawals_paused: true,
+        };
+        app.execute_contract(Addr::unchecked(ADMIN), contract_addr.clone(), &pause, &[])
+            .unwrap();
+
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::Mint {},
+            &[coin(5_000, DENOM)],
+        )
+        .unwrap();
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr,
+            &ExecuteMsg::BurnAll {},
+            &[],
+        )
+        .unwrap_err();
+    }
+
+    #[test]
+    fn mint_payment_errors() {
+        let (mut app, contract_addr) = proper_instantiate();
+
+        app = mint_tokens(app, USER.to_owned(), Uint128::new(10_000));
+        app.sudo(cw_multi_test::SudoMsg::Bank(
+            cw_multi_test::BankSudo::Mint {
+                to_address: USER.to_owned(),
+                amount: vec![coin(10_000, "uother")],
+            },
+        ))
+        .unwrap();
+
+        let cases = [
+            (
+                vec![coin(1_000, "uother")],
+                PaymentError::WrongDenom {
+                    expected: DENOM.to_string(),
+                    received: "uother".to_string(),
+                },
+            ),
+            (
+                vec![coin(1_000, DENOM), coin(1_000, "uother")],
+                PaymentError::MultipleDenoms {},
+            ),
+        ];
+
+        for (funds, expected) in cases {
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(USER),
+                    contract_addr.clone(),
+                    &ExecuteMsg::Mint {},
+                    &funds,
+                )
+                .unwrap_err();
+            assert!(matches!(
+                err.downcast_ref::<ContractError>(),
+                Some(ContractError::Payment(err)) if *err == expected
+            ));
+        }
+
+        // nothing is stranded in the vault
+        let balances = app
+            .wrap()
+            .query_all_balances(contract_addr.clone())
+            .unwrap();
+        assert!(balances.is_empty());
+
+        let config: Config = app
+            .wrap()
+            .query_wasm_smart(contract_addr, &QueryMsg::GetConfig {})
+            .unwrap();
+        assert_eq!(config.total_supply, Uint128::zero());
+    }
+
+    #[test]
+    #[cfg(not(feature = "fixed"))]
+    fn share_price_saturates() {
+        let (mut app, contract_addr) = proper_instantiate();
+
+        app = mint_tokens(app, USER.to_owned(), Uint128::new(1));
+        app.execute_contract(
+            Addr::unchecked(USER),
+            contract_addr.clone(),
+            &ExecuteMsg::Mint {},
+            &[coin(1, DENOM)],
+        )
+        .unwrap();
+
+        // a single share backed by more assets than a `Decimal` can express
+        app = mint_tokens(app, contract_addr.to_string(), Uint128::new(10u128.pow(21)));
+
+        let res = app
+            .execute_contract(
+                Addr::unchecked(USER),
+                contract_addr,
+                &ExecuteMsg::Burn {
+                    shares: Uint128::one(),
+                },
+                &[],
+            )
+            .unwrap();
+        let event = res.events.iter().find(|ev| ev.ty == "wasm").unwrap();
+        assert!(event
+            .attributes
+            .iter()
+            .any(|attr| attr.key == "share_price" && attr.value == Decimal::MAX.to_string()));
+
+        let bal = app.wrap().query_balance(USER, DENOM).unwrap();
+        assert_eq!(bal.amount, Uint128::new(10u128.pow(21) + 1));
+    }
 }
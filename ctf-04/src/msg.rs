@@ -1,11 +1,20 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::Uint128;
+use cosmwasm_std::{Decimal, Uint128};
 
 use crate::state::{Balance, Config};
 
 #[cw_serde]
 pub struct InstantiateMsg {
+    /// Virtual shares and assets are `10^offset`, at most 18
     pub offset: u64,
+    /// Defaults to the instantiator
+    pub fee_admin: Option<String>,
+    pub mint_fee: Option<Decimal>,
+    /// Net assets after fees the first mint must deposit, defaults to zero
+    pub min_first_deposit: Option<Uint128>,
+    /// Extra decimals shares carry over the asset, the first mint creates
+    /// `10^share_decimals` shares per asset unit. Defaults to zero, at most 12
+    pub share_decimals: Option<u64>,
 }
 
 #[cw_serde]
@@ -14,6 +23,21 @@ pub enum ExecuteMsg {
     Mint {},
     /// Burn shares
     Burn { shares: Uint128 },
+    /// Burn every share held by the sender
+    BurnAll {},
+    /// Withdraw accrued protocol fees
+    WithdrawProtocolFees { amount: Uint128 },
+    /// Allow an address to mint shares
+    AddAllowed { address: String },
+    /// Remove an address from the allowlist
+    RemoveAllowed { address: String },
+    /// Stop accepting new deposits, burning remains possible
+    Shutdown {},
+    /// Pause mints and burns independently, admin only
+    SetPause {
+        deposits_paused: bool,
+        withdrawals_paused: bool,
+    },
 }
 
 #[cw_serde]
@@ -24,4 +48,41 @@ pub enum QueryMsg {
 
     #[returns(Balance)]
     UserBalance { address: String },
+
+    #[returns(bool)]
+    IsAllowed { address: String },
+
+    #[returns(SolvencyReportResponse)]
+    SolvencyReport {},
+
+    #[returns(ExchangeRateResponse)]
+    ExchangeRate {},
+}
+
+#[cw_serde]
+pub struct SolvencyReportResponse {
+    /// Funds owed to users according to internal accounting
+    pub accounted_liabilities: Uint128,
+    /// Funds actually held by the contract
+    pub actual_balance: Uint128,
+    /// Surplus over the accounted liabilities
+    pub free: Uint128,
+    /// Liabilities not covered by the contract balance
+    pub shortfall: Uint128,
+}
+
+#[cw_serde]
+pub struct ExchangeRateResponse {
+    /// Assets per share applied by `Mint` and `Burn`, virtual offset included.
+    /// This is the rate user outcomes are computed with
+    pub effective_rate: Decimal,
+    /// Assets backing shares, accrued protocol fees excluded
+    pub total_assets: Uint128,
+    /// Shares in circulation
+    pub total_supply: Uint128,
+    /// Virtual assets added on top of the totals, zero in the challenge build.
+    /// Virtual shares are this scaled by `share_scale`
+    pub virtual_offset: Uint128,
+    /// Shares per asset unit at the initial price
+    pub share_scale: Uint128,
 }
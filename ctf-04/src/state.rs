@@ -1,10 +1,31 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Uint128};
+use cosmwasm_std::{Addr, Decimal, Empty, Storage, Uint128};
+use ctf_common::pause::PauseState;
 use cw_storage_plus::{Item, Map};
 
+use crate::error::ContractError;
+
 #[cw_serde]
 pub struct Config {
     pub total_supply: Uint128,
+    /// Admin allowed to manage the vault
+    pub admin: Addr,
+    /// Address allowed to withdraw protocol fees
+    pub fee_admin: Addr,
+    /// Fee taken from each mint
+    pub mint_fee: Decimal,
+    /// Protocol fees held by the contract that do not back any shares
+    pub accrued_fees: Uint128,
+    /// Set once the vault is deprecated, only redemptions are accepted afterwards
+    pub shutdown: bool,
+    /// Minimum net deposit accepted while no shares exist
+    pub min_first_deposit: Uint128,
+    /// Exponent of the virtual shares and assets used in share pricing
+    pub offset: u32,
+    /// Shares are minted at `10^share_decimals` per asset unit initially
+    pub share_decimals: u32,
+    /// Mints and burns paused by the admin, unlike `shutdown` this can be lifted
+    pub pause: PauseState,
 }
 
 #[cw_serde]
@@ -15,3 +36,16 @@ pub struct Balance {
 
 pub const CONFIG: Item<Config> = Item::new("config");
 pub const BALANCES: Map<&Addr, Balance> = Map::new("balances");
+/// Addresses allowed to obtain shares, the vault is open to anyone while empty
+pub const ALLOWED: Map<&Addr, Empty> = Map::new("allowed");
+/// Net assets deposited through `Mint` less the assets paid out by `Burn`
+pub const TOTAL_DEPOSITS: Item<Uint128> = Item::new("total_deposits");
+
+pub fn assert_admin(store: &dyn Storage, sender: &Addr) -> Result<Config, ContractError> {
+    let config = CONFIG.load(store)?;
+
+    if config.admin != *sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    Ok(config)
+}
@@ -1,17 +1,34 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::{
-    coin, entry_point, to_binary, Addr, BankMsg, Binary, CosmosMsg, Deps, DepsMut, Env,
-    MessageInfo, Response, StdResult, Uint128,
+    coin, entry_point, to_binary, Addr, BankMsg, Binary, CosmosMsg, Deps, DepsMut, Empty, Env,
+    MessageInfo, Order, Response, StdError, StdResult, Uint128, WasmMsg,
 };
-use cw_storage_plus::Item;
+use ctf_common::ibc::IbcDestination;
+use ctf_common::msg::ProtocolConfigResponse;
+use ctf_common::pause::PauseState;
+use ctf_common::payment::{one_coin, PaymentError};
+use cw_storage_plus::{Bound, Item};
 
 use crate::error::ContractError;
-use crate::msg::{ConfigQueryResponse, ExecuteMsg, InstantiateMsg, QueryMsg};
-use crate::state::{BALANCES, OWNER, THRESHOLD};
-use cw_utils::must_pay;
+use crate::msg::{
+    AuditLogResponse, ConfigQueryResponse, ExecuteMsg, InstantiateMsg, OwnershipResponse, QueryMsg,
+    SolvencyReportResponse, UserDepositHistoryResponse,
+};
+use crate::state::{
+    accrue_time_weight, bump_balance_seq, challenge_time_weighted_top, clear_pending_renounce,
+    current_owner, ranks_above, record_audit, record_balance_event, update_denom_ranking, DenomTop,
+    OwnershipState, ReindexState, ACCEPTED_DENOMS, ADMIN, ALLOWED_TARGETS, AUDIT_LOG, BALANCES,
+    BALANCE_HISTORY, BALANCE_SEQ, DENOM_BALANCES, DENOM_RANKING, DENOM_TOPS, DEPOSIT_NONCES, OWNER,
+    OWNERSHIP, PAUSE, REFUND_EXCESS, REINDEX, THRESHOLD, TIME_WEIGHTED_LEADER, TIME_WEIGHTED_TOP,
+    TOTAL_DEPOSITS,
+};
 
 pub const DENOM: &str = "uawesome";
 pub const TOP_DEPOSITOR: Item<Addr> = Item::new("address");
+pub const MAX_OWNER_MSGS: usize = 10;
+
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -21,72 +38,255 @@ pub fn instantiate(
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
     OWNER.save(deps.storage, &deps.api.addr_validate(&msg.owner)?)?;
+    ADMIN.save(deps.storage, &deps.api.addr_validate(&msg.admin)?)?;
+    OWNERSHIP.save(deps.storage, &OwnershipState::default())?;
 
     THRESHOLD.save(deps.storage, &msg.threshold)?;
+    REFUND_EXCESS.save(deps.storage, &msg.refund_excess)?;
+    TIME_WEIGHTED_TOP.save(deps.storage, &msg.time_weighted_top)?;
+    PAUSE.save(deps.storage, &PauseState::default())?;
+    TOTAL_DEPOSITS.save(deps.storage, &Uint128::zero())?;
 
     Ok(Response::new()
         .add_attribute("action", "instantiate")
-        .add_attribute("owner", msg.owner))
+        .add_attribute("owner", msg.owner)
+        .add_attribute("admin", msg.admin))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::Deposit {} => deposit(deps, info),
-        ExecuteMsg::Withdraw { amount } => withdraw(deps, info, amount),
-        ExecuteMsg::OwnerAction { msg } => owner_action(deps, info, msg),
+        ExecuteMsg::Deposit { nonce } => deposit(deps, env, info, nonce),
+        ExecuteMsg::Withdraw {
+            amount,
+            denom,
+            ibc_destination,
+        } => match denom {
+            Some(denom) if denom != DENOM => {
+                withdraw_denom(deps, env, info, amount, denom, ibc_destination)
+            }
+            _ => withdraw(deps, env, info, amount, ibc_destination),
+        },
+        ExecuteMsg::OwnerAction { msg } => owner_action(deps, env, info, vec![msg]),
+        ExecuteMsg::OwnerActions { msgs } => owner_action(deps, env, info, msgs),
         ExecuteMsg::UpdateConfig { new_threshold } => update_config(deps, info, new_threshold),
+        ExecuteMsg::AddAllowedTarget { address } => add_allowed_target(deps, info, address),
+        ExecuteMsg::RemoveAllowedTarget { address } => remove_allowed_target(deps, info, address),
+        ExecuteMsg::RenounceOwnership {} => renounce_ownership(deps, info),
+        ExecuteMsg::AcknowledgeRenounce {} => acknowledge_renounce(deps, env, info),
+        ExecuteMsg::Reindex { start_after, limit } => reindex(deps, env, info, start_after, limit),
+        ExecuteMsg::ClaimTop {} => claim_top(deps, env, info),
+        ExecuteMsg::AddAcceptedDenom { denom } => add_accepted_denom(deps, info, denom),
+        ExecuteMsg::RemoveAcceptedDenom { denom } => remove_accepted_denom(deps, info, denom),
+        ExecuteMsg::SetPause {
+            deposits_paused,
+            withdrawals_paused,
+        } => set_pause(deps, info, deposits_paused, withdrawals_paused),
     }
 }
 
 /// Deposit entry point for user
-pub fn deposit(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
-    // validate denom
-    let amount = must_pay(&info, DENOM).unwrap();
+pub fn deposit(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    nonce: Option<u64>,
+) -> Result<Response, ContractError> {
+    PAUSE.load(deps.storage)?.assert_deposits_open()?;
+
+    let payment = one_coin(&info)?;
+
+    // at-most-once deposits for retrying senders
+    if let Some(nonce) = nonce {
+        if DEPOSIT_NONCES.has(deps.storage, (&info.sender, nonce)) {
+            return Err(ContractError::DuplicateNonce {});
+        }
+        DEPOSIT_NONCES.save(deps.storage, (&info.sender, nonce), &Empty {})?;
+    }
+
+    // other denoms are kept out of the contest
+    if payment.denom != DENOM {
+        return deposit_denom(deps, info, payment.denom, payment.amount);
+    }
+    let amount = payment.amount;
 
-    // increase total stake
     let mut user_balance = BALANCES
         .load(deps.storage, &info.sender)
         .unwrap_or_default();
-    user_balance += amount;
-
-    BALANCES.save(deps.storage, &info.sender, &user_balance)?;
 
     let current_threshold = THRESHOLD.load(deps.storage)?;
 
+    // only credit what is needed to pass the threshold and become the top depositor,
+    // the top depositor already holds the threshold and cannot be credited more
+    let mut refund = Uint128::zero();
+    let cap = if user_balance >= current_threshold {
+        user_balance
+    } else {
+        current_threshold.checked_add(Uint128::one())?
+    };
+    if REFUND_EXCESS.load(deps.storage)? && user_balance.checked_add(amount)? > cap {
+        let credited = cap.checked_sub(user_balance)?;
+        if credited.is_zero() {
+            return Err(ContractError::ThresholdReached {});
+        }
+        refund = amount.checked_sub(credited)?;
+    }
+    let amount = amount.checked_sub(refund)?;
+
+    accrue_time_weight(deps.storage, &info.sender, user_balance, env.block.time)?;
+
+    // increase total stake
+    user_balance = user_balance.checked_add(amount)?;
+
+    BALANCES.save(deps.storage, &info.sender, &user_balance)?;
+    TOTAL_DEPOSITS.update(deps.storage, |total| -> StdResult<_> {
+        Ok(total.checked_add(amount)?)
+    })?;
+    bump_balance_seq(deps.storage, &info.sender)?;
+    challenge_time_weighted_top(deps.storage, &info.sender, env.block.time)?;
+    record_balance_event(
+        deps.storage,
+        &info.sender,
+        env.block.height,
+        "deposit",
+        amount,
+        user_balance,
+    )?;
+
+    // THRESHOLD is raised to the leading balance on every takeover, so one comparison
+    // keeps the top depositor current and deposits stay O(1) in the number of users.
+    // Matching the leading balance is not enough, ties go to whoever reached it first
     if user_balance > current_threshold {
+        let old_top = TOP_DEPOSITOR.may_load(deps.storage)?;
+
         THRESHOLD.save(deps.storage, &user_balance)?;
         TOP_DEPOSITOR.save(deps.storage, &info.sender)?;
+
+        if old_top.as_ref() != Some(&info.sender) {
+            clear_pending_renounce(deps.storage)?;
+            record_audit(
+                deps.storage,
+                env.block.height,
+                "top_depositor",
+                old_top,
+                Some(info.sender.clone()),
+            )?;
+        }
+    }
+
+    let mut res = Response::new()
+        .add_attribute("action", "deposit")
+        .add_attribute("user", info.sender.clone())
+        .add_attribute("amount", amount);
+
+    if !refund.is_zero() {
+        res = res
+            .add_attribute("refund", refund)
+            .add_message(BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: vec![coin(refund.u128(), DENOM)],
+            });
+    }
+
+    Ok(res)
+}
+
+/// Credits a deposit in an accepted denom other than `DENOM`
+fn deposit_denom(
+    deps: DepsMut,
+    info: MessageInfo,
+    denom: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    if !ACCEPTED_DENOMS.has(deps.storage, &denom) {
+        return Err(PaymentError::WrongDenom {
+            expected: DENOM.to_string(),
+            received: denom,
+        }
+        .into());
+    }
+
+    let previous = DENOM_BALANCES
+        .may_load(deps.storage, (&info.sender, &denom))?
+        .unwrap_or_default();
+    let balance = previous.checked_add(amount)?;
+    DENOM_BALANCES.save(deps.storage, (&info.sender, &denom), &balance)?;
+    update_denom_ranking(deps.storage, &denom, &info.sender, previous, balance)?;
+
+    let is_top = match DENOM_TOPS.may_load(deps.storage, &denom)? {
+        Some(top) => balance > top.balance,
+        None => true,
+    };
+    if is_top {
+        DENOM_TOPS.save(
+            deps.storage,
+            &denom,
+            &DenomTop {
+                address: info.sender.clone(),
+                balance,
+            },
+        )?;
     }
 
     Ok(Response::new()
         .add_attribute("action", "deposit")
         .add_attribute("user", info.sender)
+        .add_attribute("denom", denom)
         .add_attribute("amount", amount))
 }
 
 /// Withdrawal entry point for user
 pub fn withdraw(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     amount: Uint128,
+    ibc_destination: Option<IbcDestination>,
 ) -> Result<Response, ContractError> {
+    PAUSE.load(deps.storage)?.assert_withdrawals_open()?;
+
+    if amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+
     // decrease total stake
     let mut user_balance = BALANCES.load(deps.storage, &info.sender)?;
 
-    // Cosmwasm's Uint128 checks math operations
-    user_balance -= amount;
+    accrue_time_weight(deps.storage, &info.sender, user_balance, env.block.time)?;
+
+    user_balance = user_balance.checked_sub(amount)?;
 
     BALANCES.save(deps.storage, &info.sender, &user_balance)?;
+    TOTAL_DEPOSITS.update(deps.storage, |total| -> StdResult<_> {
+        Ok(total.checked_sub(amount)?)
+    })?;
+    bump_balance_seq(deps.storage, &info.sender)?;
+    challenge_time_weighted_top(deps.storage, &info.sender, env.block.time)?;
+    record_balance_event(
+        deps.storage,
+        &info.sender,
+        env.block.height,
+        "withdraw",
+        amount,
+        user_balance,
+    )?;
 
-    let msg = BankMsg::Send {
-        to_address: info.sender.to_string(),
-        amount: vec![coin(amount.u128(), DENOM)],
+    // send over ICS20 when a remote destination is given
+    let msg: CosmosMsg = match &ibc_destination {
+        Some(destination) => {
+            destination.validate()?;
+            destination.transfer_msg(&env, coin(amount.u128(), DENOM))
+        }
+        None => BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![coin(amount.u128(), DENOM)],
+        }
+        .into(),
     };
 
     Ok(Response::new()
@@ -96,15 +296,92 @@ pub fn withdraw(
         .add_message(msg))
 }
 
-/// Entry point for owner to update threshold
+/// Withdraws a balance held in an accepted denom other than `DENOM`
+fn withdraw_denom(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+    denom: String,
+    ibc_destination: Option<IbcDestination>,
+) -> Result<Response, ContractError> {
+    PAUSE.load(deps.storage)?.assert_withdrawals_open()?;
+
+    if amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+
+    let previous = DENOM_BALANCES.load(deps.storage, (&info.sender, &denom))?;
+    let balance = previous.checked_sub(amount)?;
+    DENOM_BALANCES.save(deps.storage, (&info.sender, &denom), &balance)?;
+    update_denom_ranking(deps.storage, &denom, &info.sender, previous, balance)?;
+
+    // the top keeps its place unless someone now holds more
+    let is_top = DENOM_TOPS
+        .may_load(deps.storage, &denom)?
+        .map_or(false, |top| top.address == info.sender);
+    if is_top {
+        let highest = DENOM_RANKING
+            .sub_prefix(&denom)
+            .keys(deps.storage, None, None, Order::Descending)
+            .next()
+            .transpose()?;
+
+        match highest {
+            Some((highest_balance, address)) if highest_balance > balance.u128() => {
+                DENOM_TOPS.save(
+                    deps.storage,
+                    &denom,
+                    &DenomTop {
+                        address,
+                        balance: Uint128::new(highest_balance),
+                    },
+                )?;
+            }
+            _ if balance.is_zero() => DENOM_TOPS.remove(deps.storage, &denom),
+            _ => {
+                DENOM_TOPS.save(
+                    deps.storage,
+                    &denom,
+                    &DenomTop {
+                        address: info.sender.clone(),
+                        balance,
+                    },
+                )?;
+            }
+        }
+    }
+
+    let funds = coin(amount.u128(), &denom);
+    let msg: CosmosMsg = match &ibc_destination {
+        Some(destination) => {
+            destination.validate()?;
+            destination.transfer_msg(&env, funds)
+        }
+        None => BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![funds],
+        }
+        .into(),
+    };
+
+    Ok(Response::new()
+        .add_attribute("action", "withdraw")
+        .add_attribute("user", info.sender)
+        .add_attribute("denom", denom)
+        .add_attribute("amount", amount)
+        .add_message(msg))
+}
+
+/// Entry point for admin to update threshold
 pub fn update_config(
     deps: DepsMut,
     info: MessageInfo,
     new_threshold: Uint128,
 ) -> Result<Response, ContractError> {
-    let owner = OWNER.load(deps.storage)?;
+    let admin = ADMIN.load(deps.storage)?;
 
-    if owner != info.sender {
+    if admin != info.sender {
         return Err(ContractError::Unauthorized {});
     }
 
@@ -115,29 +392,353 @@ pub fn update_config(
         .add_attribute("threshold", new_threshold))
 }
 
-/// Entry point for owner to execute arbitrary Cosmos messages
+/// Entry point for admin to pause deposits and withdrawals
+pub fn set_pause(
+    deps: DepsMut,
+    info: MessageInfo,
+    deposits_paused: bool,
+    withdrawals_paused: bool,
+) -> Result<Response, ContractError> {
+    let admin = ADMIN.load(deps.storage)?;
+
+    if admin != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    PAUSE.save(
+        deps.storage,
+        &PauseState {
+            deposits_paused,
+            withdrawals_paused,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_pause")
+        .add_attribute("deposits_paused", deposits_paused.to_string())
+        .add_attribute("withdrawals_paused", withdrawals_paused.to_string()))
+}
+
+/// Entry point for owner to execute arbitrary Cosmos messages.
+/// Every message is validated before any is dispatched.
 pub fn owner_action(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
-    msg: CosmosMsg,
+    msgs: Vec<CosmosMsg>,
 ) -> Result<Response, ContractError> {
-    let owner = OWNER.load(deps.storage)?;
+    let owner = current_owner(deps.storage)?.ok_or(ContractError::OwnershipRenounced {})?;
 
     if owner != info.sender {
         return Err(ContractError::Unauthorized {});
     }
 
+    if msgs.len() > MAX_OWNER_MSGS {
+        return Err(ContractError::TooManyOwnerMsgs {
+            max: MAX_OWNER_MSGS,
+        });
+    }
+
+    for msg in &msgs {
+        validate_owner_msg(deps.as_ref(), &env, msg)?;
+    }
+
     Ok(Response::new()
         .add_attribute("action", "owner_action")
-        .add_message(msg))
+        .add_messages(msgs))
+}
+
+/// Entry point for depositors whose time-weighted score overtook the leader while holding
+pub fn claim_top(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    if !challenge_time_weighted_top(deps.storage, &info.sender, env.block.time)? {
+        return Err(ContractError::ScoreTooLow {});
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "claim_top")
+        .add_attribute("user", info.sender))
+}
+
+/// Entry point for owner to start renouncing ownership
+pub fn renounce_ownership(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let owner = current_owner(deps.storage)?.ok_or(ContractError::OwnershipRenounced {})?;
+
+    if owner != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut ownership = OWNERSHIP.load(deps.storage)?;
+    ownership.pending_renounce = Some(info.sender);
+    OWNERSHIP.save(deps.storage, &ownership)?;
+
+    Ok(Response::new().add_attribute("action", "renounce_ownership"))
+}
+
+/// Entry point for owner to confirm the renounce, this cannot be undone
+pub fn acknowledge_renounce(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let owner = current_owner(deps.storage)?.ok_or(ContractError::OwnershipRenounced {})?;
+
+    if owner != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut ownership = OWNERSHIP.load(deps.storage)?;
+    // only the owner that proposed the renounce can confirm it
+    if ownership.pending_renounce.as_ref() != Some(&info.sender) {
+        return Err(ContractError::NoPendingRenounce {});
+    }
+
+    ownership.pending_renounce = None;
+    ownership.renounced = true;
+    // the renounced flag takes precedence over any stored owner
+    OWNERSHIP.save(deps.storage, &ownership)?;
+
+    record_audit(
+        deps.storage,
+        env.block.height,
+        "renounce_ownership",
+        Some(owner),
+        None,
+    )?;
+
+    Ok(Response::new().add_attribute("action", "acknowledge_renounce"))
+}
+
+/// Entry point for admin to repair the top depositor and deposit total if they drifted from
+/// user balances. Scans a bounded page per call and applies the result once all depositors
+/// are scanned
+pub fn reindex(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let admin = ADMIN.load(deps.storage)?;
+
+    if admin != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // a new scan starts from scratch, otherwise pages must be contiguous
+    let mut state = match start_after {
+        None => ReindexState::default(),
+        Some(start_after) => {
+            let start_after = deps.api.addr_validate(&start_after)?;
+            let state = REINDEX.may_load(deps.storage)?.unwrap_or_default();
+            if state.last.as_ref() != Some(&start_after) {
+                return Err(ContractError::ReindexOutOfOrder {});
+            }
+            state
+        }
+    };
+
+    // an empty page would never finish the scan
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT) as usize;
+    let start = state.last.as_ref().map(Bound::exclusive);
+
+    let page = BALANCES
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let scanned = page.len();
+    for (user, balance) in page {
+        let seq = BALANCE_SEQ
+            .may_load(deps.storage, &user)?
+            .unwrap_or_default();
+        let is_higher = match state.top {
+            Some(_) => ranks_above(balance, seq, state.top_balance, state.top_seq),
+            None => !balance.is_zero(),
+        };
+        if is_higher {
+            state.top = Some(user.clone());
+            state.top_balance = balance;
+            state.top_seq = seq;
+        }
+        state.total = state.total.checked_add(balance)?;
+        state.last = Some(user);
+    }
+
+    let mut res = Response::new()
+        .add_attribute("action", "reindex")
+        .add_attribute("scanned", scanned.to_string());
+
+    if scanned == limit {
+        REINDEX.save(deps.storage, &state)?;
+        if let Some(last) = &state.last {
+            res = res.add_attribute("next_start_after", last.to_string());
+        }
+        return Ok(res.add_attribute("finished", "false"));
+    }
+
+    REINDEX.remove(deps.storage);
+
+    TOTAL_DEPOSITS.save(deps.storage, &state.total)?;
+    res = res.add_attribute("total_deposits", state.total);
+
+    if let Some(top) = state.top {
+        let old_top = TOP_DEPOSITOR.may_load(deps.storage)?;
+        if old_top.as_ref() != Some(&top) {
+            TOP_DEPOSITOR.save(deps.storage, &top)?;
+            clear_pending_renounce(deps.storage)?;
+            record_audit(
+                deps.storage,
+                env.block.height,
+                "top_depositor",
+                old_top,
+                Some(top.clone()),
+            )?;
+        }
+
+        // the threshold never sits below the leading balance
+        if state.top_balance > THRESHOLD.load(deps.storage)? {
+            THRESHOLD.save(deps.storage, &state.top_balance)?;
+        }
+
+        res = res.add_attribute("top_depositor", top);
+    }
+
+    Ok(res.add_attribute("finished", "true"))
+}
+
+/// Checks a message against the owner action policy
+fn validate_owner_msg(deps: Deps, env: &Env, msg: &CosmosMsg) -> Result<(), ContractError> {
+    // funds sent back to the contract would bypass deposit accounting
+    if let CosmosMsg::Bank(BankMsg::Send { to_address, .. }) = msg {
+        if *to_address == env.contract.address {
+            return Err(ContractError::SelfCallNotAllowed {});
+        }
+    }
+
+    // contract executions are limited to allowlisted targets
+    if let CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, .. }) = msg {
+        let target = deps.api.addr_validate(contract_addr)?;
+        // re-entering as the contract itself is never allowed, even if allowlisted
+        if target == env.contract.address {
+            return Err(ContractError::SelfCallNotAllowed {});
+        }
+        if !ALLOWED_TARGETS.has(deps.storage, &target) {
+            return Err(ContractError::TargetNotAllowed {
+                target: target.to_string(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Entry point for admin to allow a contract as `OwnerAction` target
+pub fn add_allowed_target(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+) -> Result<Response, ContractError> {
+    let admin = ADMIN.load(deps.storage)?;
+
+    if admin != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let target = deps.api.addr_validate(&address)?;
+    ALLOWED_TARGETS.save(deps.storage, &target, &Empty {})?;
+
+    Ok(Response::new()
+        .add_attribute("action", "add_allowed_target")
+        .add_attribute("target", target))
+}
+
+/// Entry point for admin to accept deposits in another native denom
+pub fn add_accepted_denom(
+    deps: DepsMut,
+    info: MessageInfo,
+    denom: String,
+) -> Result<Response, ContractError> {
+    let admin = ADMIN.load(deps.storage)?;
+
+    if admin != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // the contest denom is always accepted and accounted on its own
+    if denom.is_empty() || denom == DENOM {
+        return Err(ContractError::InvalidDenom { denom });
+    }
+    ACCEPTED_DENOMS.save(deps.storage, &denom, &Empty {})?;
+
+    Ok(Response::new()
+        .add_attribute("action", "add_accepted_denom")
+        .add_attribute("denom", denom))
+}
+
+/// Entry point for admin to stop accepting deposits in a denom
+pub fn remove_accepted_denom(
+    deps: DepsMut,
+    info: MessageInfo,
+    denom: String,
+) -> Result<Response, ContractError> {
+    let admin = ADMIN.load(deps.storage)?;
+
+    if admin != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    ACCEPTED_DENOMS.remove(deps.storage, &denom);
+
+    Ok(Response::new()
+        .add_attribute("action", "remove_accepted_denom")
+        .add_attribute("denom", denom))
+}
+
+/// Entry point for admin to remove a contract from the `OwnerAction` targets
+pub fn remove_allowed_target(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+) -> Result<Response, ContractError> {
+    let admin = ADMIN.load(deps.storage)?;
+
+    if admin != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let target = deps.api.addr_validate(&address)?;
+    ALLOWED_TARGETS.remove(deps.storage, &target);
+
+    Ok(Response::new()
+        .add_attribute("action", "remove_allowed_target")
+        .add_attribute("target", target))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_binary(&query_config(deps)?),
         QueryMsg::UserBalance { address } => to_binary(&query_balance(deps, address)?),
         QueryMsg::Top {} => to_binary(&query_top_depositor(deps)?),
+        QueryMsg::Owner {} => to_binary(&query_owner(deps)?),
+        QueryMsg::Admin {} => to_binary(&query_admin(deps)?),
+        QueryMsg::Ownership {} => to_binary(&query_ownership(deps)?),
+        QueryMsg::AllowedTargets {} => to_binary(&query_allowed_targets(deps)?),
+        QueryMsg::SolvencyReport {} => to_binary(&query_solvency_report(deps, env)?),
+        QueryMsg::AuditLog { start_after, limit } => {
+            to_binary(&query_audit_log(deps, start_after, limit)?)
+        }
+        QueryMsg::ProtocolConfig {} => to_binary(&query_protocol_config()),
+        QueryMsg::UserDepositHistory {
+            user,
+            start_after,
+            limit,
+        } => to_binary(&query_user_deposit_history(deps, user, start_after, limit)?),
+        QueryMsg::AcceptedDenoms {} => to_binary(&query_accepted_denoms(deps)?),
+        QueryMsg::DenomBalance { address, denom } => {
+            to_binary(&query_denom_balance(deps, address, denom)?)
+        }
+        QueryMsg::DenomTop { denom } => to_binary(&query_denom_top(deps, denom)?),
     }
 }
 
@@ -147,15 +748,162 @@ pub fn query_balance(deps: Deps, address: String) -> StdResult<Uint128> {
     BALANCES.load(deps.storage, &address)
 }
 
+/// Returns a user's balance in any denom, zero when nothing was deposited
+pub fn query_denom_balance(deps: Deps, address: String, denom: String) -> StdResult<Uint128> {
+    let address = deps.api.addr_validate(&address)?;
+    let balance = if denom == DENOM {
+        BALANCES.may_load(deps.storage, &address)?
+    } else {
+        DENOM_BALANCES.may_load(deps.storage, (&address, &denom))?
+    };
+    Ok(balance.unwrap_or_default())
+}
+
+/// Returns the leading depositor of a denom, `DENOM` follows the contest top depositor
+pub fn query_denom_top(deps: Deps, denom: String) -> StdResult<Option<DenomTop>> {
+    if denom != DENOM {
+        return DENOM_TOPS.may_load(deps.storage, &denom);
+    }
+
+    TOP_DEPOSITOR
+        .may_load(deps.storage)?
+        .map(|address| {
+            let balance = BALANCES
+                .may_load(deps.storage, &address)?
+                .unwrap_or_default();
+            Ok(DenomTop { address, balance })
+        })
+        .transpose()
+}
+
+/// Returns the native denoms accepted besides `DENOM`
+pub fn query_accepted_denoms(deps: Deps) -> StdResult<Vec<String>> {
+    ACCEPTED_DENOMS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect()
+}
+
 /// Returns contract configuration
 pub fn query_config(deps: Deps) -> StdResult<ConfigQueryResponse> {
-    let owner = OWNER.load(deps.storage)?;
+    let owner = current_owner(deps.storage)?;
+    let admin = ADMIN.load(deps.storage)?;
     let threshold = THRESHOLD.load(deps.storage)?;
+    let refund_excess = REFUND_EXCESS.load(deps.storage)?;
+    let time_weighted_top = TIME_WEIGHTED_TOP.load(deps.storage)?;
+    let pause = PAUSE.load(deps.storage)?;
 
-    Ok(ConfigQueryResponse { owner, threshold })
+    Ok(ConfigQueryResponse {
+        owner,
+        admin,
+        threshold,
+        refund_excess,
+        time_weighted_top,
+        pause,
+    })
 }
 
-/// Returns the top depositor
+/// Returns the top depositor, ranked by time-weighted balance when enabled. The time-weighted
+/// leader only changes on balance changes and `ClaimTop`, equal scores go to the user whose
+/// balance last changed first
 pub fn query_top_depositor(deps: Deps) -> StdResult<Addr> {
-    TOP_DEPOSITOR.load(deps.storage)
+    if !TIME_WEIGHTED_TOP.load(deps.storage)? {
+        return TOP_DEPOSITOR.load(deps.storage);
+    }
+
+    TIME_WEIGHTED_LEADER
+        .may_load(deps.storage)?
+        .ok_or_else(|| StdError::not_found("top depositor"))
+}
+
+/// Returns the owner controlling funds
+pub fn query_owner(deps: Deps) -> StdResult<Option<Addr>> {
+    current_owner(deps.storage)
+}
+
+/// Returns the owner along with the renounce status
+pub fn query_ownership(deps: Deps) -> StdResult<OwnershipResponse> {
+    let ownership = OWNERSHIP.load(deps.storage)?;
+
+    Ok(OwnershipResponse {
+        owner: current_owner(deps.storage)?,
+        pending_renounce: ownership.pending_renounce,
+        renounced: ownership.renounced,
+    })
+}
+
+/// Returns the admin managing configuration
+pub fn query_admin(deps: Deps) -> StdResult<Addr> {
+    ADMIN.load(deps.storage)
+}
+
+/// Returns the contracts allowed as `OwnerAction` targets
+pub fn query_allowed_targets(deps: Deps) -> StdResult<Vec<Addr>> {
+    ALLOWED_TARGETS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect()
+}
+
+/// Returns audit log entries in insertion order
+pub fn query_audit_log(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<AuditLogResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let entries = AUDIT_LOG
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, entry)| entry))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(AuditLogResponse { entries })
+}
+
+/// Returns a user's deposits and withdrawals in the order they happened
+pub fn query_user_deposit_history(
+    deps: Deps,
+    user: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<UserDepositHistoryResponse> {
+    let user = deps.api.addr_validate(&user)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let events = BALANCE_HISTORY
+        .prefix(&user)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, event)| event))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(UserDepositHistoryResponse { events })
+}
+
+/// Returns the contract balance compared to the sum of user balances
+pub fn query_solvency_report(deps: Deps, env: Env) -> StdResult<SolvencyReportResponse> {
+    let accounted_liabilities = TOTAL_DEPOSITS.load(deps.storage)?;
+
+    let actual_balance = deps
+        .querier
+        .query_balance(env.contract.address, DENOM)?
+        .amount;
+
+    Ok(SolvencyReportResponse {
+        accounted_liabilities,
+        actual_balance,
+        free: actual_balance.saturating_sub(accounted_liabilities),
+        shortfall: accounted_liabilities.saturating_sub(actual_balance),
+    })
+}
+
+/// Returns the denom deposits are made in, funds are never locked
+pub fn query_protocol_config() -> ProtocolConfigResponse {
+    ProtocolConfigResponse {
+        denom: DENOM.to_string(),
+        lock_period: None,
+        minimum_deposit: None,
+    }
 }
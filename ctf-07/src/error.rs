@@ -1,4 +1,7 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{OverflowError, StdError};
+use ctf_common::ibc::IbcDestinationError;
+use ctf_common::pause::PauseError;
+use ctf_common::payment::PaymentError;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -6,6 +9,51 @@ pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
 
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
+    #[error("{0}")]
+    Payment(#[from] PaymentError),
+
+    #[error("{0}")]
+    Pause(#[from] PauseError),
+
+    #[error("{0}")]
+    IbcDestination(#[from] IbcDestinationError),
+
     #[error("Unauthorized")]
     Unauthorized {},
+
+    #[error("Zero amount is not allowed")]
+    ZeroAmount {},
+
+    #[error("Ownership has been renounced")]
+    OwnershipRenounced {},
+
+    #[error("No pending ownership renounce")]
+    NoPendingRenounce {},
+
+    #[error("Deposit threshold already reached")]
+    ThresholdReached {},
+
+    #[error("Owner actions are limited to {max} messages")]
+    TooManyOwnerMsgs { max: usize },
+
+    #[error("Target contract {target} is not allowed")]
+    TargetNotAllowed { target: String },
+
+    #[error("Owner actions cannot target the contract itself")]
+    SelfCallNotAllowed {},
+
+    #[error("Deposit nonce already processed")]
+    DuplicateNonce {},
+
+    #[error("Reindex has to continue after the last scanned depositor")]
+    ReindexOutOfOrder {},
+
+    #[error("Time-weighted score does not rank above the current top")]
+    ScoreTooLow {},
+
+    #[error("Denom {denom} cannot be accepted")]
+    InvalidDenom { denom: String },
 }
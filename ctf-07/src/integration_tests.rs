@@ -1,11 +1,33 @@
 #[cfg(test)]
 pub mod tests {
     use crate::{
-        contract::DENOM,
-        msg::{ExecuteMsg, InstantiateMsg, QueryMsg},
+        contract::{DENOM, MAX_OWNER_MSGS, TOP_DEPOSITOR},
+        msg::{
+            AuditLogResponse, ConfigQueryResponse, ExecuteMsg, InstantiateMsg, OwnershipResponse,
+            QueryMsg, SolvencyReportResponse, UserDepositHistoryResponse,
+        },
+        state::{
+            bump_balance_seq, record_audit, record_balance_event, DenomTop, AUDIT_COUNT,
+            BALANCE_HISTORY_COUNT, BALANCE_SEQ_COUNT, TOTAL_DEPOSITS,
+        },
+        ContractError,
+    };
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+    };
+    use cosmwasm_std::{
+        coin, Addr, BankMsg, CosmosMsg, Empty, IbcMsg, IbcTimeout, Order, OwnedDeps, Record,
+        Storage, Uint128,
+    };
+    use ctf_common::{
+        ibc::{IbcDestination, DEFAULT_IBC_TIMEOUT},
+        msg::ProtocolConfigResponse,
+        payment::PaymentError,
     };
-    use cosmwasm_std::{coin, Addr, Empty, Uint128};
     use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+    use insta::assert_json_snapshot;
+    use std::cell::Cell;
+    use std::marker::PhantomData;
 
     pub fn challenge_contract() -> Box<dyn Contract<Empty>> {
         let contract = ContractWrapper::new(
@@ -27,7 +49,10 @@ pub mod tests {
         // init contract
         let msg = InstantiateMsg {
             owner: ADMIN.to_string(),
+            admin: ADMIN.to_string(),
             threshold: Uint128::from(99u128),
+            refund_excess: false,
+            time_weighted_top: false,
         };
 
         let contract_addr = app
@@ -53,7 +78,10 @@ pub mod tests {
         // init contract
         let msg = InstantiateMsg {
             owner: ADMIN.to_string(),
+            admin: ADMIN.to_string(),
             threshold: Uint128::from(99u128),
+            refund_excess: false,
+            time_weighted_top: false,
         };
 
         let contract_addr = app
@@ -72,7 +100,7 @@ pub mod tests {
         app.execute_contract(
             Addr::unchecked(USER1),
             contract_addr.clone(),
-            &ExecuteMsg::Deposit {},
+            &ExecuteMsg::Deposit { nonce: None },
             &[coin(100, DENOM)],
         )
         .unwrap();
@@ -82,7 +110,7 @@ pub mod tests {
         app.execute_contract(
             Addr::unchecked(USER2),
             contract_addr.clone(),
-            &ExecuteMsg::Deposit {},
+            &ExecuteMsg::Deposit { nonce: None },
             &[coin(110, DENOM)],
         )
         .unwrap();
@@ -112,7 +140,7 @@ pub mod tests {
         app.execute_contract(
             Addr::unchecked(USER1),
             contract_addr.clone(),
-            &ExecuteMsg::Deposit {},
+            &ExecuteMsg::Deposit { nonce: None },
             &[coin(100, DENOM)],
         )
         .unwrap();
@@ -133,6 +161,8 @@ pub mod tests {
             contract_addr,
             &ExecuteMsg::Withdraw {
                 amount: Uint128::new(100),
+                denom: None,
+                ibc_destination: None,
             },
             &[],
         )
@@ -156,13 +186,13 @@ pub mod tests {
             .wrap()
             .query_wasm_smart(contract_addr.clone(), &QueryMsg::Config {})
             .unwrap();
-        assert_eq!(config.owner, Addr::unchecked("admin"));
+        assert_eq!(config.owner, Some(Addr::unchecked("admin")));
 
         // "grug" deposits 1_000 tokens
         app.execute_contract(
             Addr::unchecked("grug"),
             contract_addr.clone(),
-            &ExecuteMsg::Deposit {},
+            &ExecuteMsg::Deposit { nonce: None },
             &[coin(1_000, DENOM)],
         )
         .unwrap();
@@ -173,7 +203,7 @@ pub mod tests {
         app.execute_contract(
             Addr::unchecked("hacker"),
             contract_addr.clone(),
-            &ExecuteMsg::Deposit {},
+            &ExecuteMsg::Deposit { nonce: None },
             &[coin(1_001, DENOM)],
         )
         .unwrap();
@@ -192,7 +222,7 @@ pub mod tests {
             .wrap()
             .query_wasm_smart(contract_addr.clone(), &QueryMsg::Config {})
             .unwrap();
-        assert_eq!(config.owner, Addr::unchecked("hacker"));
+        assert_eq!(config.owner, Some(Addr::unchecked("hacker")));
 
         // that's not good...
         // "hacker" queries balance of contract and sends themselves all the tokens
@@ -221,4 +251,1677 @@ pub mod tests {
 
     }
 
+    #[test]
+    fn owner_action_target_allowlist() {
+        let (mut app, contract_addr) = proper_instantiate();
+
+        // second instance owned by the challenge contract
+        let cw_template_id = app.store_code(challenge_contract());
+        let target_addr = app
+            .instantiate_contract(
+                cw_template_id,
+                Addr::unchecked(ADMIN),
+                &InstantiateMsg {
+                    owner: contract_addr.to_string(),
+                    admin: contract_addr.to_string(),
+                    threshold: Uint128::from(99u128),
+                    refund_excess: false,
+                    time_weighted_top: false,
+                },
+                &[],
+                "target",
+                None,
+            )
+            .unwrap();
+
+        let update_msg = cosmwasm_std::CosmosMsg::Wasm(cosmwasm_std::WasmMsg::Execute {
+            contract_addr: target_addr.to_string(),
+            msg: cosmwasm_std::to_binary(&ExecuteMsg::UpdateConfig {
+                new_threshold: Uint128::from(500u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        });
+
+        // target is not allowlisted yet
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            contract_addr.clone(),
+            &ExecuteMsg::OwnerAction {
+                msg: update_msg.clone(),
+            },
+            &[],
+        )
+        .unwrap_err();
+
+        // only the admin can manage the allowed targets
+        app.execute_contract(
+            Addr::unchecked(USER1),
+            contract_addr.clone(),
+            &ExecuteMsg::AddAllowedTarget {
+                address: target_addr.to_string(),
+            },
+            &[],
+        )
+        .unwrap_err();
+
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            contract_addr.clone(),
+            &ExecuteMsg::AddAllowedTarget {
+                address: target_addr.to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+
+        let targets: Vec<Addr> = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::AllowedTargets {})
+            .unwrap();
+        assert_eq!(targets, vec![target_addr.clone()]);
+
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            contract_addr.clone(),
+            &ExecuteMsg::OwnerAction {
+                msg: update_msg.clone(),
+            },
+            &[],
+        )
+        .unwrap();
+
+        let config: crate::msg::ConfigQueryResponse = app
+            .wrap()
+            .query_wasm_smart(target_addr.clone(), &QueryMsg::Config {})
+            .unwrap();
+        assert_eq!(config.threshold, Uint128::from(500u128));
+
+        // removed targets are rejected again
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            contract_addr.clone(),
+            &ExecuteMsg::RemoveAllowedTarget {
+                address: target_addr.to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            contract_addr,
+            &ExecuteMsg::OwnerAction { msg: update_msg },
+            &[],
+        )
+        .unwrap_err();
+    }
+
+    #[test]
+    fn audit_log() {
+        let (app, contract_addr) = base_scenario();
+
+        // both users became top depositor in turn
+        let log: AuditLogResponse = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::AuditLog {
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(log.entries.len(), 2);
+        assert_eq!(log.entries[0].new, Some(Addr::unchecked(USER1)));
+        assert_eq!(log.entries[1].action, "top_depositor");
+        assert_eq!(log.entries[1].old, Some(Addr::unchecked(USER1)));
+        assert_eq!(log.entries[1].new, Some(Addr::unchecked(USER2)));
+
+        // paginate past the first entry
+        let log: AuditLogResponse = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr,
+                &QueryMsg::AuditLog {
+                    start_after: Some(1),
+                    limit: Some(1),
+                },
+            )
+            .unwrap();
+        assert_eq!(log.entries.len(), 1);
+        assert_eq!(log.entries[0].id, 2);
+    }
+
+    #[test]
+    fn refund_excess_deposit() {
+        let mut app = App::default();
+        let cw_template_id = app.store_code(challenge_contract());
+
+        let msg = InstantiateMsg {
+            owner: ADMIN.to_string(),
+            admin: ADMIN.to_string(),
+            threshold: Uint128::from(99u128),
+            refund_excess: true,
+            time_weighted_top: false,
+        };
+        let contract_addr = app
+            .instantiate_contract(
+                cw_template_id,
+                Addr::unchecked(ADMIN),
+                &msg,
+                &[],
+                "test",
+                None,
+            )
+            .unwrap();
+
+        app = mint_tokens(app, USER1.to_string(), Uint128::from(150u128));
+
+        // only the amount needed to pass the threshold is credited
+        app.execute_contract(
+            Addr::unchecked(USER1),
+            contract_addr.clone(),
+            &ExecuteMsg::Deposit { nonce: None },
+            &[coin(150, DENOM)],
+        )
+        .unwrap();
+
+        let balance: Uint128 = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::UserBalance {
+                    address: USER1.to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(balance, Uint128::new(100));
+
+        let bal = app.wrap().query_balance(USER1, DENOM).unwrap();
+        assert_eq!(bal.amount, Uint128::new(50));
+
+        // which is enough to become the top depositor
+        let top: Addr = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::Top {})
+            .unwrap();
+        assert_eq!(top, Addr::unchecked(USER1));
+
+        // nothing more can be credited
+        let err = app
+            .execute_contract(
+                Addr::unchecked(USER1),
+                contract_addr,
+                &ExecuteMsg::Deposit { nonce: None },
+                &[coin(50, DENOM)],
+            )
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ContractError>(),
+            Some(ContractError::ThresholdReached {})
+        ));
+    }
+
+    #[test]
+    fn separate_admin_and_owner() {
+        let mut app = App::default();
+        let cw_template_id = app.store_code(challenge_contract());
+
+        let msg = InstantiateMsg {
+            owner: USER1.to_string(),
+            admin: ADMIN.to_string(),
+            threshold: Uint128::from(99u128),
+            refund_excess: false,
+            time_weighted_top: false,
+        };
+        let contract_addr = app
+            .instantiate_contract(
+                cw_template_id,
+                Addr::unchecked(ADMIN),
+                &msg,
+                &[],
+                "test",
+                None,
+            )
+            .unwrap();
+
+        let owner: Option<Addr> = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::Owner {})
+            .unwrap();
+        assert_eq!(owner, Some(Addr::unchecked(USER1)));
+
+        let admin: Addr = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::Admin {})
+            .unwrap();
+        assert_eq!(admin, Addr::unchecked(ADMIN));
+
+        let noop_msg = cosmwasm_std::CosmosMsg::Bank(cosmwasm_std::BankMsg::Send {
+            to_address: ADMIN.to_string(),
+            amount: vec![],
+        });
+
+        // admin cannot move funds
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            contract_addr.clone(),
+            &ExecuteMsg::OwnerAction { msg: noop_msg },
+            &[],
+        )
+        .unwrap_err();
+
+        // owner cannot change config
+        app.execute_contract(
+            Addr::unchecked(USER1),
+            contract_addr.clone(),
+            &ExecuteMsg::UpdateConfig {
+                new_threshold: Uint128::from(500u128),
+            },
+            &[],
+        )
+        .unwrap_err();
+
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            contract_addr.clone(),
+            &ExecuteMsg::UpdateConfig {
+                new_threshold: Uint128::from(500u128),
+            },
+            &[],
+        )
+        .unwrap();
+
+        let config: crate::msg::ConfigQueryResponse = app
+            .wrap()
+            .query_wasm_smart(contract_addr, &QueryMsg::Config {})
+            .unwrap();
+        assert_eq!(config.threshold, Uint128::from(500u128));
+    }
+
+    #[test]
+    fn solvency_report() {
+        let (mut app, contract_addr) = base_scenario();
+
+        // donation is not owed to anyone
+        app = mint_tokens(app, contract_addr.to_string(), Uint128::from(50u128));
+
+        let report: SolvencyReportResponse = app
+            .wrap()
+            .query_wasm_smart(contract_addr, &QueryMsg::SolvencyReport {})
+            .unwrap();
+        assert_eq!(report.accounted_liabilities, Uint128::new(210));
+        assert_eq!(report.actual_balance, Uint128::new(260));
+        assert_eq!(report.free, Uint128::new(50));
+        assert_eq!(report.shortfall, Uint128::zero());
+    }
+
+    #[test]
+    fn owner_actions_batch() {
+        let (mut app, contract_addr) = base_scenario();
+
+        let send_msg = |amount: u128| -> cosmwasm_std::CosmosMsg {
+            cosmwasm_std::CosmosMsg::Bank(cosmwasm_std::BankMsg::Send {
+                to_address: ADMIN.to_string(),
+                amount: vec![coin(amount, DENOM)],
+            })
+        };
+
+        // batch length is capped
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            contract_addr.clone(),
+            &ExecuteMsg::OwnerActions {
+                msgs: vec![send_msg(1); MAX_OWNER_MSGS + 1],
+            },
+            &[],
+        )
+        .unwrap_err();
+
+        // one disallowed message reverts the whole batch
+        let disallowed = cosmwasm_std::CosmosMsg::Wasm(cosmwasm_std::WasmMsg::Execute {
+            contract_addr: "not_allowed".to_string(),
+            msg: cosmwasm_std::to_binary(&ExecuteMsg::Deposit { nonce: None }).unwrap(),
+            funds: vec![],
+        });
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            contract_addr.clone(),
+            &ExecuteMsg::OwnerActions {
+                msgs: vec![send_msg(10), disallowed],
+            },
+            &[],
+        )
+        .unwrap_err();
+
+        let bal = app.wrap().query_balance(ADMIN, DENOM).unwrap();
+        assert_eq!(bal.amount, Uint128::zero());
+
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            contract_addr,
+            &ExecuteMsg::OwnerActions {
+                msgs: vec![send_msg(10), send_msg(20)],
+            },
+            &[],
+        )
+        .unwrap();
+
+        let bal = app.wrap().query_balance(ADMIN, DENOM).unwrap();
+        assert_eq!(bal.amount, Uint128::new(30));
+    }
+
+    #[test]
+    fn renounce_ownership() {
+        let (mut app, contract_addr) = base_scenario();
+
+        let noop_msg = cosmwasm_std::CosmosMsg::Bank(cosmwasm_std::BankMsg::Send {
+            to_address: ADMIN.to_string(),
+            amount: vec![coin(1, DENOM)],
+        });
+
+        // USER2 is the current owner after the base scenario deposits
+        let owner: Option<Addr> = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::Owner {})
+            .unwrap();
+        let owner = owner.unwrap();
+        assert_eq!(owner, Addr::unchecked(USER2));
+
+        // acknowledging requires a pending renounce
+        let err = app
+            .execute_contract(
+                owner.clone(),
+                contract_addr.clone(),
+                &ExecuteMsg::AcknowledgeRenounce {},
+                &[],
+            )
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ContractError>(),
+            Some(ContractError::NoPendingRenounce {})
+        ));
+
+        app.execute_contract(
+            owner.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::RenounceOwnership {},
+            &[],
+        )
+        .unwrap();
+
+        // owner keeps control until acknowledged
+        let ownership: OwnershipResponse = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::Ownership {})
+            .unwrap();
+        assert_eq!(ownership.pending_renounce, Some(owner.clone()));
+        assert!(!ownership.renounced);
+
+        app.execute_contract(
+            owner.clone(),
+            contract_addr.clone(),
+            &ExecuteMsg::AcknowledgeRenounce {},
+            &[],
+        )
+        .unwrap();
+
+        let ownership: OwnershipResponse = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::Ownership {})
+            .unwrap();
+        assert_eq!(ownership.owner, None);
+        assert!(ownership.renounced);
+
+        app.execute_contract(
+            owner,
+            contract_addr.clone(),
+            &ExecuteMsg::OwnerAction {
+                msg: noop_msg.clone(),
+            },
+            &[],
+        )
+        .unwrap_err();
+
+        // a new top depositor does not regain owner access either
+        app = mint_tokens(app, "hacker".to_string(), Uint128::from(1_000u128));
+        app.execute_contract(
+            Addr::unchecked("hacker"),
+            contract_addr.clone(),
+            &ExecuteMsg::Deposit { nonce: None },
+            &[coin(1_000, DENOM)],
+        )
+        .unwrap();
+
+        app.execute_contract(
+            Addr::unchecked("hacker"),
+            contract_addr,
+            &ExecuteMsg::OwnerAction { msg: noop_msg },
+            &[],
+        )
+        .unwrap_err();
+    }
+
+    #[test]
+    fn renounce_cleared_on_owner_change() {
+        let (mut app, contract_addr) = base_scenario();
+
+        // USER2 is the current owner after the base scenario deposits
+        app.execute_contract(
+            Addr::unchecked(USER2),
+            contract_addr.clone(),
+            &ExecuteMsg::RenounceOwnership {},
+            &[],
+        )
+        .unwrap();
+
+        // USER1 takes over before the renounce is acknowledged
+        app = mint_tokens(app, USER1.to_string(), Uint128::from(20u128));
+        app.execute_contract(
+            Addr::unchecked(USER1),
+            contract_addr.clone(),
+            &ExecuteMsg::Deposit { nonce: None },
+            &[coin(20, DENOM)],
+        )
+        .unwrap();
+
+        let ownership: OwnershipResponse = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::Ownership {})
+            .unwrap();
+        assert_eq!(ownership.owner, Some(Addr::unchecked(USER1)));
+        assert_eq!(ownership.pending_renounce, None);
+
+        // the new owner cannot confirm a renounce it never asked for
+        let err = app
+            .execute_contract(
+                Addr::unchecked(USER1),
+                contract_addr.clone(),
+                &ExecuteMsg::AcknowledgeRenounce {},
+                &[],
+            )
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ContractError>(),
+            Some(ContractError::NoPendingRenounce {})
+        ));
+
+        // and the previous owner no longer can
+        let err = app
+            .execute_contract(
+                Addr::unchecked(USER2),
+                contract_addr.clone(),
+                &ExecuteMsg::AcknowledgeRenounce {},
+                &[],
+            )
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ContractError>(),
+            Some(ContractError::Unauthorized {})
+        ));
+
+        let ownership: OwnershipResponse = app
+            .wrap()
+            .query_wasm_smart(contract_addr, &QueryMsg::Ownership {})
+            .unwrap();
+        assert!(!ownership.renounced);
+    }
+
+    #[test]
+    fn time_weighted_top() {
+        let mut app = App::default();
+        let cw_template_id = app.store_code(challenge_contract());
+
+        let msg = InstantiateMsg {
+            owner: ADMIN.to_string(),
+            admin: ADMIN.to_string(),
+            threshold: Uint128::from(99u128),
+            refund_excess: false,
+            time_weighted_top: true,
+        };
+        let contract_addr = app
+            .instantiate_contract(
+                cw_template_id,
+                Addr::unchecked(ADMIN),
+                &msg,
+                &[],
+                "test",
+                None,
+            )
+            .unwrap();
+
+        // small deposit held for a long time
+        app = mint_tokens(app, USER1.to_string(), Uint128::from(100u128));
+        app.execute_contract(
+            Addr::unchecked(USER1),
+            contract_addr.clone(),
+            &ExecuteMsg::Deposit { nonce: None },
+            &[coin(100, DENOM)],
+        )
+        .unwrap();
+
+        app.update_block(|block| {
+            block.time = block.time.plus_seconds(1_000);
+        });
+
+        // large deposit right before the snapshot
+        app = mint_tokens(app, USER2.to_string(), Uint128::from(10_000u128));
+        app.execute_contract(
+            Addr::unchecked(USER2),
+            contract_addr.clone(),
+            &ExecuteMsg::Deposit { nonce: None },
+            &[coin(10_000, DENOM)],
+        )
+        .unwrap();
+
+        app.update_block(|block| {
+            block.time = block.time.plus_seconds(1);
+        });
+
+        let top: Addr = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::Top {})
+            .unwrap();
+        assert_eq!(top, Addr::unchecked(USER1));
+
+        let err = app
+            .execute_contract(
+                Addr::unchecked(USER2),
+                contract_addr.clone(),
+                &ExecuteMsg::ClaimTop {},
+                &[],
+            )
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ContractError>(),
+            Some(ContractError::ScoreTooLow {})
+        ));
+
+        // the large balance eventually catches up and can claim the top
+        app.update_block(|block| {
+            block.time = block.time.plus_seconds(100);
+        });
+        app.execute_contract(
+            Addr::unchecked(USER2),
+            contract_addr.clone(),
+            &ExecuteMsg::ClaimTop {},
+            &[],
+        )
+        .unwrap();
+
+        let top: Addr = app
+            .wrap()
+            .query_wasm_smart(contract_addr, &QueryMsg::Top {})
+            .unwrap();
+        assert_eq!(top, Addr::unchecked(USER2));
+    }
+
+    #[test]
+    fn protocol_config() {
+        let (mut app, contract_addr) = proper_instantiate();
+
+        let config: ProtocolConfigResponse = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::ProtocolConfig {})
+            .unwrap();
+        assert_eq!(config.denom, DENOM);
+        assert_eq!(config.lock_period, None);
+        assert_eq!(config.minimum_deposit, None);
+
+        // deposits are accepted in the reported denom
+        app.execute_contract(
+            Addr::unchecked(USER1),
+            contract_addr,
+            &ExecuteMsg::Deposit { nonce: None },
+            &[coin(100, config.denom)],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn deposit_payment_errors() {
+        let (mut app, contract_addr) = proper_instantiate();
+
+        app.sudo(cw_multi_test::SudoMsg::Bank(
+            cw_multi_test::BankSudo::Mint {
+                to_address: USER1.to_string(),
+                amount: vec![coin(100, "uother")],
+            },
+        ))
+        .unwrap();
+
+        let cases = [
+            (vec![], PaymentError::NoFunds {}),
+            (
+                vec![coin(10, "uother")],
+                PaymentError::WrongDenom {
+                    expected: DENOM.to_string(),
+                    received: "uother".to_string(),
+                },
+            ),
+            (
+                vec![coin(10, "uother"), coin(10, DENOM)],
+                PaymentError::MultipleDenoms {},
+            ),
+        ];
+
+        for (funds, expected) in cases {
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(USER1),
+                    contract_addr.clone(),
+                    &ExecuteMsg::Deposit { nonce: None },
+                    &funds,
+                )
+                .unwrap_err();
+            assert!(matches!(
+                err.downcast_ref::<ContractError>(),
+                Some(ContractError::Payment(err)) if *err == expected
+            ));
+        }
+    }
+
+    #[test]
+    fn ibc_withdrawal() {
+        // multi-test has no IBC support, so the messages are inspected directly
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        let msg = InstantiateMsg {
+            owner: ADMIN.to_string(),
+            admin: ADMIN.to_string(),
+            threshold: Uint128::from(99u128),
+            refund_excess: false,
+            time_weighted_top: false,
+        };
+        crate::contract::instantiate(deps.as_mut(), env.clone(), mock_info(ADMIN, &[]), msg)
+            .unwrap();
+
+        let info = mock_info(USER1, &[coin(100, DENOM)]);
+        crate::contract::execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            ExecuteMsg::Deposit { nonce: None },
+        )
+        .unwrap();
+
+        // recipient must look like an address
+        let msg = ExecuteMsg::Withdraw {
+            amount: Uint128::new(40),
+            denom: None,
+            ibc_destination: Some(IbcDestination {
+                channel_id: "channel-7".to_string(),
+                recipient: "not an address".to_string(),
+                timeout_seconds: None,
+            }),
+        };
+        crate::contract::execute(deps.as_mut(), env.clone(), mock_info(USER1, &[]), msg)
+            .unwrap_err();
+
+        let msg = ExecuteMsg::Withdraw {
+            amount: Uint128::new(40),
+            denom: None,
+            ibc_destination: Some(IbcDestination {
+                channel_id: "channel-7".to_string(),
+                recipient: "osmo1remote".to_string(),
+                timeout_seconds: None,
+            }),
+        };
+        let res = crate::contract::execute(deps.as_mut(), env.clone(), mock_info(USER1, &[]), msg)
+            .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Ibc(IbcMsg::Transfer {
+                channel_id: "channel-7".to_string(),
+                to_address: "osmo1remote".to_string(),
+                amount: coin(40, DENOM),
+                timeout: IbcTimeout::with_timestamp(
+                    env.block.time.plus_seconds(DEFAULT_IBC_TIMEOUT)
+                ),
+            })
+        );
+
+        // falls back to a local transfer without a destination
+        let msg = ExecuteMsg::Withdraw {
+            amount: Uint128::new(60),
+            denom: None,
+            ibc_destination: None,
+        };
+        let res = crate::contract::execute(deps.as_mut(), env, mock_info(USER1, &[]), msg).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: USER1.to_string(),
+                amount: vec![coin(60, DENOM)],
+            })
+        );
+    }
+
+    #[test]
+    fn query_snapshots() {
+        let (mut app, contract_addr) = proper_instantiate();
+
+        app.execute_contract(
+            Addr::unchecked(USER1),
+            contract_addr.clone(),
+            &ExecuteMsg::Deposit { nonce: None },
+            &[coin(100, DENOM)],
+        )
+        .unwrap();
+
+        let config: ConfigQueryResponse = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::Config {})
+            .unwrap();
+        assert_json_snapshot!("config", config);
+
+        let msg = QueryMsg::UserBalance {
+            address: USER1.to_string(),
+        };
+        let balance: Uint128 = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &msg)
+            .unwrap();
+        assert_json_snapshot!("user_balance", balance);
+
+        let top: Addr = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::Top {})
+            .unwrap();
+        assert_json_snapshot!("top", top);
+
+        let owner: Option<Addr> = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::Owner {})
+            .unwrap();
+        assert_json_snapshot!("owner", owner);
+
+        let admin: Addr = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::Admin {})
+            .unwrap();
+        assert_json_snapshot!("admin", admin);
+
+        let ownership: OwnershipResponse = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::Ownership {})
+            .unwrap();
+        assert_json_snapshot!("ownership", ownership);
+
+        let targets: Vec<Addr> = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::AllowedTargets {})
+            .unwrap();
+        assert_json_snapshot!("allowed_targets", targets);
+
+        let report: SolvencyReportResponse = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::SolvencyReport {})
+            .unwrap();
+        assert_json_snapshot!("solvency_report", report);
+
+        let msg = QueryMsg::AuditLog {
+            start_after: None,
+            limit: None,
+        };
+        let audit_log: AuditLogResponse = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &msg)
+            .unwrap();
+        assert_json_snapshot!("audit_log", audit_log);
+
+        let protocol_config: ProtocolConfigResponse = app
+            .wrap()
+            .query_wasm_smart(contract_addr, &QueryMsg::ProtocolConfig {})
+            .unwrap();
+        assert_json_snapshot!("protocol_config", protocol_config);
+    }
+
+    /// Storage counting every access, a deterministic stand-in for gas
+    #[derive(Default)]
+    struct CountingStorage {
+        inner: MockStorage,
+        ops: Cell<u64>,
+    }
+
+    impl Storage for CountingStorage {
+        fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+            self.ops.set(self.ops.get() + 1);
+            self.inner.get(key)
+        }
+
+        fn range<'a>(
+            &'a self,
+            start: Option<&[u8]>,
+            end: Option<&[u8]>,
+            order: Order,
+        ) -> Box<dyn Iterator<Item = Record> + 'a> {
+            self.ops.set(self.ops.get() + 1);
+            self.inner.range(start, end, order)
+        }
+
+        fn set(&mut self, key: &[u8], value: &[u8]) {
+            self.ops.set(self.ops.get() + 1);
+            self.inner.set(key, value)
+        }
+
+        fn remove(&mut self, key: &[u8]) {
+            self.ops.set(self.ops.get() + 1);
+            self.inner.remove(key)
+        }
+    }
+
+    #[test]
+    fn deposit_cost_is_flat() {
+        let mut deps = OwnedDeps {
+            storage: CountingStorage::default(),
+            api: MockApi::default(),
+            querier: MockQuerier::default(),
+            custom_query_type: PhantomData::<Empty>,
+        };
+        let env = mock_env();
+
+        let msg = InstantiateMsg {
+            owner: ADMIN.to_string(),
+            admin: ADMIN.to_string(),
+            threshold: Uint128::from(99u128),
+            refund_excess: false,
+            time_weighted_top: false,
+        };
+        crate::contract::instantiate(deps.as_mut(), env.clone(), mock_info(ADMIN, &[]), msg)
+            .unwrap();
+
+        let mut plain = vec![];
+        let mut takeover = vec![];
+
+        for i in 0..500u128 {
+            let user = format!("depositor{}", i);
+            // every tenth depositor takes the lead
+            let amount = if i % 10 == 0 { 100 + i } else { 50 };
+
+            deps.storage.ops.set(0);
+            let info = mock_info(&user, &[coin(amount, DENOM)]);
+            crate::contract::execute(
+                deps.as_mut(),
+                env.clone(),
+                info,
+                ExecuteMsg::Deposit { nonce: None },
+            )
+            .unwrap();
+
+            if i % 10 == 0 {
+                takeover.push(deps.storage.ops.get());
+            } else {
+                plain.push(deps.storage.ops.get());
+            }
+        }
+
+        // storage accesses don't grow with the number of depositors
+        assert!(plain.iter().all(|ops| *ops == plain[0]));
+        assert!(takeover.iter().all(|ops| *ops == takeover[0]));
+    }
+
+    #[test]
+    fn balance_overflow_rejected() {
+        // bank balances can't reach u128::MAX in multi-test, so the contract is called directly
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        let msg = InstantiateMsg {
+            owner: ADMIN.to_string(),
+            admin: ADMIN.to_string(),
+            threshold: Uint128::from(99u128),
+            refund_excess: false,
+            time_weighted_top: false,
+        };
+        crate::contract::instantiate(deps.as_mut(), env.clone(), mock_info(ADMIN, &[]), msg)
+            .unwrap();
+
+        let info = mock_info(USER1, &[coin(u128::MAX, DENOM)]);
+        crate::contract::execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            ExecuteMsg::Deposit { nonce: None },
+        )
+        .unwrap();
+
+        // errors instead of wrapping or panicking
+        let info = mock_info(USER1, &[coin(1, DENOM)]);
+        crate::contract::execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            ExecuteMsg::Deposit { nonce: None },
+        )
+        .unwrap_err();
+
+        let info = mock_info(USER2, &[coin(10, DENOM)]);
+        crate::contract::execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            ExecuteMsg::Deposit { nonce: None },
+        )
+        .unwrap();
+
+        let msg = ExecuteMsg::Withdraw {
+            amount: Uint128::new(11),
+            denom: None,
+            ibc_destination: None,
+        };
+        crate::contract::execute(deps.as_mut(), env, mock_info(USER2, &[]), msg).unwrap_err();
+    }
+
+    #[test]
+    fn zero_withdraw_rejected() {
+        let (mut app, contract_addr) = base_scenario();
+
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            contract_addr.clone(),
+            &ExecuteMsg::AddAcceptedDenom {
+                denom: "uother".to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+
+        for denom in [None, Some("uother".to_string())] {
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(USER1),
+                    contract_addr.clone(),
+                    &ExecuteMsg::Withdraw {
+                        amount: Uint128::zero(),
+                        denom,
+                        ibc_destination: None,
+                    },
+                    &[],
+                )
+                .unwrap_err();
+            assert!(matches!(
+                err.downcast_ref::<ContractError>(),
+                Some(ContractError::ZeroAmount {})
+            ));
+        }
+
+        // nothing is recorded for the rejected withdrawals
+        let history: UserDepositHistoryResponse = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr,
+                &QueryMsg::UserDepositHistory {
+                    user: USER1.to_string(),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(history.events.len(), 1);
+    }
+
+    #[test]
+    fn user_deposit_history() {
+        let (mut app, contract_addr) = base_scenario();
+
+        app.execute_contract(
+            Addr::unchecked(USER1),
+            contract_addr.clone(),
+            &ExecuteMsg::Withdraw {
+                amount: Uint128::new(40),
+                denom: None,
+                ibc_destination: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+        let history: UserDepositHistoryResponse = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::UserDepositHistory {
+                    user: USER1.to_string(),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(history.events.len(), 2);
+        assert_eq!(history.events[0].action, "deposit");
+        assert_eq!(history.events[0].amount, Uint128::new(100));
+        assert_eq!(history.events[0].balance, Uint128::new(100));
+        assert_eq!(history.events[1].action, "withdraw");
+        assert_eq!(history.events[1].amount, Uint128::new(40));
+        assert_eq!(history.events[1].balance, Uint128::new(60));
+
+        // other users keep their own log
+        let history: UserDepositHistoryResponse = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::UserDepositHistory {
+                    user: USER2.to_string(),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(history.events.len(), 1);
+        assert_eq!(history.events[0].balance, Uint128::new(110));
+
+        // paginate past the first entry
+        let history: UserDepositHistoryResponse = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr,
+                &QueryMsg::UserDepositHistory {
+                    user: USER1.to_string(),
+                    start_after: Some(1),
+                    limit: Some(1),
+                },
+            )
+            .unwrap();
+        assert_eq!(history.events.len(), 1);
+        assert_eq!(history.events[0].id, 2);
+    }
+
+    #[test]
+    fn owner_action_self_call_rejected() {
+        let (mut app, contract_addr) = proper_instantiate();
+
+        // allowlisting the contract itself does not permit re-entry
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            contract_addr.clone(),
+            &ExecuteMsg::AddAllowedTarget {
+                address: contract_addr.to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+
+        let self_execute = CosmosMsg::Wasm(cosmwasm_std::WasmMsg::Execute {
+            contract_addr: contract_addr.to_string(),
+            msg: cosmwasm_std::to_binary(&ExecuteMsg::UpdateConfig {
+                new_threshold: Uint128::from(500u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        });
+        let self_send = CosmosMsg::Bank(BankMsg::Send {
+            to_address: contract_addr.to_string(),
+            amount: vec![coin(1, DENOM)],
+        });
+
+        for msg in [self_execute, self_send] {
+            let err = app
+                .execute_contract(
+                    Addr::unchecked(ADMIN),
+                    contract_addr.clone(),
+                    &ExecuteMsg::OwnerAction { msg },
+                    &[],
+                )
+                .unwrap_err();
+            assert!(matches!(
+                err.downcast_ref::<ContractError>(),
+                Some(ContractError::SelfCallNotAllowed {})
+            ));
+        }
+    }
+
+    #[test]
+    fn deposit_nonce() {
+        let (mut app, contract_addr) = proper_instantiate();
+
+        app.execute_contract(
+            Addr::unchecked(USER1),
+            contract_addr.clone(),
+            &ExecuteMsg::Deposit { nonce: Some(7) },
+            &[coin(10, DENOM)],
+        )
+        .unwrap();
+
+        // a retried deposit is rejected and the funds stay with the sender
+        let err = app
+            .execute_contract(
+                Addr::unchecked(USER1),
+                contract_addr.clone(),
+                &ExecuteMsg::Deposit { nonce: Some(7) },
+                &[coin(10, DENOM)],
+            )
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ContractError>(),
+            Some(ContractError::DuplicateNonce {})
+        ));
+
+        let balance: Uint128 = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::UserBalance {
+                    address: USER1.to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(balance, Uint128::new(10));
+        let user_bal = app.wrap().query_balance(USER1, DENOM).unwrap();
+        assert_eq!(user_bal.amount, Uint128::new(90));
+
+        // nonces are tracked per sender
+        app = mint_tokens(app, USER2.to_string(), Uint128::from(10u128));
+        app.execute_contract(
+            Addr::unchecked(USER2),
+            contract_addr,
+            &ExecuteMsg::Deposit { nonce: Some(7) },
+            &[coin(10, DENOM)],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn reindex_repairs_top_depositor() {
+        // the pointer can only be corrupted through storage, so the contract is called directly
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        let msg = InstantiateMsg {
+            owner: ADMIN.to_string(),
+            admin: ADMIN.to_string(),
+            threshold: Uint128::from(99u128),
+            refund_excess: false,
+            time_weighted_top: false,
+        };
+        crate::contract::instantiate(deps.as_mut(), env.clone(), mock_info(ADMIN, &[]), msg)
+            .unwrap();
+
+        for (user, amount) in [(USER1, 100), ("carol", 150), (USER2, 120)] {
+            let info = mock_info(user, &[coin(amount, DENOM)]);
+            crate::contract::execute(
+                deps.as_mut(),
+                env.clone(),
+                info,
+                ExecuteMsg::Deposit { nonce: None },
+            )
+            .unwrap();
+        }
+
+        TOP_DEPOSITOR
+            .save(deps.as_mut().storage, &Addr::unchecked(USER2))
+            .unwrap();
+        TOTAL_DEPOSITS
+            .save(deps.as_mut().storage, &Uint128::from(1u128))
+            .unwrap();
+
+        let reindex = |start_after: Option<&str>| ExecuteMsg::Reindex {
+            start_after: start_after.map(|addr| addr.to_string()),
+            limit: Some(1),
+        };
+
+        // only the admin can reindex
+        crate::contract::execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(USER1, &[]),
+            reindex(None),
+        )
+        .unwrap_err();
+
+        let res = crate::contract::execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADMIN, &[]),
+            reindex(None),
+        )
+        .unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|attr| attr.key == "finished" && attr.value == "false"));
+
+        // pages can't be skipped
+        let err = crate::contract::execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADMIN, &[]),
+            reindex(Some(USER1)),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::ReindexOutOfOrder {}));
+
+        let mut start_after = "carol".to_string();
+        loop {
+            let res = crate::contract::execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(ADMIN, &[]),
+                reindex(Some(&start_after)),
+            )
+            .unwrap();
+
+            let next = res
+                .attributes
+                .iter()
+                .find(|attr| attr.key == "next_start_after");
+            match next {
+                Some(next) => start_after = next.value.clone(),
+                None => break,
+            }
+        }
+
+        let top = crate::contract::query_top_depositor(deps.as_ref()).unwrap();
+        assert_eq!(top, Addr::unchecked("carol"));
+
+        let total = TOTAL_DEPOSITS.load(deps.as_ref().storage).unwrap();
+        assert_eq!(total, Uint128::from(370u128));
+    }
+
+    #[test]
+    fn pause_deposits_only() {
+        let (mut app, contract_addr) = base_scenario();
+
+        let pause = ExecuteMsg::SetPause {
+            deposits_paused: true,
+            withdrawals_paused: false,
+        };
+        app.execute_contract(Addr::unchecked(USER1), contract_addr.clone(), &pause, &[])
+            .unwrap_err();
+        app.execute_contract(Addr::unchecked(ADMIN), contract_addr.clone(), &pause, &[])
+            .unwrap();
+
+        app = mint_tokens(app, USER1.to_string(), Uint128::from(100u128));
+        app.execute_contract(
+            Addr::unchecked(USER1),
+            contract_addr.clone(),
+            &ExecuteMsg::Deposit { nonce: None },
+            &[coin(100, DENOM)],
+        )
+        .unwrap_err();
+
+        // users can still exit
+        app.execute_contract(
+            Addr::unchecked(USER1),
+            contract_addr.clone(),
+            &ExecuteMsg::Withdraw {
+                amount: Uint128::from(100u128),
+                denom: None,
+                ibc_destination: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+        let config: ConfigQueryResponse = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::Config {})
+            .unwrap();
+        assert!(config.pause.deposits_paused);
+        assert!(!config.pause.withdrawals_paused);
+
+        let pause = ExecuteMsg::SetPause {
+            deposits_paused: false,
+            withdrawals_paused: true,
+        };
+        app.execute_contract(Addr::unchecked(ADMIN), contract_addr.clone(), &pause, &[])
+            .unwrap();
+
+        app.execute_contract(
+            Addr::unchecked(USER2),
+            contract_addr,
+            &ExecuteMsg::Withdraw {
+                amount: Uint128::from(110u128),
+                denom: None,
+                ibc_destination: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+    }
+
+    #[test]
+    fn multi_denom_deposits() {
+        let (mut app, contract_addr) = base_scenario();
+
+        app.sudo(cw_multi_test::SudoMsg::Bank(
+            cw_multi_test::BankSudo::Mint {
+                to_address: USER1.to_string(),
+                amount: vec![coin(500, "uother")],
+            },
+        ))
+        .unwrap();
+
+        let msg = ExecuteMsg::AddAcceptedDenom {
+            denom: "uother".to_string(),
+        };
+        app.execute_contract(Addr::unchecked(USER1), contract_addr.clone(), &msg, &[])
+            .unwrap_err();
+        app.execute_contract(Addr::unchecked(ADMIN), contract_addr.clone(), &msg, &[])
+            .unwrap();
+
+        let denoms: Vec<String> = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::AcceptedDenoms {})
+            .unwrap();
+        assert_eq!(denoms, vec!["uother".to_string()]);
+
+        app.execute_contract(
+            Addr::unchecked(USER1),
+            contract_addr.clone(),
+            &ExecuteMsg::Deposit { nonce: None },
+            &[coin(500, "uother")],
+        )
+        .unwrap();
+
+        // the contest balance and top depositor are untouched
+        let balance: Uint128 = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::UserBalance {
+                    address: USER1.to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(balance, Uint128::new(100));
+
+        let top: Option<DenomTop> = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::DenomTop {
+                    denom: DENOM.to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            top,
+            Some(DenomTop {
+                address: Addr::unchecked(USER2),
+                balance: Uint128::new(110),
+            })
+        );
+
+        let top: Option<DenomTop> = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::DenomTop {
+                    denom: "uother".to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            top,
+            Some(DenomTop {
+                address: Addr::unchecked(USER1),
+                balance: Uint128::new(500),
+            })
+        );
+
+        // each denom is withdrawn from its own balance
+        let err = app
+            .execute_contract(
+                Addr::unchecked(USER1),
+                contract_addr.clone(),
+                &ExecuteMsg::Withdraw {
+                    amount: Uint128::new(200),
+                    denom: None,
+                    ibc_destination: None,
+                },
+                &[],
+            )
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ContractError>(),
+            Some(ContractError::Overflow(_))
+        ));
+
+        app.execute_contract(
+            Addr::unchecked(USER1),
+            contract_addr.clone(),
+            &ExecuteMsg::Withdraw {
+                amount: Uint128::new(200),
+                denom: Some("uother".to_string()),
+                ibc_destination: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+        let balance = app.wrap().query_balance(USER1, "uother").unwrap().amount;
+        assert_eq!(balance, Uint128::new(200));
+        let balance = app.wrap().query_balance(USER1, DENOM).unwrap().amount;
+        assert_eq!(balance, Uint128::zero());
+
+        let balance: Uint128 = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::DenomBalance {
+                    address: USER1.to_string(),
+                    denom: "uother".to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(balance, Uint128::new(300));
+
+        // removed denoms can still be withdrawn but no longer deposited
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            contract_addr.clone(),
+            &ExecuteMsg::RemoveAcceptedDenom {
+                denom: "uother".to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+
+        app.execute_contract(
+            Addr::unchecked(USER1),
+            contract_addr.clone(),
+            &ExecuteMsg::Deposit { nonce: None },
+            &[coin(200, "uother")],
+        )
+        .unwrap_err();
+
+        app.execute_contract(
+            Addr::unchecked(USER1),
+            contract_addr.clone(),
+            &ExecuteMsg::Withdraw {
+                amount: Uint128::new(300),
+                denom: Some("uother".to_string()),
+                ibc_destination: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+        let balance = app.wrap().query_balance(USER1, "uother").unwrap().amount;
+        assert_eq!(balance, Uint128::new(500));
+
+        // the next highest balance takes over once the top withdraws everything
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            contract_addr.clone(),
+            &ExecuteMsg::AddAcceptedDenom {
+                denom: "uthird".to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+        for (user, amount) in [(USER1, 100), (USER2, 50)] {
+            app.sudo(cw_multi_test::SudoMsg::Bank(
+                cw_multi_test::BankSudo::Mint {
+                    to_address: user.to_string(),
+                    amount: vec![coin(amount, "uthird")],
+                },
+            ))
+            .unwrap();
+            app.execute_contract(
+                Addr::unchecked(user),
+                contract_addr.clone(),
+                &ExecuteMsg::Deposit { nonce: None },
+                &[coin(amount, "uthird")],
+            )
+            .unwrap();
+        }
+
+        app.execute_contract(
+            Addr::unchecked(USER1),
+            contract_addr.clone(),
+            &ExecuteMsg::Withdraw {
+                amount: Uint128::new(100),
+                denom: Some("uthird".to_string()),
+                ibc_destination: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+        let msg = QueryMsg::DenomTop {
+            denom: "uthird".to_string(),
+        };
+        let top: Option<DenomTop> = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &msg)
+            .unwrap();
+        assert_eq!(
+            top,
+            Some(DenomTop {
+                address: Addr::unchecked(USER2),
+                balance: Uint128::new(50),
+            })
+        );
+
+        // and the top is cleared once nobody holds the denom
+        app.execute_contract(
+            Addr::unchecked(USER2),
+            contract_addr.clone(),
+            &ExecuteMsg::Withdraw {
+                amount: Uint128::new(50),
+                denom: Some("uthird".to_string()),
+                ibc_destination: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+        let top: Option<DenomTop> = app.wrap().query_wasm_smart(contract_addr, &msg).unwrap();
+        assert_eq!(top, None);
+    }
+
+    #[test]
+    fn top_depositor_ties() {
+        let mut deps = mock_dependencies();
+        let env = mock_env();
+
+        let msg = InstantiateMsg {
+            owner: ADMIN.to_string(),
+            admin: ADMIN.to_string(),
+            threshold: Uint128::from(99u128),
+            refund_excess: false,
+            time_weighted_top: false,
+        };
+        crate::contract::instantiate(deps.as_mut(), env.clone(), mock_info(ADMIN, &[]), msg)
+            .unwrap();
+
+        // "zed" reaches the balance first but sorts after "amy"
+        for user in ["zed", "amy"] {
+            crate::contract::execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(user, &[coin(100, DENOM)]),
+                ExecuteMsg::Deposit { nonce: None },
+            )
+            .unwrap();
+        }
+
+        let top = crate::contract::query_top_depositor(deps.as_ref()).unwrap();
+        assert_eq!(top, Addr::unchecked("zed"));
+
+        // a full reindex settles the tie the same way
+        TOP_DEPOSITOR
+            .save(deps.as_mut().storage, &Addr::unchecked("amy"))
+            .unwrap();
+        crate::contract::execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(ADMIN, &[]),
+            ExecuteMsg::Reindex {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+
+        let top = crate::contract::query_top_depositor(deps.as_ref()).unwrap();
+        assert_eq!(top, Addr::unchecked("zed"));
+
+        // equal time-weighted scores, challenged through `ClaimTop`
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg {
+            owner: ADMIN.to_string(),
+            admin: ADMIN.to_string(),
+            threshold: Uint128::from(99u128),
+            refund_excess: false,
+            time_weighted_top: true,
+        };
+        crate::contract::instantiate(deps.as_mut(), env.clone(), mock_info(ADMIN, &[]), msg)
+            .unwrap();
+
+        for user in ["zed", "amy"] {
+            crate::contract::execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info(user, &[coin(100, DENOM)]),
+                ExecuteMsg::Deposit { nonce: None },
+            )
+            .unwrap();
+        }
+
+        let mut later = env;
+        later.block.time = later.block.time.plus_seconds(1_000);
+        let err = crate::contract::execute(
+            deps.as_mut(),
+            later,
+            mock_info("amy", &[]),
+            ExecuteMsg::ClaimTop {},
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::ScoreTooLow {}));
+
+        let top = crate::contract::query_top_depositor(deps.as_ref()).unwrap();
+        assert_eq!(top, Addr::unchecked("zed"));
+    }
+
+    #[test]
+    fn counter_overflow_rejected() {
+        let mut deps = mock_dependencies();
+        let user = Addr::unchecked(USER1);
+
+        BALANCE_SEQ_COUNT
+            .save(deps.as_mut().storage, &u64::MAX)
+            .unwrap();
+        AUDIT_COUNT.save(deps.as_mut().storage, &u64::MAX).unwrap();
+        BALANCE_HISTORY_COUNT
+            .save(deps.as_mut().storage, &user, &u64::MAX)
+            .unwrap();
+
+        // errors instead of wrapping around to reuse ids
+        bump_balance_seq(deps.as_mut().storage, &user).unwrap_err();
+        record_audit(deps.as_mut().storage, 1, "transfer_ownership", None, None).unwrap_err();
+        record_balance_event(
+            deps.as_mut().storage,
+            &user,
+            1,
+            "deposit",
+            Uint128::one(),
+            Uint128::one(),
+        )
+        .unwrap_err();
+    }
 }
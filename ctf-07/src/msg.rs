@@ -1,30 +1,148 @@
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{Addr, CosmosMsg, Uint128};
+use ctf_common::ibc::IbcDestination;
+use ctf_common::pause::PauseState;
+
+use crate::state::{AuditEntry, BalanceEvent};
 
 #[cw_serde]
 pub struct InstantiateMsg {
     pub owner: String,
+    pub admin: String,
     pub threshold: Uint128,
+    /// Refunds deposits beyond one token above the threshold, the minimum needed to become
+    /// the top depositor. Once on top, further deposits are rejected
+    pub refund_excess: bool,
+    pub time_weighted_top: bool,
 }
 
 #[cw_serde]
 pub enum ExecuteMsg {
-    Deposit {},
-    Withdraw { amount: Uint128 },
-    OwnerAction { msg: CosmosMsg },
-    UpdateConfig { new_threshold: Uint128 },
+    Deposit {
+        /// Rejects the deposit if this sender already used the nonce, making retries safe
+        nonce: Option<u64>,
+    },
+    Withdraw {
+        amount: Uint128,
+        /// Defaults to `DENOM`
+        denom: Option<String>,
+        /// Sends the withdrawal over ICS20 instead of to the sender's local account
+        ibc_destination: Option<IbcDestination>,
+    },
+    OwnerAction {
+        msg: CosmosMsg,
+    },
+    OwnerActions {
+        msgs: Vec<CosmosMsg>,
+    },
+    UpdateConfig {
+        new_threshold: Uint128,
+    },
+    AddAllowedTarget {
+        address: String,
+    },
+    RemoveAllowedTarget {
+        address: String,
+    },
+    RenounceOwnership {},
+    AcknowledgeRenounce {},
+    /// Recomputes the top depositor and deposit total from user balances, one page per call.
+    /// A scan starts with `start_after: None` and continues from the last address scanned
+    Reindex {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Takes the time-weighted top from the current leader once the sender's score
+    /// ranks above it, for scores that overtook without a balance change
+    ClaimTop {},
+    /// Accept deposits in another native denom, admin only
+    AddAcceptedDenom {
+        denom: String,
+    },
+    /// Stop accepting deposits in a denom, existing balances can still be withdrawn
+    RemoveAcceptedDenom {
+        denom: String,
+    },
+    /// Pause deposits and withdrawals independently, admin only
+    SetPause {
+        deposits_paused: bool,
+        withdrawals_paused: bool,
+    },
 }
 
 #[cw_serde]
 pub enum QueryMsg {
     Config {},
-    UserBalance { address: String },
+    UserBalance {
+        address: String,
+    },
+    /// With time-weighted ranking this is the stored leader. It is only challenged on balance
+    /// changes and `ClaimTop`, so it can lag behind a depositor whose score has since overtaken
+    /// it until they claim
     Top {},
+    Owner {},
+    Admin {},
+    Ownership {},
+    AllowedTargets {},
+    SolvencyReport {},
+    AuditLog {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    ProtocolConfig {},
+    UserDepositHistory {
+        user: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    AcceptedDenoms {},
+    DenomBalance {
+        address: String,
+        denom: String,
+    },
+    /// Returns `None` while nobody has deposited the denom
+    DenomTop {
+        denom: String,
+    },
 }
 
 // We define a custom struct for each query response
 #[cw_serde]
 pub struct ConfigQueryResponse {
-    pub owner: Addr,
+    pub owner: Option<Addr>,
+    pub admin: Addr,
     pub threshold: Uint128,
+    pub refund_excess: bool,
+    pub time_weighted_top: bool,
+    pub pause: PauseState,
+}
+
+#[cw_serde]
+pub struct AuditLogResponse {
+    pub entries: Vec<AuditEntry>,
+}
+
+#[cw_serde]
+pub struct UserDepositHistoryResponse {
+    pub events: Vec<BalanceEvent>,
+}
+
+#[cw_serde]
+pub struct SolvencyReportResponse {
+    /// Funds owed to users according to internal accounting
+    pub accounted_liabilities: Uint128,
+    /// Funds actually held by the contract
+    pub actual_balance: Uint128,
+    /// Surplus over the accounted liabilities
+    pub free: Uint128,
+    /// Liabilities not covered by the contract balance
+    pub shortfall: Uint128,
+}
+
+#[cw_serde]
+pub struct OwnershipResponse {
+    pub owner: Option<Addr>,
+    /// Owner that asked to renounce, cleared when the owner changes
+    pub pending_renounce: Option<Addr>,
+    pub renounced: bool,
 }
@@ -1,8 +1,302 @@
-use cosmwasm_std::{Addr, Uint128};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{
+    Addr, Empty, OverflowError, OverflowOperation, StdError, StdResult, Storage, Timestamp, Uint128,
+};
+use ctf_common::pause::PauseState;
 use cw_storage_plus::{Item, Map};
 
 pub const OWNER: Item<Addr> = Item::new("address");
 
+/// Manages configuration, has no access to funds
+pub const ADMIN: Item<Addr> = Item::new("admin");
+
+#[cw_serde]
+#[derive(Default)]
+pub struct OwnershipState {
+    /// Owner that asked to renounce and still has to acknowledge it
+    pub pending_renounce: Option<Addr>,
+    /// Ownership is permanently cleared
+    pub renounced: bool,
+}
+
+pub const OWNERSHIP: Item<OwnershipState> = Item::new("ownership");
+
 pub const THRESHOLD: Item<Uint128> = Item::new("config");
 
+/// Whether deposits are capped at the threshold with the excess refunded
+pub const REFUND_EXCESS: Item<bool> = Item::new("refund_excess");
+
+/// Whether `Top` ranks depositors by balance held over time
+pub const TIME_WEIGHTED_TOP: Item<bool> = Item::new("time_weighted_top");
+
+/// Deposits and withdrawals paused by the admin
+pub const PAUSE: Item<PauseState> = Item::new("pause");
+
 pub const BALANCES: Map<&Addr, Uint128> = Map::new("user_balances");
+/// Sum of `BALANCES`, updated alongside them so reports don't range over every user
+pub const TOTAL_DEPOSITS: Item<Uint128> = Item::new("total_deposits");
+
+/// Global sequence of the last balance change of each user. Ties for top depositor go to
+/// the lower sequence, i.e. the user that reached the tied balance first
+pub const BALANCE_SEQ: Map<&Addr, u64> = Map::new("balance_seq");
+pub const BALANCE_SEQ_COUNT: Item<u64> = Item::new("balance_seq_count");
+
+/// Stamps a user's balance change with the next global sequence
+pub fn bump_balance_seq(store: &mut dyn Storage, user: &Addr) -> StdResult<u64> {
+    let seq = next_count(BALANCE_SEQ_COUNT.may_load(store)?.unwrap_or_default())?;
+    BALANCE_SEQ_COUNT.save(store, &seq)?;
+    BALANCE_SEQ.save(store, user, &seq)?;
+    Ok(seq)
+}
+
+/// Increments a sequence counter, erroring instead of wrapping around
+fn next_count(count: u64) -> StdResult<u64> {
+    count
+        .checked_add(1)
+        .ok_or_else(|| StdError::overflow(OverflowError::new(OverflowOperation::Add, count, 1)))
+}
+
+/// Whether `(value, seq)` ranks above `(other_value, other_seq)` for top depositor,
+/// the higher value wins and ties go to the lower sequence
+pub fn ranks_above(value: Uint128, seq: u64, other_value: Uint128, other_seq: u64) -> bool {
+    value > other_value || (value == other_value && seq < other_seq)
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct TimeWeight {
+    /// Accumulated balance multiplied by seconds held
+    pub score: Uint128,
+    /// Time the score was last brought up to date
+    pub last_update: Timestamp,
+}
+
+pub const TIME_WEIGHTS: Map<&Addr, TimeWeight> = Map::new("time_weights");
+
+/// Leading depositor by time-weighted score. It is challenged on every balance change and
+/// through `ClaimTop`, so `Top` never ranges over all depositors
+pub const TIME_WEIGHTED_LEADER: Item<Addr> = Item::new("time_weighted_leader");
+
+/// Native denoms accepted besides `DENOM`, they are accounted separately and do not
+/// take part in the threshold, time weights or balance history
+pub const ACCEPTED_DENOMS: Map<&str, Empty> = Map::new("accepted_denoms");
+
+/// Balances of users in accepted denoms other than `DENOM`
+pub const DENOM_BALANCES: Map<(&Addr, &str), Uint128> = Map::new("denom_balances");
+
+#[cw_serde]
+pub struct DenomTop {
+    pub address: Addr,
+    pub balance: Uint128,
+}
+
+/// Leading depositor of each accepted denom, replaced only by a higher balance.
+/// When the top withdraws below another balance, the highest one takes over
+pub const DENOM_TOPS: Map<&str, DenomTop> = Map::new("denom_tops");
+
+/// Non-zero denom balances by amount, to find the next top when the top withdraws
+pub const DENOM_RANKING: Map<(&str, u128, &Addr), Empty> = Map::new("denom_ranking");
+
+/// Moves a user's `DENOM_RANKING` entry from their old balance to the new one
+pub fn update_denom_ranking(
+    store: &mut dyn Storage,
+    denom: &str,
+    user: &Addr,
+    old_balance: Uint128,
+    new_balance: Uint128,
+) -> StdResult<()> {
+    if !old_balance.is_zero() {
+        DENOM_RANKING.remove(store, (denom, old_balance.u128(), user));
+    }
+    if !new_balance.is_zero() {
+        DENOM_RANKING.save(store, (denom, new_balance.u128(), user), &Empty {})?;
+    }
+    Ok(())
+}
+
+/// Contracts the owner is allowed to execute through `OwnerAction`
+pub const ALLOWED_TARGETS: Map<&Addr, Empty> = Map::new("allowed_targets");
+
+/// Deposit nonces already processed per sender
+pub const DEPOSIT_NONCES: Map<(&Addr, u64), Empty> = Map::new("deposit_nonces");
+
+#[cw_serde]
+#[derive(Default)]
+pub struct ReindexState {
+    /// Last depositor scanned, the next page has to start after it
+    pub last: Option<Addr>,
+    /// Leading depositor among the scanned ones
+    pub top: Option<Addr>,
+    pub top_balance: Uint128,
+    pub top_seq: u64,
+    /// Sum of the scanned balances, replaces `TOTAL_DEPOSITS` once the scan finishes
+    pub total: Uint128,
+}
+
+/// Progress of a `Reindex` spanning several calls
+pub const REINDEX: Item<ReindexState> = Item::new("reindex");
+
+#[cw_serde]
+pub struct AuditEntry {
+    pub id: u64,
+    pub height: u64,
+    pub action: String,
+    pub old: Option<Addr>,
+    pub new: Option<Addr>,
+}
+
+/// History of ownership and top depositor changes
+pub const AUDIT_LOG: Map<u64, AuditEntry> = Map::new("audit_log");
+pub const AUDIT_COUNT: Item<u64> = Item::new("audit_count");
+
+/// Appends a privileged state transition to the audit log
+pub fn record_audit(
+    store: &mut dyn Storage,
+    height: u64,
+    action: &str,
+    old: Option<Addr>,
+    new: Option<Addr>,
+) -> StdResult<()> {
+    let id = next_count(AUDIT_COUNT.may_load(store)?.unwrap_or_default())?;
+
+    let entry = AuditEntry {
+        id,
+        height,
+        action: action.to_string(),
+        old,
+        new,
+    };
+
+    AUDIT_LOG.save(store, id, &entry)?;
+    AUDIT_COUNT.save(store, &id)
+}
+
+#[cw_serde]
+pub struct BalanceEvent {
+    pub id: u64,
+    pub height: u64,
+    pub action: String,
+    pub amount: Uint128,
+    /// User balance after the event
+    pub balance: Uint128,
+}
+
+/// Deposits and withdrawals of each user, keyed by a per-user sequence
+pub const BALANCE_HISTORY: Map<(&Addr, u64), BalanceEvent> = Map::new("balance_history");
+pub const BALANCE_HISTORY_COUNT: Map<&Addr, u64> = Map::new("balance_history_count");
+
+/// Appends a balance change to the user's history
+pub fn record_balance_event(
+    store: &mut dyn Storage,
+    user: &Addr,
+    height: u64,
+    action: &str,
+    amount: Uint128,
+    balance: Uint128,
+) -> StdResult<()> {
+    let id = next_count(
+        BALANCE_HISTORY_COUNT
+            .may_load(store, user)?
+            .unwrap_or_default(),
+    )?;
+
+    let event = BalanceEvent {
+        id,
+        height,
+        action: action.to_string(),
+        amount,
+        balance,
+    };
+
+    BALANCE_HISTORY.save(store, (user, id), &event)?;
+    BALANCE_HISTORY_COUNT.save(store, user, &id)
+}
+
+/// Drops a renounce proposed by a previous owner, called whenever the owner changes
+pub fn clear_pending_renounce(store: &mut dyn Storage) -> StdResult<()> {
+    let mut ownership = OWNERSHIP.may_load(store)?.unwrap_or_default();
+    if ownership.pending_renounce.take().is_some() {
+        OWNERSHIP.save(store, &ownership)?;
+    }
+    Ok(())
+}
+
+/// Returns the owner, `None` once ownership has been renounced
+pub fn current_owner(store: &dyn Storage) -> StdResult<Option<Addr>> {
+    let ownership = OWNERSHIP.may_load(store)?.unwrap_or_default();
+    if ownership.renounced {
+        return Ok(None);
+    }
+    OWNER.may_load(store)
+}
+
+/// Returns the time-weighted score of a balance held since the last update
+pub fn current_score(
+    weight: &TimeWeight,
+    balance: Uint128,
+    now: Timestamp,
+) -> Result<Uint128, OverflowError> {
+    let held = now.seconds().saturating_sub(weight.last_update.seconds());
+    weight
+        .score
+        .checked_add(balance.checked_mul(Uint128::from(held))?)
+}
+
+/// Makes the user the time-weighted leader when their score at `now` ranks above the
+/// current leader's. Returns whether the user leads afterwards
+pub fn challenge_time_weighted_top(
+    store: &mut dyn Storage,
+    user: &Addr,
+    now: Timestamp,
+) -> StdResult<bool> {
+    // only depositors have a score
+    if !TIME_WEIGHTS.has(store, user) {
+        return Ok(false);
+    }
+
+    let leader = match TIME_WEIGHTED_LEADER.may_load(store)? {
+        Some(leader) if leader == *user => return Ok(true),
+        Some(leader) => leader,
+        None => {
+            TIME_WEIGHTED_LEADER.save(store, user)?;
+            return Ok(true);
+        }
+    };
+
+    let (score, seq) = time_weighted_rank(store, user, now)?;
+    let (leader_score, leader_seq) = time_weighted_rank(store, &leader, now)?;
+    if !ranks_above(score, seq, leader_score, leader_seq) {
+        return Ok(false);
+    }
+
+    TIME_WEIGHTED_LEADER.save(store, user)?;
+    Ok(true)
+}
+
+/// Returns the user's time-weighted score at `now` along with the sequence breaking ties
+fn time_weighted_rank(
+    store: &dyn Storage,
+    user: &Addr,
+    now: Timestamp,
+) -> StdResult<(Uint128, u64)> {
+    let weight = TIME_WEIGHTS.may_load(store, user)?.unwrap_or_default();
+    let balance = BALANCES.may_load(store, user)?.unwrap_or_default();
+    let seq = BALANCE_SEQ.may_load(store, user)?.unwrap_or_default();
+    Ok((current_score(&weight, balance, now)?, seq))
+}
+
+/// Accrues the user's score for the balance held until now, call before the balance changes
+pub fn accrue_time_weight(
+    store: &mut dyn Storage,
+    user: &Addr,
+    balance: Uint128,
+    now: Timestamp,
+) -> StdResult<()> {
+    let weight = TIME_WEIGHTS.may_load(store, user)?.unwrap_or_default();
+
+    let weight = TimeWeight {
+        score: current_score(&weight, balance, now)?,
+        last_update: now,
+    };
+    TIME_WEIGHTS.save(store, user, &weight)
+}
@@ -1,18 +1,30 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_binary, wasm_instantiate, Addr, Binary, CosmosMsg, Deps, DepsMut, Empty, Env, MessageInfo,
-    Reply, Response, StdResult, SubMsg, WasmMsg,
+    from_binary, to_binary, wasm_instantiate, Addr, Binary, CosmosMsg, Deps, DepsMut, Empty, Env,
+    MessageInfo, OverflowError, OverflowOperation, Reply, Response, StdResult, SubMsg, Timestamp,
+    Uint128, WasmMsg,
 };
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw721::OwnerOfResponse;
+#[cfg(not(feature = "fixed"))]
 use cw721::TokensResponse;
 use cw721_base::{
-    ExecuteMsg as Cw721ExecuteMsg, InstantiateMsg as Cw721InstantiateMsg, QueryMsg as Cw721QueryMsg,
+    ExecuteMsg as Cw721ExecuteMsg, InstantiateMsg as Cw721InstantiateMsg, MinterResponse,
+    QueryMsg as Cw721QueryMsg,
 };
 use cw_utils::parse_reply_instantiate_data;
+use std::collections::HashSet;
 
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
-use crate::state::{Config, Whitelist, CONFIG, WHITELIST};
+use crate::msg::{
+    CanMintResponse, CollectionInfoResponse, ExecuteMsg, InstantiateMsg, QueryMsg, ReceiveMsg,
+    SupplyInfoResponse,
+};
+use crate::state::{
+    next_token_id, Config, Cw20Price, Whitelist, CONFIG, LAST_MINT, LINEAGE, NEXT_TOKEN_ID,
+    WHITELIST,
+};
 
 pub const DENOM: &str = "uawesome";
 
@@ -20,13 +32,18 @@ pub const DENOM: &str = "uawesome";
 pub fn instantiate(
     deps: DepsMut,
     env: Env,
-    _info: MessageInfo,
+    info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
+    // validate collection metadata
+    if msg.collection_name.trim().is_empty() || msg.collection_symbol.trim().is_empty() {
+        return Err(ContractError::InvalidCollectionInfo {});
+    }
+
     // nft contract init msg
     let cw721_init_msg = Cw721InstantiateMsg {
-        name: "Awesome Wasm".to_owned(),
-        symbol: "AWESOME".to_owned(),
+        name: msg.collection_name.clone(),
+        symbol: msg.collection_symbol.clone(),
         minter: env.contract.address.to_string(),
     };
 
@@ -41,24 +58,55 @@ pub fn instantiate(
         1,
     );
 
+    // validate cw20 payment token
+    let cw20_price = msg
+        .cw20_price
+        .map(|price| -> StdResult<_> {
+            Ok(Cw20Price {
+                token: deps.api.addr_validate(&price.token)?,
+                amount: price.amount,
+            })
+        })
+        .transpose()?;
+
+    if let Some(reserved) = &msg.reserved_ids {
+        if reserved.start > reserved.end {
+            return Err(ContractError::InvalidReservedRange {});
+        }
+    }
+
     // store config
     let config = Config {
+        admin: info.sender,
         nft_contract: Addr::unchecked(""),
         mint_per_user: msg.mint_per_user,
         total_tokens: 0,
+        total_minted: 0,
+        cw20_price,
+        mint_cooldown: msg.mint_cooldown,
+        reserved_ids: msg.reserved_ids,
+        max_whitelist_size: msg.max_whitelist_size,
+        max_supply: msg.max_supply,
     };
 
     CONFIG.save(deps.storage, &config)?;
+    NEXT_TOKEN_ID.save(deps.storage, &0)?;
 
-    // validate and store whitelisted users
-    let _ = msg
-        .whitelisted_users
-        .iter()
-        .map(|user| deps.api.addr_validate(user).unwrap());
+    // validate and store whitelisted users, skipping duplicates
+    let mut whitelist = Whitelist { users: vec![] };
+    let mut seen: HashSet<String> = HashSet::new();
+    for user in msg.whitelisted_users {
+        let user = deps.api.addr_validate(&user)?.into_string();
+        if seen.insert(user.clone()) {
+            whitelist.users.push(user);
+        }
+    }
 
-    let whitelist = Whitelist {
-        users: msg.whitelisted_users,
-    };
+    if let Some(max) = msg.max_whitelist_size {
+        if whitelist.users.len() as u64 > max {
+            return Err(ContractError::WhitelistFull { max });
+        }
+    }
 
     WHITELIST.save(deps.storage, &whitelist)?;
 
@@ -78,41 +126,153 @@ pub fn execute(
 ) -> Result<Response, ContractError> {
     match msg {
         ExecuteMsg::Mint {} => mint(deps, env, info),
+        ExecuteMsg::BatchMint { amount } => batch_mint(deps, env, info, amount),
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
+        ExecuteMsg::BurnAndRemint {
+            token_id,
+            token_uri,
+        } => burn_and_remint(deps, info, token_id, token_uri),
+        ExecuteMsg::AdminMint {
+            token_id,
+            recipient,
+        } => admin_mint(deps, info, token_id, recipient),
+        ExecuteMsg::AddToWhitelist { users } => add_to_whitelist(deps, info, users),
     }
 }
 
 /// Mint NFT to recipient
-pub fn mint(deps: DepsMut, _env: Env, info: MessageInfo) -> Result<Response, ContractError> {
-    let mut config = CONFIG.load(deps.storage)?;
+pub fn mint(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
 
-    // check user is in whitelist
-    let users = WHITELIST.load(deps.storage)?.users;
-    let is_whitelisted = users.iter().any(|user| user == &info.sender.to_string());
-    if !is_whitelisted {
-        return Err(ContractError::NotWhitelisted {});
+    // paid mints must go through the cw20 hook
+    if config.cw20_price.is_some() {
+        return Err(ContractError::PaymentRequired {});
     }
 
-    let tokens_response: TokensResponse = deps.querier.query_wasm_smart(
-        config.nft_contract.to_string(),
-        &Cw721QueryMsg::Tokens::<Empty> {
-            owner: info.sender.to_string(),
-            start_after: None,
-            limit: None,
-        },
-    )?;
+    let (msgs, token_ids) = mint_tokens(deps, &env, &info.sender, 1)?;
 
-    // ensure mint per user limit is not exceeded
-    if tokens_response.tokens.len() >= config.mint_per_user as usize {
-        return Err(ContractError::MaxLimitExceeded {});
+    Ok(Response::new()
+        .add_attribute("action", "mint")
+        .add_attribute("recipient", info.sender.to_string())
+        .add_attribute("token_id", token_ids.join(","))
+        .add_messages(msgs))
+}
+
+/// Mint several NFTs to recipient in a single call
+pub fn batch_mint(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // paid mints must go through the cw20 hook
+    if config.cw20_price.is_some() {
+        return Err(ContractError::PaymentRequired {});
     }
 
-    let token_id = config.total_tokens;
+    let (msgs, token_ids) = mint_tokens(deps, &env, &info.sender, amount)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "batch_mint")
+        .add_attribute("recipient", info.sender.to_string())
+        .add_attribute("token_ids", token_ids.join(","))
+        .add_messages(msgs))
+}
+
+/// Mint NFTs paid for with the configured cw20 token, the payment is forwarded to the admin
+pub fn receive_cw20(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    wrapper: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // only the configured cw20 contract can pay for mints
+    let price = match config.cw20_price {
+        Some(price) if price.token == info.sender => price,
+        _ => return Err(ContractError::InvalidPaymentToken {}),
+    };
 
+    let recipient = deps.api.addr_validate(&wrapper.sender)?;
+
+    let hook: ReceiveMsg = from_binary(&wrapper.msg)?;
+    let amount = match hook {
+        ReceiveMsg::Mint {} => 1,
+        ReceiveMsg::BatchMint { amount } => amount,
+    };
+
+    // ensure payment covers every requested mint
+    let cost = price.amount.checked_mul(Uint128::from(amount))?;
+    if wrapper.amount < cost {
+        return Err(ContractError::InsufficientPayment { required: cost });
+    }
+
+    let (mut msgs, token_ids) = mint_tokens(deps, &env, &recipient, amount)?;
+
+    // forward the proceeds to the admin, nothing is kept in the contract
+    if !cost.is_zero() {
+        msgs.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: price.token.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: config.admin.to_string(),
+                amount: cost,
+            })?,
+            funds: vec![],
+        }));
+    }
+
+    // refund any excess payment
+    let refund = wrapper.amount.checked_sub(cost)?;
+    if !refund.is_zero() {
+        msgs.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: price.token.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: recipient.to_string(),
+                amount: refund,
+            })?,
+            funds: vec![],
+        }));
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "cw20_mint")
+        .add_attribute("recipient", recipient.to_string())
+        .add_attribute("token_ids", token_ids.join(","))
+        .add_attribute("paid", cost)
+        .add_attribute("refund", refund)
+        .add_messages(msgs))
+}
+
+/// Mint a token id from the reserved range
+pub fn admin_mint(
+    deps: DepsMut,
+    info: MessageInfo,
+    token_id: u64,
+    recipient: String,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if config.admin != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if !matches!(&config.reserved_ids, Some(reserved) if reserved.contains(token_id)) {
+        return Err(ContractError::NotReserved { token_id });
+    }
+
+    let recipient = deps.api.addr_validate(&recipient)?;
+
+    check_max_supply(&config, 1)?;
+
+    // cw721 rejects ids that were already minted
     let msg = CosmosMsg::Wasm(WasmMsg::Execute {
         contract_addr: config.nft_contract.to_string(),
         msg: to_binary(&Cw721ExecuteMsg::Mint::<Empty, Empty> {
             token_id: token_id.to_string(),
-            owner: info.sender.to_string(),
+            owner: recipient.to_string(),
             token_uri: None,
             extension: Empty {},
         })?,
@@ -120,16 +280,239 @@ pub fn mint(deps: DepsMut, _env: Env, info: MessageInfo) -> Result<Response, Con
     });
 
     // increment total tokens
-    config.total_tokens += 1;
+    config.total_tokens = checked_add(config.total_tokens, 1)?;
+    config.total_minted = checked_add(config.total_minted, 1)?;
     CONFIG.save(deps.storage, &config)?;
 
     Ok(Response::new()
-        .add_attribute("action", "mint")
-        .add_attribute("recipient", info.sender.to_string())
+        .add_attribute("action", "admin_mint")
+        .add_attribute("recipient", recipient.to_string())
         .add_attribute("token_id", token_id.to_string())
         .add_message(msg))
 }
 
+/// Whitelist users in a single write, admin only
+pub fn add_to_whitelist(
+    deps: DepsMut,
+    info: MessageInfo,
+    users: Vec<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    if config.admin != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut whitelist = WHITELIST.load(deps.storage)?;
+    let previous = whitelist.users.len();
+
+    // skip users already whitelisted or repeated in the batch
+    let mut seen: HashSet<String> = whitelist.users.iter().cloned().collect();
+    for user in users {
+        let user = deps.api.addr_validate(&user)?.into_string();
+        if seen.insert(user.clone()) {
+            whitelist.users.push(user);
+        }
+    }
+
+    if let Some(max) = config.max_whitelist_size {
+        if whitelist.users.len() as u64 > max {
+            return Err(ContractError::WhitelistFull { max });
+        }
+    }
+
+    WHITELIST.save(deps.storage, &whitelist)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "add_to_whitelist")
+        .add_attribute("added", (whitelist.users.len() - previous).to_string())
+        .add_attribute("total_whitelisted_users", whitelist.users.len().to_string()))
+}
+
+/// Replace an owned NFT with a new one without using the per-user mint allowance
+pub fn burn_and_remint(
+    deps: DepsMut,
+    info: MessageInfo,
+    token_id: String,
+    token_uri: Option<String>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    let owner: OwnerOfResponse = deps.querier.query_wasm_smart(
+        config.nft_contract.to_string(),
+        &Cw721QueryMsg::OwnerOf::<Empty> {
+            token_id: token_id.clone(),
+            include_expired: None,
+        },
+    )?;
+    if owner.owner != info.sender {
+        return Err(ContractError::NotTokenOwner {});
+    }
+
+    let new_token_id = next_token_id(deps.storage, &config)?;
+
+    let burn_msg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: config.nft_contract.to_string(),
+        msg: to_binary(&Cw721ExecuteMsg::Burn::<Empty, Empty> {
+            token_id: token_id.clone(),
+        })?,
+        funds: vec![],
+    });
+
+    let mint_msg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: config.nft_contract.to_string(),
+        msg: to_binary(&Cw721ExecuteMsg::Mint::<Empty, Empty> {
+            token_id: new_token_id.clone(),
+            owner: info.sender.to_string(),
+            token_uri,
+            extension: Empty {},
+        })?,
+        funds: vec![],
+    });
+
+    // increment total tokens, the replaced token doesn't count against the max supply
+    config.total_tokens = checked_add(config.total_tokens, 1)?;
+    CONFIG.save(deps.storage, &config)?;
+
+    LINEAGE.save(deps.storage, &new_token_id, &token_id)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "burn_and_remint")
+        .add_attribute("recipient", info.sender.to_string())
+        .add_attribute("burned_token_id", token_id)
+        .add_attribute("token_id", new_token_id)
+        .add_message(burn_msg)
+        .add_message(mint_msg))
+}
+
+/// Validates the recipient can mint `amount` NFTs and builds the cw721 mint messages
+fn mint_tokens(
+    deps: DepsMut,
+    env: &Env,
+    recipient: &Addr,
+    amount: u64,
+) -> Result<(Vec<CosmosMsg>, Vec<String>), ContractError> {
+    if amount == 0 {
+        return Err(ContractError::ZeroAmount {});
+    }
+
+    let mut config = CONFIG.load(deps.storage)?;
+    // only the `fixed` build keeps track of minted counts
+    #[cfg_attr(not(feature = "fixed"), allow(unused_variables))]
+    let minted = check_mint_allowed(deps.as_ref(), env, &config, recipient, amount)?;
+
+    // every check is done up front and the mints are dispatched as plain messages,
+    // so a failing mint reverts the whole batch including the counters saved below
+    let mut msgs = vec![];
+    let mut token_ids = vec![];
+
+    for _ in 0..amount {
+        let token_id = next_token_id(deps.storage, &config)?;
+
+        msgs.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: config.nft_contract.to_string(),
+            msg: to_binary(&Cw721ExecuteMsg::Mint::<Empty, Empty> {
+                token_id: token_id.clone(),
+                owner: recipient.to_string(),
+                token_uri: None,
+                extension: Empty {},
+            })?,
+            funds: vec![],
+        }));
+        token_ids.push(token_id);
+
+        // increment total tokens
+        config.total_tokens = checked_add(config.total_tokens, 1)?;
+        config.total_minted = checked_add(config.total_minted, 1)?;
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+    LAST_MINT.save(deps.storage, recipient, &env.block.time)?;
+    #[cfg(feature = "fixed")]
+    crate::state::MINTED.save(deps.storage, recipient, &minted)?;
+
+    Ok((msgs, token_ids))
+}
+
+/// Runs the whitelist, cooldown, limit and supply checks of a mint without changing state,
+/// returns the number of tokens counted against the recipient's limit afterwards
+fn check_mint_allowed(
+    deps: Deps,
+    env: &Env,
+    config: &Config,
+    recipient: &Addr,
+    amount: u64,
+) -> Result<u64, ContractError> {
+    // check user is in whitelist
+    let users = WHITELIST.load(deps.storage)?.users;
+    let is_whitelisted = users.iter().any(|user| user == &recipient.to_string());
+    if !is_whitelisted {
+        return Err(ContractError::NotWhitelisted {});
+    }
+
+    // ensure the user's mint cooldown has elapsed
+    if let Some(cooldown) = config.mint_cooldown {
+        if let Some(last_mint) = LAST_MINT.may_load(deps.storage, recipient)? {
+            let next_mint = last_mint.plus_seconds(cooldown);
+            if env.block.time < next_mint {
+                return Err(ContractError::MintCooldown { next_mint });
+            }
+        }
+    }
+
+    // ensure mint per user limit is not exceeded
+    let minted = minted_count(deps, config, recipient)?
+        .checked_add(amount)
+        .ok_or(ContractError::MaxLimitExceeded {})?;
+    if minted > config.mint_per_user {
+        return Err(ContractError::MaxLimitExceeded {});
+    }
+
+    check_max_supply(config, amount)?;
+
+    Ok(minted)
+}
+
+/// Ensures minting `amount` more tokens stays within the max supply
+fn check_max_supply(config: &Config, amount: u64) -> Result<(), ContractError> {
+    if let Some(max) = config.max_supply {
+        if checked_add(config.total_minted, amount as u128)? > max as u128 {
+            return Err(ContractError::MaxSupplyReached { max });
+        }
+    }
+    Ok(())
+}
+
+/// Adds raw `u128` amounts, erroring instead of wrapping around
+fn checked_add(a: u128, b: u128) -> Result<u128, OverflowError> {
+    a.checked_add(b)
+        .ok_or_else(|| OverflowError::new(OverflowOperation::Add, a, b))
+}
+
+/// Returns how many tokens count against the user's mint limit.
+/// Only tokens currently held are counted, so transferring one away frees up a mint.
+#[cfg(not(feature = "fixed"))]
+fn minted_count(deps: Deps, config: &Config, user: &Addr) -> StdResult<u64> {
+    let tokens_response: TokensResponse = deps.querier.query_wasm_smart(
+        config.nft_contract.to_string(),
+        &Cw721QueryMsg::Tokens::<Empty> {
+            owner: user.to_string(),
+            start_after: None,
+            limit: None,
+        },
+    )?;
+    Ok(tokens_response.tokens.len() as u64)
+}
+
+/// Returns how many tokens count against the user's mint limit.
+/// Every token minted to the user is counted, regardless of where it is now.
+#[cfg(feature = "fixed")]
+fn minted_count(deps: Deps, _config: &Config, user: &Addr) -> StdResult<u64> {
+    Ok(crate::state::MINTED
+        .may_load(deps.storage, user)?
+        .unwrap_or_default())
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn reply(deps: DepsMut, _env: Env, reply: Reply) -> Result<Response, ContractError> {
     match reply.id {
@@ -146,10 +529,16 @@ pub fn reply(deps: DepsMut, _env: Env, reply: Reply) -> Result<Response, Contrac
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_binary(&query_config(deps)?),
         QueryMsg::Whitelist {} => to_binary(&query_whitelist(deps)?),
+        QueryMsg::NextMint { user } => to_binary(&query_next_mint(deps, env, user)?),
+        QueryMsg::TokenLineage { token_id } => to_binary(&query_token_lineage(deps, token_id)?),
+        QueryMsg::HasReachedLimit { user } => to_binary(&query_has_reached_limit(deps, user)?),
+        QueryMsg::SupplyInfo {} => to_binary(&query_supply_info(deps)?),
+        QueryMsg::CollectionInfo {} => to_binary(&query_collection_info(deps)?),
+        QueryMsg::CanMint { user } => to_binary(&query_can_mint(deps, env, user)?),
     }
 }
 
@@ -164,3 +553,93 @@ fn query_whitelist(deps: Deps) -> StdResult<Whitelist> {
     let whitelist = WHITELIST.load(deps.storage)?;
     Ok(whitelist)
 }
+
+/// Returns the earliest time a user is allowed to mint again
+fn query_next_mint(deps: Deps, env: Env, user: String) -> StdResult<Timestamp> {
+    let user = deps.api.addr_validate(&user)?;
+    let config = CONFIG.load(deps.storage)?;
+    let last_mint = LAST_MINT.may_load(deps.storage, &user)?;
+
+    let next_mint = match (config.mint_cooldown, last_mint) {
+        (Some(cooldown), Some(last_mint)) => last_mint.plus_seconds(cooldown).max(env.block.time),
+        _ => env.block.time,
+    };
+    Ok(next_mint)
+}
+
+/// Returns the token id a reminted token replaced
+fn query_token_lineage(deps: Deps, token_id: String) -> StdResult<Option<String>> {
+    LINEAGE.may_load(deps.storage, &token_id)
+}
+
+/// Returns whether a user can no longer mint, which is always the case for non-whitelisted users
+fn query_has_reached_limit(deps: Deps, user: String) -> StdResult<bool> {
+    let user = deps.api.addr_validate(&user)?;
+    let config = CONFIG.load(deps.storage)?;
+
+    let users = WHITELIST.load(deps.storage)?.users;
+    if !users.iter().any(|whitelisted| whitelisted == user.as_str()) {
+        return Ok(true);
+    }
+
+    Ok(minted_count(deps, &config, &user)? >= config.mint_per_user)
+}
+
+/// Returns minted tokens against the max supply
+fn query_supply_info(deps: Deps) -> StdResult<SupplyInfoResponse> {
+    let config = CONFIG.load(deps.storage)?;
+
+    Ok(SupplyInfoResponse {
+        total_minted: config.total_minted,
+        total_tokens: config.total_tokens,
+        max_supply: config.max_supply,
+        remaining: config
+            .max_supply
+            .map(|max| (max as u128).saturating_sub(config.total_minted)),
+    })
+}
+
+/// Returns who controls the cw721 collection, this contract should be its only minter
+fn query_collection_info(deps: Deps) -> StdResult<CollectionInfoResponse> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let minter: MinterResponse = deps.querier.query_wasm_smart(
+        config.nft_contract.to_string(),
+        &Cw721QueryMsg::Minter::<Empty> {},
+    )?;
+    let contract_info = deps
+        .querier
+        .query_wasm_contract_info(config.nft_contract.to_string())?;
+
+    Ok(CollectionInfoResponse {
+        nft_contract: config.nft_contract,
+        minter: minter.minter,
+        admin: contract_info.admin,
+    })
+}
+
+/// Returns whether `Mint` would succeed for the user and the error it would fail with otherwise
+fn query_can_mint(deps: Deps, env: Env, user: String) -> StdResult<CanMintResponse> {
+    let user = deps.api.addr_validate(&user)?;
+    let config = CONFIG.load(deps.storage)?;
+
+    // same order as `mint`, paid mints must go through the cw20 hook
+    let check = if config.cw20_price.is_some() {
+        Err(ContractError::PaymentRequired {})
+    } else {
+        check_mint_allowed(deps, &env, &config, &user, 1)
+    };
+
+    match check {
+        Ok(_) => Ok(CanMintResponse {
+            allowed: true,
+            reason: None,
+        }),
+        // failing to read state is not a reason the mint would be rejected
+        Err(ContractError::Std(err)) => Err(err),
+        Err(err) => Ok(CanMintResponse {
+            allowed: false,
+            reason: Some(err.to_string()),
+        }),
+    }
+}
@@ -1,4 +1,4 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{OverflowError, StdError, Timestamp, Uint128};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -6,12 +6,48 @@ pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
 
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
     #[error("Unauthorized")]
     Unauthorized {},
 
+    #[error("Zero amount is not allowed")]
+    ZeroAmount {},
+
+    #[error("Collection name and symbol cannot be empty")]
+    InvalidCollectionInfo {},
+
+    #[error("Caller does not own the token")]
+    NotTokenOwner {},
+
+    #[error("Reserved range start must not exceed its end")]
+    InvalidReservedRange {},
+
+    #[error("Token id {token_id} is not reserved")]
+    NotReserved { token_id: u64 },
+
     #[error("User is not whitelisted")]
     NotWhitelisted {},
 
+    #[error("Whitelist cannot exceed {max} users")]
+    WhitelistFull { max: u64 },
+
+    #[error("Max supply of {max} tokens reached")]
+    MaxSupplyReached { max: u64 },
+
     #[error("Max mint limit exceeded")]
     MaxLimitExceeded {},
+
+    #[error("Mint cooldown active until {next_mint}")]
+    MintCooldown { next_mint: Timestamp },
+
+    #[error("Minting requires payment with the configured cw20 token")]
+    PaymentRequired {},
+
+    #[error("Payment token is not accepted")]
+    InvalidPaymentToken {},
+
+    #[error("Insufficient payment, {required} required")]
+    InsufficientPayment { required: Uint128 },
 }
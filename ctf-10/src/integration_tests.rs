@@ -1,12 +1,20 @@
 #[cfg(test)]
 pub mod tests {
     use crate::{
-        msg::{ExecuteMsg, InstantiateMsg, QueryMsg},
-        state::{Config, Whitelist},
+        msg::{
+            CanMintResponse, CollectionInfoResponse, Cw20PriceMsg, ExecuteMsg, InstantiateMsg,
+            QueryMsg, ReceiveMsg, SupplyInfoResponse,
+        },
+        state::{Config, ReservedRange, Whitelist},
+        ContractError,
     };
-    use cosmwasm_std::{Addr, Empty};
+    use cosmwasm_std::{
+        to_binary, Addr, DepsMut, Empty, Env, MessageInfo, Response, Timestamp, Uint128,
+    };
+    use cw20::{BalanceResponse, Cw20Coin, Cw20ExecuteMsg, Cw20QueryMsg};
 
     use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+    use insta::assert_json_snapshot;
 
     pub fn challenge_code() -> Box<dyn Contract<Empty>> {
         let contract = ContractWrapper::new(
@@ -27,22 +35,67 @@ pub mod tests {
         Box::new(contract)
     }
 
+    /// cw721 that rejects minting token "1", used to make a batch fail halfway
+    fn failing_cw721_execute(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: cw721_base::ExecuteMsg<cw721_base::Extension, Empty>,
+    ) -> Result<Response, cw721_base::ContractError> {
+        if let cw721_base::ExecuteMsg::Mint { token_id, .. } = &msg {
+            if token_id == "1" {
+                return Err(cw721_base::ContractError::Claimed {});
+            }
+        }
+        cw721_base::entry::execute(deps, env, info, msg)
+    }
+
+    fn failing_cw721_code() -> Box<dyn Contract<Empty>> {
+        let contract = ContractWrapper::new(
+            failing_cw721_execute,
+            cw721_base::entry::instantiate,
+            cw721_base::entry::query,
+        );
+        Box::new(contract)
+    }
+
+    fn cw20_code() -> Box<dyn Contract<Empty>> {
+        let contract = ContractWrapper::new(
+            cw20_base::contract::execute,
+            cw20_base::contract::instantiate,
+            cw20_base::contract::query,
+        );
+        Box::new(contract)
+    }
+
     pub const ADMIN: &str = "admin";
     pub const USER1: &str = "user1";
     pub const USER2: &str = "user2";
     pub const USER3: &str = "user3";
 
+    /// Instantiate message shared by the tests, they override the fields they exercise
+    pub fn default_instantiate_msg(cw721_code_id: u64) -> InstantiateMsg {
+        InstantiateMsg {
+            cw721_code_id,
+            collection_name: "Awesome Wasm".to_owned(),
+            collection_symbol: "AWESOME".to_owned(),
+            mint_per_user: 3,
+            whitelisted_users: vec![USER1.to_owned(), USER2.to_owned(), USER3.to_owned()],
+            cw20_price: None,
+            mint_cooldown: None,
+            reserved_ids: None,
+            max_whitelist_size: None,
+            max_supply: None,
+        }
+    }
+
     pub fn proper_instantiate() -> (App, Addr) {
         let mut app = App::default();
         let challenge_id = app.store_code(challenge_code());
         let cw_721_id = app.store_code(cw721_code());
 
         // Init challenge
-        let challenge_inst = InstantiateMsg {
-            cw721_code_id: cw_721_id,
-            mint_per_user: 3,
-            whitelisted_users: vec![USER1.to_owned(), USER2.to_owned(), USER3.to_owned()],
-        };
+        let challenge_inst = default_instantiate_msg(cw_721_id);
 
         let contract_addr = app
             .instantiate_contract(
@@ -67,11 +120,7 @@ pub mod tests {
         let cw_721_id = app.store_code(cw721_code());
 
         // Init challenge
-        let challenge_inst = InstantiateMsg {
-            cw721_code_id: cw_721_id,
-            mint_per_user: 3,
-            whitelisted_users: vec![USER1.to_owned(), USER2.to_owned(), USER3.to_owned()],
-        };
+        let challenge_inst = default_instantiate_msg(cw_721_id);
 
         let contract_addr = app
             .instantiate_contract(
@@ -99,6 +148,59 @@ pub mod tests {
         (app, contract_addr, dummy_addr)
     }
 
+    /// Same logic as `proper_instantiate` except mints are paid for with a cw20 token,
+    /// returns the cw20 token address as well
+    pub fn paid_instantiate(price: Uint128) -> (App, Addr, Addr) {
+        let mut app = App::default();
+        let challenge_id = app.store_code(challenge_code());
+        let cw_721_id = app.store_code(cw721_code());
+        let cw_20_id = app.store_code(cw20_code());
+
+        // Init payment token, USER1 starts with 1_000 tokens
+        let token_addr = app
+            .instantiate_contract(
+                cw_20_id,
+                Addr::unchecked(ADMIN),
+                &cw20_base::msg::InstantiateMsg {
+                    name: "Awesome Token".to_owned(),
+                    symbol: "AWE".to_owned(),
+                    decimals: 6,
+                    initial_balances: vec![Cw20Coin {
+                        address: USER1.to_owned(),
+                        amount: Uint128::new(1_000),
+                    }],
+                    mint: None,
+                    marketing: None,
+                },
+                &[],
+                "token",
+                None,
+            )
+            .unwrap();
+
+        // Init challenge
+        let challenge_inst = InstantiateMsg {
+            cw20_price: Some(Cw20PriceMsg {
+                token: token_addr.to_string(),
+                amount: price,
+            }),
+            ..default_instantiate_msg(cw_721_id)
+        };
+
+        let contract_addr = app
+            .instantiate_contract(
+                challenge_id,
+                Addr::unchecked(ADMIN),
+                &challenge_inst,
+                &[],
+                "test",
+                None,
+            )
+            .unwrap();
+
+        (app, contract_addr, token_addr)
+    }
+
     #[test]
     fn basic_flow() {
         let (mut app, contract_addr) = proper_instantiate();
@@ -183,6 +285,7 @@ pub mod tests {
     }
     
     #[test]
+    #[cfg(not(feature = "fixed"))]
     fn clever_test_name() {
         let (mut app, contract_addr, dummy_addr) = proper_instantiate_w_dummy();
 
@@ -289,4 +392,1038 @@ pub mod tests {
         assert_eq!(config.total_tokens, 4);
     }
 
+    #[test]
+    fn cw20_paid_mint() {
+        let (mut app, contract_addr, token_addr) = paid_instantiate(Uint128::new(100));
+
+        // free mint is rejected once a price is configured
+        app.execute_contract(
+            Addr::unchecked(USER1),
+            contract_addr.clone(),
+            &ExecuteMsg::Mint {},
+            &[],
+        )
+        .unwrap_err();
+
+        // underpaying fails
+        app.execute_contract(
+            Addr::unchecked(USER1),
+            token_addr.clone(),
+            &Cw20ExecuteMsg::Send {
+                contract: contract_addr.to_string(),
+                amount: Uint128::new(150),
+                msg: to_binary(&ReceiveMsg::BatchMint { amount: 2 }).unwrap(),
+            },
+            &[],
+        )
+        .unwrap_err();
+
+        // paying 250 for two mints refunds the excess 50
+        app.execute_contract(
+            Addr::unchecked(USER1),
+            token_addr.clone(),
+            &Cw20ExecuteMsg::Send {
+                contract: contract_addr.to_string(),
+                amount: Uint128::new(250),
+                msg: to_binary(&ReceiveMsg::BatchMint { amount: 2 }).unwrap(),
+            },
+            &[],
+        )
+        .unwrap();
+
+        let balance: BalanceResponse = app
+            .wrap()
+            .query_wasm_smart(
+                token_addr.clone(),
+                &Cw20QueryMsg::Balance {
+                    address: USER1.to_owned(),
+                },
+            )
+            .unwrap();
+        assert_eq!(balance.balance, Uint128::new(800));
+
+        // the proceeds go to the admin instead of staying in the contract
+        let balance: BalanceResponse = app
+            .wrap()
+            .query_wasm_smart(
+                token_addr.clone(),
+                &Cw20QueryMsg::Balance {
+                    address: ADMIN.to_owned(),
+                },
+            )
+            .unwrap();
+        assert_eq!(balance.balance, Uint128::new(200));
+
+        let balance: BalanceResponse = app
+            .wrap()
+            .query_wasm_smart(
+                token_addr,
+                &Cw20QueryMsg::Balance {
+                    address: contract_addr.to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(balance.balance, Uint128::zero());
+
+        // USER1 received both NFTs
+        let config: Config = app
+            .wrap()
+            .query_wasm_smart(contract_addr, &QueryMsg::Config {})
+            .unwrap();
+        assert_eq!(config.total_tokens, 2);
+
+        let user1_nfts: cw721::TokensResponse = app
+            .wrap()
+            .query_wasm_smart(
+                config.nft_contract,
+                &cw721_base::QueryMsg::Tokens::<Empty> {
+                    owner: USER1.to_string(),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(user1_nfts.tokens.len(), 2);
+    }
+
+    #[test]
+    fn mint_cooldown() {
+        let mut app = App::default();
+        let challenge_id = app.store_code(challenge_code());
+        let cw_721_id = app.store_code(cw721_code());
+
+        // Init challenge with a one minute cooldown
+        let challenge_inst = InstantiateMsg {
+            whitelisted_users: vec![USER1.to_owned()],
+            mint_cooldown: Some(60),
+            ..default_instantiate_msg(cw_721_id)
+        };
+
+        let contract_addr = app
+            .instantiate_contract(
+                challenge_id,
+                Addr::unchecked(ADMIN),
+                &challenge_inst,
+                &[],
+                "test",
+                None,
+            )
+            .unwrap();
+
+        app.execute_contract(
+            Addr::unchecked(USER1),
+            contract_addr.clone(),
+            &ExecuteMsg::Mint {},
+            &[],
+        )
+        .unwrap();
+
+        // second mint within the cooldown fails
+        app.execute_contract(
+            Addr::unchecked(USER1),
+            contract_addr.clone(),
+            &ExecuteMsg::Mint {},
+            &[],
+        )
+        .unwrap_err();
+
+        let next_mint: Timestamp = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr.clone(),
+                &QueryMsg::NextMint {
+                    user: USER1.to_owned(),
+                },
+            )
+            .unwrap();
+        assert_eq!(next_mint, app.block_info().time.plus_seconds(60));
+
+        // fast forward past the cooldown
+        app.update_block(|block| {
+            block.time = block.time.plus_seconds(60);
+        });
+
+        app.execute_contract(
+            Addr::unchecked(USER1),
+            contract_addr.clone(),
+            &ExecuteMsg::Mint {},
+            &[],
+        )
+        .unwrap();
+
+        let config: Config = app
+            .wrap()
+            .query_wasm_smart(contract_addr, &QueryMsg::Config {})
+            .unwrap();
+        assert_eq!(config.total_tokens, 2);
+    }
+
+    #[test]
+    fn collection_metadata() {
+        let mut app = App::default();
+        let challenge_id = app.store_code(challenge_code());
+        let cw_721_id = app.store_code(cw721_code());
+
+        let mut challenge_inst = InstantiateMsg {
+            collection_name: "".to_owned(),
+            collection_symbol: "OAK".to_owned(),
+            whitelisted_users: vec![USER1.to_owned()],
+            ..default_instantiate_msg(cw_721_id)
+        };
+
+        // empty collection name fails
+        app.instantiate_contract(
+            challenge_id,
+            Addr::unchecked(ADMIN),
+            &challenge_inst,
+            &[],
+            "test",
+            None,
+        )
+        .unwrap_err();
+
+        challenge_inst.collection_name = "Oak Collection".to_owned();
+        let contract_addr = app
+            .instantiate_contract(
+                challenge_id,
+                Addr::unchecked(ADMIN),
+                &challenge_inst,
+                &[],
+                "test",
+                None,
+            )
+            .unwrap();
+
+        let config: Config = app
+            .wrap()
+            .query_wasm_smart(contract_addr, &QueryMsg::Config {})
+            .unwrap();
+
+        // the child collection uses the provided metadata
+        let info: cw721::ContractInfoResponse = app
+            .wrap()
+            .query_wasm_smart(
+                config.nft_contract,
+                &cw721_base::QueryMsg::ContractInfo::<Empty> {},
+            )
+            .unwrap();
+        assert_eq!(info.name, "Oak Collection");
+        assert_eq!(info.symbol, "OAK");
+    }
+
+    #[test]
+    fn zero_batch_mint_rejected() {
+        let (mut app, contract_addr) = proper_instantiate();
+
+        app.execute_contract(
+            Addr::unchecked(USER1),
+            contract_addr.clone(),
+            &ExecuteMsg::BatchMint { amount: 0 },
+            &[],
+        )
+        .unwrap_err();
+
+        let config: Config = app
+            .wrap()
+            .query_wasm_smart(contract_addr, &QueryMsg::Config {})
+            .unwrap();
+        assert_eq!(config.total_tokens, 0);
+    }
+
+    #[test]
+    fn burn_and_remint() {
+        let (mut app, contract_addr) = proper_instantiate();
+
+        let config: Config = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::Config {})
+            .unwrap();
+
+        app.execute_contract(
+            Addr::unchecked(USER1),
+            contract_addr.clone(),
+            &ExecuteMsg::BatchMint { amount: 3 },
+            &[],
+        )
+        .unwrap();
+
+        // contract must be approved to burn the token
+        app.execute_contract(
+            Addr::unchecked(USER1),
+            config.nft_contract.clone(),
+            &cw721_base::ExecuteMsg::Approve::<Empty, Empty> {
+                spender: contract_addr.to_string(),
+                token_id: "0".to_string(),
+                expires: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+        // only the owner can remint
+        app.execute_contract(
+            Addr::unchecked(USER2),
+            contract_addr.clone(),
+            &ExecuteMsg::BurnAndRemint {
+                token_id: "0".to_string(),
+                token_uri: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+
+        // remint works even though the user is at the mint limit
+        app.execute_contract(
+            Addr::unchecked(USER1),
+            contract_addr.clone(),
+            &ExecuteMsg::BurnAndRemint {
+                token_id: "0".to_string(),
+                token_uri: Some("ipfs://evolved".to_string()),
+            },
+            &[],
+        )
+        .unwrap();
+
+        // old token is burned
+        app.wrap()
+            .query_wasm_smart::<cw721::OwnerOfResponse>(
+                config.nft_contract.clone(),
+                &cw721_base::QueryMsg::OwnerOf::<Empty> {
+                    token_id: "0".to_string(),
+                    include_expired: None,
+                },
+            )
+            .unwrap_err();
+
+        // new token is owned by the user
+        let nft: cw721::AllNftInfoResponse<Empty> = app
+            .wrap()
+            .query_wasm_smart(
+                config.nft_contract,
+                &cw721_base::QueryMsg::AllNftInfo::<Empty> {
+                    token_id: "3".to_string(),
+                    include_expired: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(nft.access.owner, USER1);
+        assert_eq!(nft.info.token_uri, Some("ipfs://evolved".to_string()));
+
+        let lineage: Option<String> = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr,
+                &QueryMsg::TokenLineage {
+                    token_id: "3".to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(lineage, Some("0".to_string()));
+    }
+
+    #[test]
+    fn batch_mint_is_atomic() {
+        let mut app = App::default();
+        let challenge_id = app.store_code(challenge_code());
+        let cw_721_id = app.store_code(failing_cw721_code());
+
+        let challenge_inst = InstantiateMsg {
+            whitelisted_users: vec![USER1.to_owned()],
+            mint_cooldown: Some(60),
+            ..default_instantiate_msg(cw_721_id)
+        };
+        let contract_addr = app
+            .instantiate_contract(
+                challenge_id,
+                Addr::unchecked(ADMIN),
+                &challenge_inst,
+                &[],
+                "test",
+                None,
+            )
+            .unwrap();
+
+        // second mint of the batch fails
+        app.execute_contract(
+            Addr::unchecked(USER1),
+            contract_addr.clone(),
+            &ExecuteMsg::BatchMint { amount: 3 },
+            &[],
+        )
+        .unwrap_err();
+
+        // no counters advanced
+        let config: Config = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::Config {})
+            .unwrap();
+        assert_eq!(config.total_tokens, 0);
+
+        let next_mint: Timestamp = app
+            .wrap()
+            .query_wasm_smart(
+                contract_addr,
+                &QueryMsg::NextMint {
+                    user: USER1.to_string(),
+                },
+            )
+            .unwrap();
+        assert_eq!(next_mint, app.block_info().time);
+
+        // the first mint was rolled back too
+        let tokens: cw721::TokensResponse = app
+            .wrap()
+            .query_wasm_smart(
+                config.nft_contract,
+                &cw721_base::QueryMsg::Tokens::<Empty> {
+                    owner: USER1.to_string(),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap();
+        assert!(tokens.tokens.is_empty());
+    }
+
+    #[test]
+    fn reserved_token_ids() {
+        let mut app = App::default();
+        let challenge_id = app.store_code(challenge_code());
+        let cw_721_id = app.store_code(cw721_code());
+
+        let challenge_inst = InstantiateMsg {
+            whitelisted_users: vec![USER1.to_owned()],
+            reserved_ids: Some(ReservedRange { start: 0, end: 99 }),
+            ..default_instantiate_msg(cw_721_id)
+        };
+        let contract_addr = app
+            .instantiate_contract(
+                challenge_id,
+                Addr::unchecked(ADMIN),
+                &challenge_inst,
+                &[],
+                "test",
+                None,
+            )
+            .unwrap();
+
+        // public mint skips the reserved window
+        let res = app
+            .execute_contract(
+                Addr::unchecked(USER1),
+                contract_addr.clone(),
+                &ExecuteMsg::Mint {},
+                &[],
+            )
+            .unwrap();
+        let event = res.events.iter().find(|ev| ev.ty == "wasm").unwrap();
+        let token_id = event
+            .attributes
+            .iter()
+            .find(|attr| attr.key == "token_id")
+            .unwrap();
+        assert_eq!(token_id.value, "100");
+
+        // only admin can mint reserved ids
+        app.execute_contract(
+            Addr::unchecked(USER1),
+            contract_addr.clone(),
+            &ExecuteMsg::AdminMint {
+                token_id: 5,
+                recipient: USER1.to_string(),
+            },
+            &[],
+        )
+        .unwrap_err();
+
+        // ids outside the reserve cannot be admin minted
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            contract_addr.clone(),
+            &ExecuteMsg::AdminMint {
+                token_id: 150,
+                recipient: ADMIN.to_string(),
+            },
+            &[],
+        )
+        .unwrap_err();
+
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            contract_addr.clone(),
+            &ExecuteMsg::AdminMint {
+                token_id: 5,
+                recipient: ADMIN.to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+
+        let config: Config = app
+            .wrap()
+            .query_wasm_smart(contract_addr, &QueryMsg::Config {})
+            .unwrap();
+        let owner: cw721::OwnerOfResponse = app
+            .wrap()
+            .query_wasm_smart(
+                config.nft_contract,
+                &cw721_base::QueryMsg::OwnerOf::<Empty> {
+                    token_id: "5".to_string(),
+                    include_expired: None,
+                },
+            )
+            .unwrap();
+        assert_eq!(owner.owner, ADMIN);
+        assert_eq!(config.total_tokens, 2);
+    }
+
+    #[test]
+    #[cfg(feature = "fixed")]
+    fn transfer_does_not_reset_mint_limit() {
+        let (mut app, contract_addr, dummy_addr) = proper_instantiate_w_dummy();
+
+        let config: Config = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::Config {})
+            .unwrap();
+
+        app.execute_contract(
+            Addr::unchecked(USER1),
+            contract_addr.clone(),
+            &ExecuteMsg::BatchMint { amount: 3 },
+            &[],
+        )
+        .unwrap();
+
+        // USER1 moves an NFT away
+        app.execute_contract(
+            Addr::unchecked(USER1),
+            config.nft_contract,
+            &cw721_base::ExecuteMsg::TransferNft::<Empty, Empty> {
+                recipient: dummy_addr.to_string(),
+                token_id: "0".to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+
+        // the transferred NFT still counts against the limit
+        app.execute_contract(
+            Addr::unchecked(USER1),
+            contract_addr.clone(),
+            &ExecuteMsg::Mint {},
+            &[],
+        )
+        .unwrap_err();
+
+        let config: Config = app
+            .wrap()
+            .query_wasm_smart(contract_addr, &QueryMsg::Config {})
+            .unwrap();
+        assert_eq!(config.total_tokens, 3);
+    }
+
+    #[test]
+    fn query_snapshots() {
+        let (mut app, contract_addr) = proper_instantiate();
+
+        app.execute_contract(
+            Addr::unchecked(USER1),
+            contract_addr.clone(),
+            &ExecuteMsg::Mint {},
+            &[],
+        )
+        .unwrap();
+
+        let config: Config = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::Config {})
+            .unwrap();
+        assert_json_snapshot!("config", config);
+
+        let whitelist: Whitelist = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::Whitelist {})
+            .unwrap();
+        assert_json_snapshot!("whitelist", whitelist);
+
+        let msg = QueryMsg::NextMint {
+            user: USER1.to_owned(),
+        };
+        let next_mint: Timestamp = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &msg)
+            .unwrap();
+        assert_json_snapshot!("next_mint", next_mint);
+
+        let msg = QueryMsg::TokenLineage {
+            token_id: "0".to_owned(),
+        };
+        let lineage: Option<String> = app.wrap().query_wasm_smart(contract_addr, &msg).unwrap();
+        assert_json_snapshot!("token_lineage", lineage);
+    }
+
+    #[test]
+    fn has_reached_limit() {
+        let (mut app, contract_addr) = proper_instantiate();
+
+        let msg = QueryMsg::HasReachedLimit {
+            user: USER1.to_owned(),
+        };
+        let reached: bool = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &msg)
+            .unwrap();
+        assert!(!reached);
+
+        app.execute_contract(
+            Addr::unchecked(USER1),
+            contract_addr.clone(),
+            &ExecuteMsg::BatchMint { amount: 3 },
+            &[],
+        )
+        .unwrap();
+
+        let reached: bool = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &msg)
+            .unwrap();
+        assert!(reached);
+
+        // non-whitelisted users can never mint
+        let msg = QueryMsg::HasReachedLimit {
+            user: "outsider".to_owned(),
+        };
+        let reached: bool = app.wrap().query_wasm_smart(contract_addr, &msg).unwrap();
+        assert!(reached);
+    }
+
+    #[test]
+    fn batch_mint_overflow_rejected() {
+        let (mut app, contract_addr) = proper_instantiate();
+
+        app.execute_contract(
+            Addr::unchecked(USER1),
+            contract_addr.clone(),
+            &ExecuteMsg::BatchMint { amount: 1 },
+            &[],
+        )
+        .unwrap();
+
+        // minted + amount would wrap past u64::MAX
+        app.execute_contract(
+            Addr::unchecked(USER1),
+            contract_addr.clone(),
+            &ExecuteMsg::BatchMint { amount: u64::MAX },
+            &[],
+        )
+        .unwrap_err();
+
+        let config: Config = app
+            .wrap()
+            .query_wasm_smart(contract_addr, &QueryMsg::Config {})
+            .unwrap();
+        assert_eq!(config.total_tokens, 1);
+    }
+
+    #[test]
+    fn add_to_whitelist() {
+        let mut app = App::default();
+        let challenge_id = app.store_code(challenge_code());
+        let cw_721_id = app.store_code(cw721_code());
+
+        let challenge_inst = InstantiateMsg {
+            whitelisted_users: vec![USER1.to_owned()],
+            max_whitelist_size: Some(200),
+            ..default_instantiate_msg(cw_721_id)
+        };
+        let contract_addr = app
+            .instantiate_contract(
+                challenge_id,
+                Addr::unchecked(ADMIN),
+                &challenge_inst,
+                &[],
+                "test",
+                None,
+            )
+            .unwrap();
+
+        // the initial whitelist is deduplicated and capped as well
+        let msg = InstantiateMsg {
+            whitelisted_users: vec![USER1.to_owned(), USER2.to_owned(), USER1.to_owned()],
+            max_whitelist_size: Some(2),
+            ..challenge_inst.clone()
+        };
+        let deduped_addr = app
+            .instantiate_contract(
+                challenge_id,
+                Addr::unchecked(ADMIN),
+                &msg,
+                &[],
+                "test",
+                None,
+            )
+            .unwrap();
+        let whitelist: Whitelist = app
+            .wrap()
+            .query_wasm_smart(deduped_addr, &QueryMsg::Whitelist {})
+            .unwrap();
+        assert_eq!(whitelist.users, vec![USER1.to_owned(), USER2.to_owned()]);
+
+        let msg = InstantiateMsg {
+            whitelisted_users: vec![USER1.to_owned(), USER2.to_owned(), USER3.to_owned()],
+            ..msg
+        };
+        app.instantiate_contract(
+            challenge_id,
+            Addr::unchecked(ADMIN),
+            &msg,
+            &[],
+            "test",
+            None,
+        )
+        .unwrap_err();
+
+        // repeated and already whitelisted users are skipped
+        let mut users: Vec<String> = (0..150).map(|i| format!("batch{i}")).collect();
+        users.extend((0..10).map(|i| format!("batch{i}")));
+        users.push(USER1.to_owned());
+
+        let msg = ExecuteMsg::AddToWhitelist { users };
+        app.execute_contract(Addr::unchecked(USER1), contract_addr.clone(), &msg, &[])
+            .unwrap_err();
+        app.execute_contract(Addr::unchecked(ADMIN), contract_addr.clone(), &msg, &[])
+            .unwrap();
+
+        let whitelist: Whitelist = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::Whitelist {})
+            .unwrap();
+        assert_eq!(whitelist.users.len(), 151);
+        let unique: std::collections::HashSet<_> = whitelist.users.iter().collect();
+        assert_eq!(unique.len(), whitelist.users.len());
+
+        // the batch is rejected as a whole once it would exceed the maximum
+        let users = (150..200).map(|i| format!("batch{i}")).collect();
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            contract_addr.clone(),
+            &ExecuteMsg::AddToWhitelist { users },
+            &[],
+        )
+        .unwrap_err();
+
+        let whitelist: Whitelist = app
+            .wrap()
+            .query_wasm_smart(contract_addr, &QueryMsg::Whitelist {})
+            .unwrap();
+        assert_eq!(whitelist.users.len(), 151);
+    }
+
+    #[test]
+    fn supply_info() {
+        let (app, contract_addr) = proper_instantiate();
+
+        // uncapped collections have no remaining supply
+        let supply: SupplyInfoResponse = app
+            .wrap()
+            .query_wasm_smart(contract_addr, &QueryMsg::SupplyInfo {})
+            .unwrap();
+        assert_eq!(
+            supply,
+            SupplyInfoResponse {
+                total_minted: 0,
+                total_tokens: 0,
+                max_supply: None,
+                remaining: None,
+            }
+        );
+
+        let mut app = App::default();
+        let challenge_id = app.store_code(challenge_code());
+        let cw_721_id = app.store_code(cw721_code());
+
+        let challenge_inst = InstantiateMsg {
+            whitelisted_users: vec![USER1.to_owned(), USER2.to_owned()],
+            max_supply: Some(3),
+            ..default_instantiate_msg(cw_721_id)
+        };
+        let contract_addr = app
+            .instantiate_contract(
+                challenge_id,
+                Addr::unchecked(ADMIN),
+                &challenge_inst,
+                &[],
+                "test",
+                None,
+            )
+            .unwrap();
+
+        app.execute_contract(
+            Addr::unchecked(USER1),
+            contract_addr.clone(),
+            &ExecuteMsg::BatchMint { amount: 2 },
+            &[],
+        )
+        .unwrap();
+
+        let supply: SupplyInfoResponse = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::SupplyInfo {})
+            .unwrap();
+        assert_eq!(
+            supply,
+            SupplyInfoResponse {
+                total_minted: 2,
+                total_tokens: 2,
+                max_supply: Some(3),
+                remaining: Some(1),
+            }
+        );
+
+        // mints beyond the max supply are rejected
+        app.execute_contract(
+            Addr::unchecked(USER2),
+            contract_addr.clone(),
+            &ExecuteMsg::BatchMint { amount: 2 },
+            &[],
+        )
+        .unwrap_err();
+
+        app.execute_contract(
+            Addr::unchecked(USER2),
+            contract_addr.clone(),
+            &ExecuteMsg::Mint {},
+            &[],
+        )
+        .unwrap();
+
+        let supply: SupplyInfoResponse = app
+            .wrap()
+            .query_wasm_smart(contract_addr, &QueryMsg::SupplyInfo {})
+            .unwrap();
+        assert_eq!(supply.total_minted, 3);
+        assert_eq!(supply.remaining, Some(0));
+    }
+
+    #[test]
+    fn remint_outside_max_supply() {
+        let mut app = App::default();
+        let challenge_id = app.store_code(challenge_code());
+        let cw_721_id = app.store_code(cw721_code());
+
+        let challenge_inst = InstantiateMsg {
+            // high enough that only the max supply limits USER2's last mint
+            mint_per_user: 10,
+            whitelisted_users: vec![USER1.to_owned(), USER2.to_owned()],
+            max_supply: Some(10),
+            ..default_instantiate_msg(cw_721_id)
+        };
+        let contract_addr = app
+            .instantiate_contract(
+                challenge_id,
+                Addr::unchecked(ADMIN),
+                &challenge_inst,
+                &[],
+                "test",
+                None,
+            )
+            .unwrap();
+
+        let config: Config = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::Config {})
+            .unwrap();
+
+        app.execute_contract(
+            Addr::unchecked(USER1),
+            contract_addr.clone(),
+            &ExecuteMsg::BatchMint { amount: 5 },
+            &[],
+        )
+        .unwrap();
+        app.execute_contract(
+            Addr::unchecked(USER2),
+            contract_addr.clone(),
+            &ExecuteMsg::BatchMint { amount: 4 },
+            &[],
+        )
+        .unwrap();
+
+        app.execute_contract(
+            Addr::unchecked(USER1),
+            config.nft_contract,
+            &cw721_base::ExecuteMsg::Approve::<Empty, Empty> {
+                spender: contract_addr.to_string(),
+                token_id: "0".to_string(),
+                expires: None,
+            },
+            &[],
+        )
+        .unwrap();
+        app.execute_contract(
+            Addr::unchecked(USER1),
+            contract_addr.clone(),
+            &ExecuteMsg::BurnAndRemint {
+                token_id: "0".to_string(),
+                token_uri: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+        // the remint leaves the last token of the supply to mint
+        let supply: SupplyInfoResponse = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::SupplyInfo {})
+            .unwrap();
+        assert_eq!(
+            supply,
+            SupplyInfoResponse {
+                total_minted: 9,
+                total_tokens: 10,
+                max_supply: Some(10),
+                remaining: Some(1),
+            }
+        );
+
+        app.execute_contract(
+            Addr::unchecked(USER2),
+            contract_addr.clone(),
+            &ExecuteMsg::Mint {},
+            &[],
+        )
+        .unwrap();
+
+        let err = app
+            .execute_contract(
+                Addr::unchecked(USER2),
+                contract_addr,
+                &ExecuteMsg::Mint {},
+                &[],
+            )
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ContractError>(),
+            Some(ContractError::MaxSupplyReached { max: 10 })
+        ));
+    }
+
+    #[test]
+    fn collection_info() {
+        let (app, contract_addr) = proper_instantiate();
+
+        let config: Config = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::Config {})
+            .unwrap();
+
+        let info: CollectionInfoResponse = app
+            .wrap()
+            .query_wasm_smart(contract_addr.clone(), &QueryMsg::CollectionInfo {})
+            .unwrap();
+        assert_eq!(
+            info,
+            CollectionInfoResponse {
+                nft_contract: config.nft_contract,
+                minter: Some(contract_addr.to_string()),
+                admin: None,
+            }
+        );
+    }
+
+    #[test]
+    fn can_mint() {
+        fn can_mint(app: &App, contract_addr: &Addr, user: &str) -> CanMintResponse {
+            app.wrap()
+                .query_wasm_smart(
+                    contract_addr,
+                    &QueryMsg::CanMint {
+                        user: user.to_string(),
+                    },
+                )
+                .unwrap()
+        }
+
+        fn rejected(err: ContractError) -> CanMintResponse {
+            CanMintResponse {
+                allowed: false,
+                reason: Some(err.to_string()),
+            }
+        }
+
+        let mut app = App::default();
+        let challenge_id = app.store_code(challenge_code());
+        let cw_721_id = app.store_code(cw721_code());
+
+        let challenge_inst = InstantiateMsg {
+            mint_per_user: 1,
+            mint_cooldown: Some(60),
+            max_supply: Some(2),
+            ..default_instantiate_msg(cw_721_id)
+        };
+        let contract_addr = app
+            .instantiate_contract(
+                challenge_id,
+                Addr::unchecked(ADMIN),
+                &challenge_inst,
+                &[],
+                "test",
+                None,
+            )
+            .unwrap();
+
+        assert_eq!(
+            can_mint(&app, &contract_addr, "stranger"),
+            rejected(ContractError::NotWhitelisted {})
+        );
+        assert_eq!(
+            can_mint(&app, &contract_addr, USER1),
+            CanMintResponse {
+                allowed: true,
+                reason: None,
+            }
+        );
+
+        app.execute_contract(
+            Addr::unchecked(USER1),
+            contract_addr.clone(),
+            &ExecuteMsg::Mint {},
+            &[],
+        )
+        .unwrap();
+
+        let next_mint = app.block_info().time.plus_seconds(60);
+        assert_eq!(
+            can_mint(&app, &contract_addr, USER1),
+            rejected(ContractError::MintCooldown { next_mint })
+        );
+
+        app.update_block(|block| {
+            block.time = block.time.plus_seconds(60);
+        });
+        assert_eq!(
+            can_mint(&app, &contract_addr, USER1),
+            rejected(ContractError::MaxLimitExceeded {})
+        );
+
+        app.execute_contract(
+            Addr::unchecked(USER2),
+            contract_addr.clone(),
+            &ExecuteMsg::Mint {},
+            &[],
+        )
+        .unwrap();
+        assert_eq!(
+            can_mint(&app, &contract_addr, USER3),
+            rejected(ContractError::MaxSupplyReached { max: 2 })
+        );
+
+        // paid collections only mint through the cw20 hook
+        let (app, contract_addr, _) = paid_instantiate(Uint128::new(10));
+        assert_eq!(
+            can_mint(&app, &contract_addr, USER1),
+            rejected(ContractError::PaymentRequired {})
+        );
+    }
 }
@@ -1,19 +1,103 @@
 use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Uint128};
+use cw20::Cw20ReceiveMsg;
+
+use crate::state::ReservedRange;
 
 #[cw_serde]
 pub struct InstantiateMsg {
     pub cw721_code_id: u64,
+    pub collection_name: String,
+    pub collection_symbol: String,
     pub mint_per_user: u64,
     pub whitelisted_users: Vec<String>,
+    pub cw20_price: Option<Cw20PriceMsg>,
+    pub mint_cooldown: Option<u64>,
+    pub reserved_ids: Option<ReservedRange>,
+    pub max_whitelist_size: Option<u64>,
+    pub max_supply: Option<u64>,
+}
+
+#[cw_serde]
+pub struct Cw20PriceMsg {
+    pub token: String,
+    pub amount: Uint128,
 }
 
 #[cw_serde]
 pub enum ExecuteMsg {
     Mint {},
+    BatchMint {
+        amount: u64,
+    },
+    Receive(Cw20ReceiveMsg),
+    /// Burn an owned NFT and mint its successor, the contract must be approved for the token
+    BurnAndRemint {
+        token_id: String,
+        token_uri: Option<String>,
+    },
+    /// Mint a reserved token id, admin only
+    AdminMint {
+        token_id: u64,
+        recipient: String,
+    },
+    /// Whitelist users, admin only. Already whitelisted users are skipped
+    AddToWhitelist {
+        users: Vec<String>,
+    },
+}
+
+/// Hook messages accepted through `ExecuteMsg::Receive`
+#[cw_serde]
+pub enum ReceiveMsg {
+    Mint {},
+    BatchMint { amount: u64 },
 }
 
 #[cw_serde]
 pub enum QueryMsg {
     Config {},
     Whitelist {},
+    NextMint {
+        user: String,
+    },
+    TokenLineage {
+        token_id: String,
+    },
+    HasReachedLimit {
+        user: String,
+    },
+    SupplyInfo {},
+    CollectionInfo {},
+    /// Dry-runs the checks of `Mint` for the user
+    CanMint {
+        user: String,
+    },
+}
+
+#[cw_serde]
+pub struct SupplyInfoResponse {
+    /// Tokens minted so far, counted against the max supply
+    pub total_minted: u128,
+    /// Tokens minted so far, remints included
+    pub total_tokens: u128,
+    pub max_supply: Option<u64>,
+    /// Tokens left to mint, `None` when the supply is uncapped
+    pub remaining: Option<u128>,
+}
+
+#[cw_serde]
+pub struct CollectionInfoResponse {
+    pub nft_contract: Addr,
+    /// Minter configured on the cw721, expected to be this contract
+    pub minter: Option<String>,
+    /// Admin able to migrate the cw721
+    pub admin: Option<String>,
+}
+
+#[cw_serde]
+pub struct CanMintResponse {
+    pub allowed: bool,
+    /// Error `Mint` would fail with, `None` when allowed
+    pub reason: Option<String>,
 }
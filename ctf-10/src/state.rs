@@ -1,16 +1,52 @@
 use cosmwasm_schema::cw_serde;
 
-use cosmwasm_std::Addr;
-use cw_storage_plus::Item;
+use cosmwasm_std::{Addr, StdResult, Storage, Timestamp, Uint128, Uint64};
+use cw_storage_plus::{Item, Map};
 
 #[cw_serde]
 pub struct Config {
+    /// Admin allowed to mint reserved token ids
+    pub admin: Addr,
     /// NFT contract address
     pub nft_contract: Addr,
     /// Mint per user
     pub mint_per_user: u64,
-    /// Total minted tokens
+    /// Total minted tokens, remints included
     pub total_tokens: u128,
+    /// Tokens counted against the max supply, remints excluded
+    pub total_minted: u128,
+    /// Price per NFT when paying with a cw20 token
+    pub cw20_price: Option<Cw20Price>,
+    /// Seconds a user must wait between mints
+    pub mint_cooldown: Option<u64>,
+    /// Token ids only the admin can mint
+    pub reserved_ids: Option<ReservedRange>,
+    /// Maximum number of whitelisted users
+    pub max_whitelist_size: Option<u64>,
+    /// Cap on minted tokens, remints replace a burned token and are not capped
+    pub max_supply: Option<u64>,
+}
+
+#[cw_serde]
+pub struct ReservedRange {
+    /// First reserved token id
+    pub start: u64,
+    /// Last reserved token id, inclusive
+    pub end: u64,
+}
+
+impl ReservedRange {
+    pub fn contains(&self, token_id: u64) -> bool {
+        self.start <= token_id && token_id <= self.end
+    }
+}
+
+#[cw_serde]
+pub struct Cw20Price {
+    /// cw20 token accepted as payment
+    pub token: Addr,
+    /// Amount charged per minted NFT
+    pub amount: Uint128,
 }
 
 #[cw_serde]
@@ -21,3 +57,25 @@ pub struct Whitelist {
 
 pub const CONFIG: Item<Config> = Item::new("config");
 pub const WHITELIST: Item<Whitelist> = Item::new("whitelist");
+pub const LAST_MINT: Map<&Addr, Timestamp> = Map::new("last_mint");
+/// Tokens ever minted to a user, only tracked by the `fixed` build
+pub const MINTED: Map<&Addr, u64> = Map::new("minted");
+/// Next token id handed out by public mints
+pub const NEXT_TOKEN_ID: Item<u64> = Item::new("next_token_id");
+/// Maps a reminted token id to the token id it replaced
+pub const LINEAGE: Map<&str, String> = Map::new("lineage");
+
+/// Returns the next public token id, skipping over the reserved range
+pub fn next_token_id(store: &mut dyn Storage, config: &Config) -> StdResult<String> {
+    let mut token_id = NEXT_TOKEN_ID.load(store)?;
+
+    if let Some(reserved) = &config.reserved_ids {
+        if reserved.contains(token_id) {
+            token_id = Uint64::new(reserved.end).checked_add(Uint64::one())?.u64();
+        }
+    }
+
+    let next = Uint64::new(token_id).checked_add(Uint64::one())?;
+    NEXT_TOKEN_ID.save(store, &next.u64())?;
+    Ok(token_id.to_string())
+}
@@ -0,0 +1,123 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Coin, CosmosMsg, Env, IbcMsg, IbcTimeout};
+use thiserror::Error;
+
+/// Seconds until an ICS20 withdrawal times out when no timeout is given
+pub const DEFAULT_IBC_TIMEOUT: u64 = 60 * 10;
+/// Longest timeout accepted, keeps the deadline far from overflowing the block time
+pub const MAX_IBC_TIMEOUT: u64 = 60 * 60 * 24 * 7;
+
+/// Longest remote recipient accepted, generous enough for any bech32 or hex address
+const MAX_RECIPIENT_LENGTH: usize = 128;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum IbcDestinationError {
+    #[error("Invalid IBC channel {channel_id}")]
+    InvalidChannel { channel_id: String },
+
+    #[error("Invalid remote recipient {recipient}")]
+    InvalidRemoteRecipient { recipient: String },
+
+    #[error("IBC timeout must be between 1 and {max} seconds")]
+    InvalidTimeout { max: u64 },
+}
+
+/// Remote destination for withdrawals sent over ICS20
+#[cw_serde]
+pub struct IbcDestination {
+    /// Local channel the transfer is sent over, e.g. `channel-0`
+    pub channel_id: String,
+    /// Recipient address on the remote chain
+    pub recipient: String,
+    /// Seconds until the transfer times out, defaults to `DEFAULT_IBC_TIMEOUT`
+    pub timeout_seconds: Option<u64>,
+}
+
+impl IbcDestination {
+    /// Checks the destination is well formed, remote addresses can't be fully validated locally
+    pub fn validate(&self) -> Result<(), IbcDestinationError> {
+        let valid_channel = matches!(
+            self.channel_id.strip_prefix("channel-"),
+            Some(id) if !id.is_empty() && id.chars().all(|c| c.is_ascii_digit())
+        );
+        if !valid_channel {
+            return Err(IbcDestinationError::InvalidChannel {
+                channel_id: self.channel_id.clone(),
+            });
+        }
+
+        if self.recipient.is_empty()
+            || self.recipient.len() > MAX_RECIPIENT_LENGTH
+            || !self.recipient.chars().all(|c| c.is_ascii_alphanumeric())
+        {
+            return Err(IbcDestinationError::InvalidRemoteRecipient {
+                recipient: self.recipient.clone(),
+            });
+        }
+
+        if let Some(timeout) = self.timeout_seconds {
+            if timeout == 0 || timeout > MAX_IBC_TIMEOUT {
+                return Err(IbcDestinationError::InvalidTimeout {
+                    max: MAX_IBC_TIMEOUT,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Builds the ICS20 transfer of `amount` to the remote recipient,
+    /// the destination has to pass `validate` first
+    pub fn transfer_msg(&self, env: &Env, amount: Coin) -> CosmosMsg {
+        let timeout = self.timeout_seconds.unwrap_or(DEFAULT_IBC_TIMEOUT);
+
+        CosmosMsg::Ibc(IbcMsg::Transfer {
+            channel_id: self.channel_id.clone(),
+            to_address: self.recipient.clone(),
+            amount,
+            timeout: IbcTimeout::with_timestamp(env.block.time.plus_seconds(timeout)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::coin;
+    use cosmwasm_std::testing::mock_env;
+
+    fn destination(timeout_seconds: Option<u64>) -> IbcDestination {
+        IbcDestination {
+            channel_id: "channel-0".to_string(),
+            recipient: "osmo1recipient".to_string(),
+            timeout_seconds,
+        }
+    }
+
+    #[test]
+    fn timeout_bounds() {
+        for timeout_seconds in [Some(0), Some(MAX_IBC_TIMEOUT + 1), Some(u64::MAX)] {
+            assert_eq!(
+                destination(timeout_seconds).validate(),
+                Err(IbcDestinationError::InvalidTimeout {
+                    max: MAX_IBC_TIMEOUT
+                })
+            );
+        }
+
+        for timeout_seconds in [None, Some(1), Some(MAX_IBC_TIMEOUT)] {
+            assert_eq!(destination(timeout_seconds).validate(), Ok(()));
+        }
+
+        // the longest accepted timeout still builds a transfer
+        let env = mock_env();
+        let msg = destination(Some(MAX_IBC_TIMEOUT)).transfer_msg(&env, coin(100, "uawesome"));
+        match msg {
+            CosmosMsg::Ibc(IbcMsg::Transfer { timeout, .. }) => assert_eq!(
+                timeout.timestamp(),
+                Some(env.block.time.plus_seconds(MAX_IBC_TIMEOUT))
+            ),
+            _ => panic!("expected an ICS20 transfer"),
+        }
+    }
+}
@@ -0,0 +1,7 @@
+//! Types and helpers shared by the challenge contracts
+#[cfg(feature = "ibc")]
+pub mod ibc;
+pub mod msg;
+pub mod pause;
+pub mod payment;
+pub mod penalty;
@@ -0,0 +1,13 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::Uint128;
+
+/// Denom and timing parameters clients need to interact with a contract
+#[cw_serde]
+pub struct ProtocolConfigResponse {
+    /// Native denom accepted for deposits
+    pub denom: String,
+    /// Seconds funds stay locked, `None` when the contract has no lock period
+    pub lock_period: Option<u64>,
+    /// Minimum amount accepted per deposit, `None` when any amount is accepted
+    pub minimum_deposit: Option<Uint128>,
+}
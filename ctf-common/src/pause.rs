@@ -0,0 +1,63 @@
+use cosmwasm_schema::cw_serde;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum PauseError {
+    #[error("Deposits are paused")]
+    DepositsPaused {},
+
+    #[error("Withdrawals are paused")]
+    WithdrawalsPaused {},
+}
+
+/// Independent pause flags, so inflows can be stopped while users can still exit
+#[cw_serde]
+#[derive(Default)]
+pub struct PauseState {
+    pub deposits_paused: bool,
+    pub withdrawals_paused: bool,
+}
+
+impl PauseState {
+    pub fn assert_deposits_open(&self) -> Result<(), PauseError> {
+        if self.deposits_paused {
+            return Err(PauseError::DepositsPaused {});
+        }
+        Ok(())
+    }
+
+    pub fn assert_withdrawals_open(&self) -> Result<(), PauseError> {
+        if self.withdrawals_paused {
+            return Err(PauseError::WithdrawalsPaused {});
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_are_independent() {
+        let pause = PauseState {
+            deposits_paused: true,
+            withdrawals_paused: false,
+        };
+        assert_eq!(
+            pause.assert_deposits_open(),
+            Err(PauseError::DepositsPaused {})
+        );
+        assert_eq!(pause.assert_withdrawals_open(), Ok(()));
+
+        let pause = PauseState {
+            deposits_paused: false,
+            withdrawals_paused: true,
+        };
+        assert_eq!(pause.assert_deposits_open(), Ok(()));
+        assert_eq!(
+            pause.assert_withdrawals_open(),
+            Err(PauseError::WithdrawalsPaused {})
+        );
+    }
+}
@@ -0,0 +1,100 @@
+use cosmwasm_std::{Coin, MessageInfo, Uint128};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum PaymentError {
+    #[error("No funds sent")]
+    NoFunds {},
+
+    #[error("Expected {expected} but received {received}")]
+    WrongDenom { expected: String, received: String },
+
+    #[error("Sent more than one denomination")]
+    MultipleDenoms {},
+}
+
+/// Returns the coin sent, requiring exactly one non-zero coin of any denom
+pub fn one_coin(info: &MessageInfo) -> Result<Coin, PaymentError> {
+    let coin = match info.funds.as_slice() {
+        [] => return Err(PaymentError::NoFunds {}),
+        [coin] => coin,
+        _ => return Err(PaymentError::MultipleDenoms {}),
+    };
+
+    if coin.amount.is_zero() {
+        return Err(PaymentError::NoFunds {});
+    }
+
+    Ok(coin.clone())
+}
+
+/// Returns the amount sent, requiring exactly one non-zero coin of `denom`
+pub fn must_pay_exact(info: &MessageInfo, denom: &str) -> Result<Uint128, PaymentError> {
+    let coin = one_coin(info)?;
+
+    if coin.denom != denom {
+        return Err(PaymentError::WrongDenom {
+            expected: denom.to_string(),
+            received: coin.denom,
+        });
+    }
+
+    Ok(coin.amount)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::coin;
+    use cosmwasm_std::testing::mock_info;
+
+    const DENOM: &str = "uawesome";
+
+    #[test]
+    fn exact_payment() {
+        let info = mock_info("user", &[coin(100, DENOM)]);
+        assert_eq!(must_pay_exact(&info, DENOM), Ok(Uint128::new(100)));
+    }
+
+    #[test]
+    fn no_funds() {
+        let info = mock_info("user", &[]);
+        assert_eq!(must_pay_exact(&info, DENOM), Err(PaymentError::NoFunds {}));
+
+        let info = mock_info("user", &[coin(0, DENOM)]);
+        assert_eq!(must_pay_exact(&info, DENOM), Err(PaymentError::NoFunds {}));
+    }
+
+    #[test]
+    fn wrong_denom() {
+        let info = mock_info("user", &[coin(100, "uother")]);
+        assert_eq!(
+            must_pay_exact(&info, DENOM),
+            Err(PaymentError::WrongDenom {
+                expected: DENOM.to_string(),
+                received: "uother".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn multiple_denoms() {
+        let info = mock_info("user", &[coin(100, DENOM), coin(100, "uother")]);
+        assert_eq!(
+            must_pay_exact(&info, DENOM),
+            Err(PaymentError::MultipleDenoms {})
+        );
+    }
+
+    #[test]
+    fn any_single_coin() {
+        let info = mock_info("user", &[coin(100, "uother")]);
+        assert_eq!(one_coin(&info), Ok(coin(100, "uother")));
+
+        let info = mock_info("user", &[coin(0, "uother")]);
+        assert_eq!(one_coin(&info), Err(PaymentError::NoFunds {}));
+
+        let info = mock_info("user", &[coin(100, DENOM), coin(100, "uother")]);
+        assert_eq!(one_coin(&info), Err(PaymentError::MultipleDenoms {}));
+    }
+}
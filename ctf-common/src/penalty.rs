@@ -0,0 +1,23 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Api, StdResult};
+
+/// Where funds forfeited through penalties end up
+#[cw_serde]
+pub enum PenaltyDestination {
+    /// Kept by the contract without being credited to anyone
+    Contract,
+    /// Sent to a treasury address
+    Treasury { address: Addr },
+    /// Shared among the remaining participants
+    Redistribute,
+}
+
+impl PenaltyDestination {
+    /// Checks the treasury address, other destinations are always valid
+    pub fn validate(&self, api: &dyn Api) -> StdResult<()> {
+        if let PenaltyDestination::Treasury { address } = self {
+            api.addr_validate(address.as_str())?;
+        }
+        Ok(())
+    }
+}